@@ -1,2 +1,4 @@
+#[cfg(feature = "rtree")]
 pub mod rtree;
+#[cfg(feature = "sqlite")]
 pub mod sqlite;
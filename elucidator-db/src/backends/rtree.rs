@@ -1,71 +1,99 @@
+#[cfg(feature = "sqlite")]
+use crate::backends::sqlite::SqlDatabase;
+pub use crate::database::MetadataClone;
 use crate::{
-    backends::sqlite::SqlDatabase,
-    database::{Database, DatabaseConfig, Datum, Metadata, Result},
+    database::{
+        parse_specs, widen_bounds, BoundingBox, Database, DatabaseConfig, Datum, Metadata, Result,
+    },
+    error::DatabaseError,
 };
 use rstar::{RTree, RTreeObject, AABB};
 
 use elucidator::designation::DesignationSpecification;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+/// Source of the identifiers that tie a [`DesignationHandle`] to the database that issued it
+static NEXT_DATABASE_ID: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Debug)]
 pub struct RTreeDatabase {
-    /// R*-Tree used internally
-    rtree: RTree<MetadataClone>,
+    id: u64,
     designations: HashMap<String, DesignationSpecification>,
+    /// Records sharded by designation, each behind its own lock so that writing one
+    /// designation does not block queries of another
+    shards: HashMap<String, Arc<RwLock<DesignationStore>>>,
 }
 
-pub struct RTreeConfig {
+/// A registered designation resolved once by [`RTreeDatabase::designation_handle`], so that
+/// [`RTreeDatabase::insert_with_handle`] can insert records without looking the designation
+/// up by name each time. A handle is only accepted by the database that issued it.
+#[derive(Debug, Clone)]
+pub struct DesignationHandle {
+    database_id: u64,
+    designation: String,
+    spec: DesignationSpecification,
+    store: Arc<RwLock<DesignationStore>>,
+}
+
+impl DesignationHandle {
+    pub fn designation(&self) -> &str {
+        &self.designation
+    }
+    pub fn spec(&self) -> &DesignationSpecification {
+        &self.spec
+    }
+}
+
+/// Records of a single designation
+#[derive(Debug, Default)]
+struct DesignationStore {
     /// R*-Tree used internally
-    _config: u8,
+    rtree: RTree<Record>,
 }
-#[derive(Debug, Clone, PartialEq)]
-pub struct MetadataClone {
-    pub xmin: f64,
-    pub xmax: f64,
-    pub ymin: f64,
-    pub ymax: f64,
-    pub zmin: f64,
-    pub zmax: f64,
-    pub tmin: f64,
-    pub tmax: f64,
-    pub designation: String,
-    pub buffer: Vec<u8>,
+
+/// A stored buffer and its bounding box
+#[derive(Debug)]
+struct Record {
+    envelope: AABB<[f64; 4]>,
+    buffer: Vec<u8>,
 }
 
-impl From<Metadata<'_>> for MetadataClone {
-    fn from(m: Metadata) -> Self {
-        MetadataClone {
-            xmin: m.xmin,
-            xmax: m.xmax,
-            ymin: m.ymin,
-            ymax: m.ymax,
-            zmin: m.zmin,
-            zmax: m.zmax,
-            tmin: m.tmin,
-            tmax: m.tmax,
-            designation: m.designation.to_string(),
-            buffer: m.buffer.into(),
+impl From<&Metadata<'_>> for Record {
+    fn from(m: &Metadata) -> Self {
+        Record {
+            envelope: m.envelope(),
+            buffer: m.buffer.to_vec(),
         }
     }
 }
 
-impl From<&Metadata<'_>> for MetadataClone {
-    fn from(m: &Metadata) -> Self {
-        MetadataClone {
-            xmin: m.xmin,
-            xmax: m.xmax,
-            ymin: m.ymin,
-            ymax: m.ymax,
-            zmin: m.zmin,
-            zmax: m.zmax,
-            tmin: m.tmin,
-            tmax: m.tmax,
-            designation: m.designation.to_string(),
-            buffer: m.buffer.into(),
+impl From<MetadataClone> for Record {
+    fn from(m: MetadataClone) -> Self {
+        Record {
+            envelope: m.envelope(),
+            buffer: m.buffer,
         }
     }
 }
 
+impl RTreeObject for Record {
+    type Envelope = AABB<[f64; 4]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+pub struct RTreeConfig {
+    /// R*-Tree used internally
+    _config: u8,
+}
 impl RTreeObject for &MetadataClone {
     type Envelope = AABB<[f64; 4]>;
 
@@ -88,64 +116,233 @@ impl RTreeObject for MetadataClone {
     }
 }
 
+impl RTreeDatabase {
+    /// Look up the specification registered for `designation`
+    pub fn get_designation_spec(&self, designation: &str) -> Option<&DesignationSpecification> {
+        self.designations.get(designation)
+    }
+    /// Insert a record through a shared reference, locking only the shard of its designation
+    /// so that queries of other designations can proceed concurrently. Unlike
+    /// `insert_metadata`, the designation must already be registered.
+    pub fn insert_metadata_shared(&self, datum: &Metadata) -> Result<()> {
+        let shard = self
+            .shards
+            .get(datum.designation)
+            .ok_or_else(|| DatabaseError::unregistered(datum.designation))?;
+        shard.write()?.rtree.insert(datum.into());
+        Ok(())
+    }
+    /// Insert several records through a shared reference as `insert_metadata_shared` does.
+    /// Every designation is checked before anything is inserted, so an unregistered one
+    /// leaves the database unchanged.
+    pub fn insert_n_metadata_shared(&self, data: &[Metadata]) -> Result<()> {
+        let shards = data
+            .iter()
+            .map(|datum| {
+                self.shards
+                    .get(datum.designation)
+                    .ok_or_else(|| DatabaseError::unregistered(datum.designation))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for (shard, datum) in shards.into_iter().zip(data) {
+            shard.write()?.rtree.insert(datum.into());
+        }
+        Ok(())
+    }
+    /// Resolve a registered designation into a handle for [`Self::insert_with_handle`].
+    pub fn designation_handle(&self, designation: &str) -> Result<DesignationHandle> {
+        let not_registered = || DatabaseError::unregistered(designation);
+        let spec = self
+            .designations
+            .get(designation)
+            .ok_or_else(not_registered)?;
+        let store = self.shards.get(designation).ok_or_else(not_registered)?;
+        Ok(DesignationHandle {
+            database_id: self.id,
+            designation: designation.to_string(),
+            spec: spec.clone(),
+            store: Arc::clone(store),
+        })
+    }
+    /// Insert a buffer occupying `bb` under the designation resolved by `handle`, as with
+    /// `insert_metadata_box` but without looking the designation up by name.
+    pub fn insert_with_handle(
+        &mut self,
+        handle: &DesignationHandle,
+        bb: &BoundingBox,
+        buffer: &[u8],
+    ) -> Result<()> {
+        if handle.database_id != self.id {
+            return Err(DatabaseError::SpecificationError {
+                reason: format!(
+                    "handle for designation \"{}\" was issued by a different database",
+                    handle.designation
+                ),
+            });
+        }
+        let envelope = AABB::from_corners(
+            [bb.xmin(), bb.ymin(), bb.zmin(), bb.tmin()],
+            [bb.xmax(), bb.ymax(), bb.zmax(), bb.tmax()],
+        );
+        handle.store.write()?.rtree.insert(Record {
+            envelope,
+            buffer: buffer.to_vec(),
+        });
+        Ok(())
+    }
+    /// Copy out every stored record, in no particular order.
+    pub fn get_all_metadata(&self) -> Result<Vec<MetadataClone>> {
+        let mut data = Vec::new();
+        for (designation, shard) in &self.shards {
+            let store = shard.read()?;
+            data.extend(store.rtree.iter().map(|r| {
+                let (lower, upper) = (r.envelope.lower(), r.envelope.upper());
+                MetadataClone {
+                    xmin: lower[0],
+                    xmax: upper[0],
+                    ymin: lower[1],
+                    ymax: upper[1],
+                    zmin: lower[2],
+                    zmax: upper[2],
+                    tmin: lower[3],
+                    tmax: upper[3],
+                    designation: designation.clone(),
+                    buffer: r.buffer.clone(),
+                }
+            }));
+        }
+        Ok(data)
+    }
+    /// Call `f` with each buffer of `designation` in a bounding box, widened by `epsilon`,
+    /// while holding the read lock of its shard.
+    fn for_each_blob_in_bb(
+        &self,
+        bounds: [f64; 8],
+        designation: &str,
+        epsilon: Option<f64>,
+        mut f: impl FnMut(&[u8]),
+    ) -> Result<()> {
+        let [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax] = widen_bounds(bounds, epsilon)?;
+        let Some(shard) = self.shards.get(designation) else {
+            return Ok(());
+        };
+        let bb = AABB::from_corners([xmin, ymin, zmin, tmin], [xmax, ymax, zmax, tmax]);
+        let store = shard.read()?;
+        for record in store.rtree.locate_in_envelope(&bb) {
+            f(&record.buffer);
+        }
+        Ok(())
+    }
+    /// The store for `designation`, created if this is its first record.
+    fn shard(&mut self, designation: &str) -> &RwLock<DesignationStore> {
+        self.shards.entry(designation.to_string()).or_default()
+    }
+}
+
 impl Database for RTreeDatabase {
     fn new(_: Option<&str>, _: Option<&DatabaseConfig>) -> Result<Self> {
         Ok(Self {
-            rtree: RTree::new(),
+            id: NEXT_DATABASE_ID.fetch_add(1, Ordering::Relaxed),
             designations: HashMap::new(),
+            shards: HashMap::new(),
         })
     }
+    #[cfg(not(feature = "sqlite"))]
+    fn from_path(_: &str) -> Result<Self> {
+        Err(DatabaseError::ConfigError {
+            reason: "Loading an RTreeDatabase from a file requires the sqlite feature".to_string(),
+        })
+    }
+    #[cfg(feature = "sqlite")]
     fn from_path(filename: &str) -> Result<Self> {
         let sqlite = SqlDatabase::from_path(filename)?;
         let designations = sqlite.get_designations();
-        let mds = sqlite.get_all_metadata()?;
-        let rtree = RTree::bulk_load(mds);
+        let mut records: HashMap<String, Vec<Record>> = designations
+            .keys()
+            .map(|designation| (designation.clone(), Vec::new()))
+            .collect();
+        for md in sqlite.get_all_metadata()? {
+            records
+                .entry(md.designation.clone())
+                .or_default()
+                .push(md.into());
+        }
+        let shards = records
+            .into_iter()
+            .map(|(designation, records)| {
+                let store = DesignationStore {
+                    rtree: RTree::bulk_load(records),
+                };
+                (designation, Arc::new(RwLock::new(store)))
+            })
+            .collect();
         Ok(RTreeDatabase {
-            rtree,
+            id: NEXT_DATABASE_ID.fetch_add(1, Ordering::Relaxed),
             designations,
+            shards,
         })
     }
+    #[cfg(not(feature = "sqlite"))]
+    fn save_as(&self, _: &str) -> Result<()> {
+        Err(DatabaseError::ConfigError {
+            reason: "Saving an RTreeDatabase to a file requires the sqlite feature".to_string(),
+        })
+    }
+    /// Persist to a sqlite database at `filename`, which `from_path` can reload. Coordinates are
+    /// stored in sqlite's rtree module, which keeps them at 32-bit float precision.
+    #[cfg(feature = "sqlite")]
     fn save_as(&self, filename: &str) -> Result<()> {
         let mut sqlite = SqlDatabase::new(Some(filename), None)?;
 
         for (designation, designation_spec) in self.designations.iter() {
-            sqlite.insert_spec_text(designation, &designation_spec.to_string())?;
+            sqlite.register_designation(designation, designation_spec.clone())?;
         }
-        let md_results: Result<Vec<()>, crate::error::DatabaseError> = self
-            .rtree
+        let all = self.get_all_metadata()?;
+        let mds: Vec<Metadata> = all
             .iter()
-            .map(|m| {
-                let md = Metadata {
-                    xmin: m.xmin,
-                    xmax: m.xmax,
-                    ymin: m.ymin,
-                    ymax: m.ymax,
-                    zmin: m.zmin,
-                    zmax: m.zmax,
-                    tmin: m.tmin,
-                    tmax: m.tmax,
-                    designation: &m.designation,
-                    buffer: &m.buffer,
-                };
-                sqlite.insert_metadata(&md)
+            .map(|m| Metadata {
+                xmin: m.xmin,
+                xmax: m.xmax,
+                ymin: m.ymin,
+                ymax: m.ymax,
+                zmin: m.zmin,
+                zmax: m.zmax,
+                tmin: m.tmin,
+                tmax: m.tmax,
+                designation: &m.designation,
+                buffer: &m.buffer,
             })
             .collect();
-        md_results?;
-        Ok(())
+        sqlite.insert_n_metadata(&mds)
     }
     fn insert_spec_text(&mut self, designation: &str, spec: &str) -> Result<()> {
         let designation_spec = DesignationSpecification::from_text(spec)?;
         self.designations
             .insert(designation.to_string(), designation_spec);
+        self.shard(designation);
+        Ok(())
+    }
+    fn insert_specs(&mut self, specs: &[(String, String)]) -> Result<()> {
+        for (designation, designation_spec) in parse_specs(specs, &self.designations)? {
+            self.designations
+                .insert(designation.to_string(), designation_spec);
+            self.shard(designation);
+        }
         Ok(())
     }
     fn insert_metadata(&mut self, datum: &Metadata) -> Result<()> {
-        self.rtree.insert(datum.into());
+        self.shard(datum.designation)
+            .write()?
+            .rtree
+            .insert(datum.into());
         Ok(())
     }
     fn insert_n_metadata(&mut self, data: &[Metadata]) -> Result<()> {
         for datum in data {
-            self.rtree.insert(datum.into());
+            self.shard(datum.designation)
+                .write()?
+                .rtree
+                .insert(datum.into());
         }
         Ok(())
     }
@@ -161,21 +358,16 @@ impl Database for RTreeDatabase {
         tmax: f64,
         designation: &str,
         epsilon: Option<f64>,
-    ) -> Result<Vec<Datum>> {
+    ) -> Result<Vec<Datum<'_>>> {
         let d = self.designations.get(designation).unwrap();
-        let blobs = self.get_metadata_blobs_in_bb(
-            xmin,
-            xmax,
-            ymin,
-            ymax,
-            zmin,
-            zmax,
-            tmin,
-            tmax,
+        let mut data = Vec::new();
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
             designation,
             epsilon,
+            |b| data.push(Datum::new(d, d.interpret_enum(b).unwrap())),
         )?;
-        Ok(blobs.iter().map(|b| d.interpret_enum(b).unwrap()).collect())
+        Ok(data)
     }
 
     fn get_metadata_blobs_in_bb(
@@ -190,18 +382,15 @@ impl Database for RTreeDatabase {
         tmax: f64,
         designation: &str,
         epsilon: Option<f64>,
-    ) -> Result<Vec<&Vec<u8>>> {
-        let eps = epsilon.unwrap_or(0.0);
-        let mins = [xmin - eps, ymin - eps, zmin - eps, tmin - eps];
-        let maxs = [xmax + eps, ymax + eps, zmax + eps, tmax + eps];
-
-        let bb = AABB::from_corners(mins, maxs);
-        Ok(self
-            .rtree
-            .locate_in_envelope(&bb)
-            .filter(|m| m.designation == designation)
-            .map(|m| &m.buffer)
-            .collect())
+    ) -> Result<Vec<Vec<u8>>> {
+        let mut blobs = Vec::new();
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+            |b| blobs.push(b.to_vec()),
+        )?;
+        Ok(blobs)
     }
 }
 
@@ -216,6 +405,7 @@ mod test {
     }
 
     impl TempFile {
+        #[allow(dead_code)]
         pub fn new() -> Result<Self> {
             let random_filename = random_identifier(10);
             Self::from(&random_filename)
@@ -241,7 +431,7 @@ mod test {
     fn random_identifier(size: u8) -> String {
         let mut rng = rand::thread_rng();
         (0..size)
-            .map(|_| (rng.gen_range(b'a'..=b'z') as char))
+            .map(|_| rng.gen_range(b'a'..=b'z') as char)
             .collect()
     }
 
@@ -259,6 +449,17 @@ mod test {
         }
 
         #[test]
+        #[cfg(not(feature = "sqlite"))]
+        fn save_without_sqlite_fails() {
+            let db = RTreeDatabase::new(None, None).unwrap();
+            assert!(matches!(
+                db.save_as("unused.db"),
+                Err(DatabaseError::ConfigError { .. })
+            ));
+        }
+
+        #[test]
+        #[cfg(feature = "sqlite")]
         fn from_empty_ok() {
             let tempfile = TempFile::from("temp.db").unwrap();
             let db = SqlDatabase::new(Some(&tempfile.filepath), None);
@@ -267,6 +468,81 @@ mod test {
             assert!(loaded_db.is_ok());
         }
 
+        #[test]
+        #[cfg(feature = "sqlite")]
+        fn save_and_reload_ok() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let mut db = RTreeDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u8, bar: f64[]").unwrap();
+            db.insert_spec_text("Baz", "baz: string").unwrap();
+            let buffers: Vec<Vec<u8>> = (0..4u8)
+                .map(|i| {
+                    let mut buffer = vec![i];
+                    buffer.extend(1u64.to_le_bytes());
+                    buffer.extend(DataValue::Float64Array(vec![i as f64 * 0.5]).as_buffer());
+                    buffer
+                })
+                .collect();
+            let baz = DataValue::Str("dog".to_string()).as_buffer();
+            let mut metadata: Vec<Metadata> = buffers
+                .iter()
+                .enumerate()
+                .map(|(i, buffer)| Metadata {
+                    xmin: i as f64,
+                    xmax: i as f64 + 0.5,
+                    ymin: 0.0,
+                    ymax: 1.0,
+                    zmin: -1.0,
+                    zmax: 0.0,
+                    tmin: 10.0,
+                    tmax: 20.0,
+                    designation: "Foo",
+                    buffer,
+                })
+                .collect();
+            metadata.push(Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation: "Baz",
+                buffer: &baz,
+            });
+            db.insert_n_metadata(&metadata).unwrap();
+            db.save_as(&tempfile.filepath).unwrap();
+
+            let loaded = RTreeDatabase::from_path(&tempfile.filepath).unwrap();
+            pretty_assertions::assert_eq!(loaded.designations, db.designations);
+            for (designation, xmax) in [("Foo", 2.5), ("Foo", 10.0), ("Baz", 1.0)] {
+                let query = |d: &RTreeDatabase| {
+                    let mut found: Vec<String> = d
+                        .get_metadata_in_bb(
+                            0.0,
+                            xmax,
+                            0.0,
+                            1.0,
+                            -1.0,
+                            0.0,
+                            0.0,
+                            20.0,
+                            designation,
+                            None,
+                        )
+                        .unwrap()
+                        .iter()
+                        .map(|datum| format!("{:?}", datum.iter().collect::<Vec<_>>()))
+                        .collect();
+                    found.sort();
+                    found
+                };
+                pretty_assertions::assert_eq!(query(&loaded), query(&db));
+            }
+        }
+
         #[test]
         fn insert_designation_ok() {
             let mut db = RTreeDatabase::new(None, None).unwrap();
@@ -383,6 +659,48 @@ mod test {
             pretty_assertions::assert_eq!(result, Ok(()));
         }
 
+        #[test]
+        fn space_search_ignores_time_ok() {
+            let mut db = RTreeDatabase::new(None, None).unwrap();
+            let designation = "Foo";
+            let buffers: Vec<[u8; 1]> = vec![[1], [2], [3], [4]];
+            let times = [-5.0e9, 0.0, 1.0, 3.0e7];
+            let mut metadata: Vec<Metadata> = buffers
+                .iter()
+                .zip(times.iter())
+                .map(|(buffer, t)| Metadata {
+                    xmin: 0.0,
+                    xmax: 1.0,
+                    ymin: 0.0,
+                    ymax: 1.0,
+                    zmin: 0.0,
+                    zmax: 1.0,
+                    tmin: *t,
+                    tmax: *t,
+                    designation,
+                    buffer,
+                })
+                .collect();
+            // Outside of the queried region
+            metadata.push(Metadata {
+                xmin: 5.0,
+                xmax: 6.0,
+                ..metadata[0].clone()
+            });
+            let _ = db.insert_spec_text(designation, "foo: u8");
+            let _ = db.insert_n_metadata(&metadata);
+
+            let result = db
+                .get_metadata_in_space(0.0, 1.0, 0.0, 1.0, 0.0, 1.0, designation, None)
+                .unwrap();
+            let mut found: Vec<u8> = result
+                .iter()
+                .map(|datum| datum.get_as::<u8>("foo").unwrap())
+                .collect();
+            found.sort();
+            pretty_assertions::assert_eq!(found, vec![1, 2, 3, 4]);
+        }
+
         #[test]
         fn bb_search_ok() {
             let mut db = RTreeDatabase::new(None, None).unwrap();
@@ -452,11 +770,12 @@ mod test {
             let result = result.unwrap();
             assert_eq!(result.len(), expected.len());
             for x in expected.iter() {
-                assert!(result.contains(x));
+                assert!(result.iter().any(|datum| datum == x));
             }
         }
 
         #[test]
+        #[cfg(feature = "sqlite")]
         fn test_save_and_recover_ok() {
             let mut db = RTreeDatabase::new(None, None).unwrap();
 
@@ -514,12 +833,150 @@ mod test {
 
             let recovered = RTreeDatabase::from_path(&tempfile.filepath).unwrap();
             pretty_assertions::assert_eq!(db.designations, recovered.designations);
-            let db_md = db.rtree.iter().collect::<Vec<&MetadataClone>>();
-            let recovered_md = recovered.rtree.iter().collect::<Vec<&MetadataClone>>();
+            let db_md = db.get_all_metadata().unwrap();
+            let recovered_md = recovered.get_all_metadata().unwrap();
             assert_eq!(db_md.len(), recovered_md.len());
             for element in db_md.iter() {
                 assert!(recovered_md.contains(element));
             }
         }
+
+        #[test]
+        fn concurrent_insert_and_query_ok() {
+            let point = |designation, buffer| Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation,
+                buffer,
+            };
+            let mut db = RTreeDatabase::new(None, None).unwrap();
+            db.insert_spec_text("A", "a: u32").unwrap();
+            db.insert_spec_text("B", "b: u32").unwrap();
+            let b_buffers: Vec<[u8; 4]> = (0..10u32).map(u32::to_le_bytes).collect();
+            for buffer in &b_buffers {
+                db.insert_metadata(&point("B", buffer)).unwrap();
+            }
+            let a_buffers: Vec<[u8; 4]> = (0..500u32).map(u32::to_le_bytes).collect();
+
+            let db = &db;
+            std::thread::scope(|s| {
+                s.spawn(|| {
+                    for buffer in &a_buffers {
+                        db.insert_metadata_shared(&point("A", buffer)).unwrap();
+                    }
+                });
+                s.spawn(|| {
+                    for _ in 0..500 {
+                        let found = db
+                            .get_metadata_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "B", None)
+                            .unwrap();
+                        assert_eq!(found.len(), 10);
+                    }
+                });
+            });
+
+            let mut found: Vec<u32> = db
+                .get_metadata_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "A", None)
+                .unwrap()
+                .iter()
+                .map(|datum| datum.get_as::<u32>("a").unwrap())
+                .collect();
+            found.sort();
+            assert_eq!(found, (0..500).collect::<Vec<u32>>());
+        }
+
+        #[test]
+        fn insert_shared_unregistered_fails() {
+            let db = RTreeDatabase::new(None, None).unwrap();
+            let md = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation: "Foo",
+                buffer: &[1],
+            };
+            assert!(matches!(
+                db.insert_metadata_shared(&md),
+                Err(DatabaseError::SpecificationError { .. })
+            ));
+        }
+
+        #[test]
+        fn insert_with_handle_ok() {
+            let mut db = RTreeDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u32").unwrap();
+            let handle = db.designation_handle("Foo").unwrap();
+            pretty_assertions::assert_eq!(handle.designation(), "Foo");
+            pretty_assertions::assert_eq!(handle.spec().to_string(), "foo: u32");
+            for i in 0..3u32 {
+                let bb = BoundingBox::from_points([i as f64; 4], [i as f64; 4]);
+                db.insert_with_handle(&handle, &bb, &i.to_le_bytes())
+                    .unwrap();
+            }
+            let bb = BoundingBox::from_points([3.0; 4], [3.0; 4]);
+            db.insert_metadata_box(&bb, "Foo", &3u32.to_le_bytes())
+                .unwrap();
+            let mut found: Vec<u32> = db
+                .get_metadata_in_bb(0.0, 3.0, 0.0, 3.0, 0.0, 3.0, 0.0, 3.0, "Foo", None)
+                .unwrap()
+                .iter()
+                .map(|datum| datum.get_as::<u32>("foo").unwrap())
+                .collect();
+            found.sort();
+            pretty_assertions::assert_eq!(found, vec![0, 1, 2, 3]);
+        }
+
+        #[test]
+        fn negative_epsilon_shrinks_box() {
+            let mut db = RTreeDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u32").unwrap();
+            for i in 0..4u32 {
+                let bb = BoundingBox::from_points([i as f64; 4], [i as f64; 4]);
+                db.insert_metadata_box(&bb, "Foo", &i.to_le_bytes())
+                    .unwrap();
+            }
+            let mut found: Vec<u32> = db
+                .get_metadata_in_bb(0.0, 3.0, 0.0, 3.0, 0.0, 3.0, 0.0, 3.0, "Foo", Some(-0.5))
+                .unwrap()
+                .iter()
+                .map(|datum| datum.get_as::<u32>("foo").unwrap())
+                .collect();
+            found.sort();
+            pretty_assertions::assert_eq!(found, vec![1, 2]);
+            assert!(matches!(
+                db.get_metadata_in_bb(0.0, 3.0, 0.0, 3.0, 0.0, 3.0, 0.0, 3.0, "Foo", Some(-2.0)),
+                Err(DatabaseError::InvalidQueryBox { .. })
+            ));
+        }
+
+        #[test]
+        fn handle_unregistered_or_foreign_fails() {
+            let mut db = RTreeDatabase::new(None, None).unwrap();
+            assert!(matches!(
+                db.designation_handle("Foo"),
+                Err(DatabaseError::SpecificationError { .. })
+            ));
+            let mut other = RTreeDatabase::new(None, None).unwrap();
+            other.insert_spec_text("Foo", "foo: u8").unwrap();
+            db.insert_spec_text("Foo", "foo: u8").unwrap();
+            let handle = other.designation_handle("Foo").unwrap();
+            let bb = BoundingBox::from_points([0.0; 4], [0.0; 4]);
+            assert!(matches!(
+                db.insert_with_handle(&handle, &bb, &[1]),
+                Err(DatabaseError::SpecificationError { .. })
+            ));
+            pretty_assertions::assert_eq!(other.get_all_metadata().unwrap().len(), 0);
+        }
     }
 }
@@ -1,20 +1,33 @@
-use std::{collections::HashMap, io::Read};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Read,
+};
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 
 use crate::{
-    backends::rtree::MetadataClone,
-    database::{Config, Database, DatabaseConfig, Datum, Metadata, Result},
+    database::{
+        parse_specs, widen_bounds, Aggregate, BoundingBox, Config, Database, DatabaseConfig, Datum,
+        Metadata, MetadataClone, Result,
+    },
     error::DatabaseError,
 };
-use elucidator::designation::DesignationSpecification;
+use elucidator::{
+    designation::DesignationSpecification,
+    member::{Dtype, Sizing},
+    value::DataValue,
+    Representable,
+};
 
 use serde::{Deserialize, Serialize};
 use serde_json;
 
 use std::fs::File;
 use std::io::Write;
-use std::sync::{Arc, Mutex};
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Mutex,
+};
 
 pub struct SqlDatabase {
     /// Active database connection
@@ -25,6 +38,30 @@ pub struct SqlDatabase {
     config: SqliteConfig,
 }
 
+/// Smallest number of matching records worth spreading over threads when decoding
+#[cfg(feature = "parallel")]
+const PARALLEL_DECODE_THRESHOLD: usize = 4096;
+
+/// Which of the rows matching a bounding box query are visited
+#[derive(Clone, Copy)]
+enum RowWindow {
+    /// Every matching row
+    All,
+    /// Rows `offset..offset + limit` in id order
+    Page { limit: usize, offset: usize },
+    /// At most `limit` rows, in whatever order sqlite finds them
+    Limit(usize),
+}
+
+/// A stored record whose buffer could not be decoded, as reported by
+/// [`SqlDatabase::verify_all`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct VerificationError {
+    pub id: i64,
+    pub designation: String,
+    pub error: DatabaseError,
+}
+
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct SqliteConfig {
     use_rtree: bool,
@@ -34,6 +71,12 @@ pub struct SqliteConfig {
     use_memory_temp_store: bool,
     threads: u32,
     cached_pages: u32,
+    #[serde(default)]
+    reject_non_finite_floats: bool,
+    #[serde(default)]
+    store_checksums: bool,
+    #[serde(default)]
+    log_statements: bool,
 }
 
 impl Config for SqliteConfig {
@@ -46,6 +89,9 @@ impl Config for SqliteConfig {
             use_memory_temp_store: false,
             threads: 0,
             cached_pages: 0,
+            reject_non_finite_floats: false,
+            store_checksums: false,
+            log_statements: false,
         }
     }
     fn from_json_file(filename: &str) -> Result<Self> {
@@ -75,6 +121,22 @@ impl SqliteConfig {
         self.synchronous_off = true;
         self.clone()
     }
+    /// Reject inserted buffers whose float members contain NaN or infinite values
+    pub fn reject_non_finite_floats(&mut self) -> Self {
+        self.reject_non_finite_floats = true;
+        self.clone()
+    }
+    /// Store a CRC-32 of each buffer alongside it and verify it whenever the buffer is queried
+    pub fn store_checksums(&mut self) -> Self {
+        self.store_checksums = true;
+        self.clone()
+    }
+    /// Log each insert and query statement with its bound parameters at debug level via the
+    /// `log` crate. Buffers are logged by length only.
+    pub fn log_statements(&mut self) -> Self {
+        self.log_statements = true;
+        self.clone()
+    }
 }
 
 impl SqlDatabase {
@@ -83,7 +145,8 @@ impl SqlDatabase {
         self.verify_version()?;
         let conn = self.conn.lock()?;
         if self.config.use_wal {
-            conn.execute("PRAGMA journal_mode = WAL", [])?;
+            // journal_mode reports the resulting mode, so it must be run as a query
+            conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
         }
         conn.execute(&format!("PRAGMA page_size = {}", self.config.page_size), [])?;
         if self.config.synchronous_off {
@@ -115,12 +178,19 @@ impl SqlDatabase {
             )",
             (), // empty list of parameters.
         )?;
+        let checksum_column = if self.config.store_checksums {
+            ",\n                crc32 INTEGER"
+        } else {
+            ""
+        };
         conn.execute(
-            "CREATE TABLE Metadata (
+            &format!(
+                "CREATE TABLE Metadata (
                 id INTEGER PRIMARY KEY,
                 designation TEXT,
-                buffer BLOB
-            )",
+                buffer BLOB{checksum_column}
+            )"
+            ),
             [],
         )?;
         conn.execute("PRAGMA optimize", [])?;
@@ -153,85 +223,87 @@ impl SqlDatabase {
         }
         Ok(())
     }
-    pub fn get_designations(&self) -> HashMap<String, DesignationSpecification> {
-        self.designations.clone()
+    /// When configured to, ensure no float member of the datum's buffer is NaN or infinite.
+    /// Buffers of unregistered designations are not checked.
+    fn check_finite(&self, datum: &Metadata) -> Result<()> {
+        if !self.config.reject_non_finite_floats {
+            return Ok(());
+        }
+        if let Some(spec) = self.designations.get(datum.designation) {
+            if let Some(member) = spec.first_non_finite_member(datum.buffer)? {
+                Err(DatabaseError::NonFiniteValue {
+                    designation: datum.designation.to_string(),
+                    member: member.to_string(),
+                })?;
+            }
+        }
+        Ok(())
     }
-    pub fn get_all_metadata(&self) -> Result<Vec<MetadataClone>> {
-        let mut data = Vec::new();
-        let conn = self.conn.lock()?;
-        let mut stmt = conn.prepare_cached(
-            "SELECT 
-                ml.id, ml.xmin, ml.xmax, ml.ymin, ml.ymax, ml.zmin, ml.zmax, ml.tmin, ml.tmax,
-                m.designation, m.buffer
-            FROM 
-                Metadata AS m
-            JOIN 
-                MetadataLocations AS ml
-            ON 
-                ml.id = m.id
-            ",
-        )?;
-        let mut rows = stmt.raw_query();
-        while let Some(row) = rows.next()? {
-            let xmin = row.get_ref(1)?.as_f64()?;
-            let xmax = row.get_ref(2)?.as_f64()?;
-            let ymin = row.get_ref(3)?.as_f64()?;
-            let ymax = row.get_ref(4)?.as_f64()?;
-            let zmin = row.get_ref(5)?.as_f64()?;
-            let zmax = row.get_ref(6)?.as_f64()?;
-            let tmin = row.get_ref(7)?.as_f64()?;
-            let tmax = row.get_ref(8)?.as_f64()?;
-            let designation = row.get_ref(9)?.as_str()?;
-            let buffer = match row.get_ref(10)? {
-                rusqlite::types::ValueRef::Blob(b) => b,
-                _ => unreachable!("We should always retrieve blobs!"),
-            };
-            data.push(MetadataClone {
-                xmin,
-                xmax,
-                ymin,
-                ymax,
-                zmin,
-                zmax,
-                tmin,
-                tmax,
-                designation: designation.to_string(),
-                buffer: buffer.into(),
-            });
+    /// When configured to, log `sql` along with the description of its bound parameters.
+    /// The description is only built if it will be logged.
+    fn log_statement(&self, sql: &str, params: impl FnOnce() -> String) {
+        if self.config.log_statements {
+            log::debug!("{sql} -- params: {}", params());
         }
-        Ok(data)
     }
-}
+    /// Insert several metadata in a single transaction, returning the id assigned to each,
+    /// in the same order as `data`.
+    pub fn insert_n_metadata_returning_ids(&mut self, data: &[Metadata]) -> Result<Vec<i64>> {
+        for datum in data {
+            self.check_finite(datum)?;
+        }
+        let mut conn = self.conn.lock()?;
+        let tx = conn.transaction()?;
+        let mut ids = Vec::with_capacity(data.len());
 
-impl Database for SqlDatabase {
-    fn new(filename: Option<&str>, config: Option<&DatabaseConfig>) -> Result<Self> {
-        let config = match config {
-            Some(dbcfg) => match &dbcfg {
-                DatabaseConfig::SqliteConfig(sqlcfg) => sqlcfg.clone(),
-                _ => Err(DatabaseError::ConfigError {
-                    reason: "Sqlite given config for incorrect backend.".to_string(),
-                })?,
-            },
-            None => SqliteConfig::new(),
-        };
-        let db = if let Some(name) = filename {
-            SqlDatabase {
-                conn: Arc::new(Mutex::new(Connection::open(name)?)),
-                designations: HashMap::new(),
-                config,
-            }
-        } else {
-            SqlDatabase {
-                conn: Arc::new(Mutex::new(Connection::open_in_memory()?)),
-                designations: HashMap::new(),
-                config,
+        for datum in data {
+            let sql = "INSERT INTO MetadataLocations (xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)";
+            self.log_statement(sql, || Self::describe_location(datum));
+            let mut stmt = tx.prepare_cached(sql)?;
+            stmt.execute([
+                datum.xmin, datum.xmax, datum.ymin, datum.ymax, datum.zmin, datum.zmax, datum.tmin,
+                datum.tmax,
+            ])?;
+            let id = tx.last_insert_rowid();
+            let sql = if self.config.store_checksums {
+                "INSERT INTO Metadata (id, designation, buffer, crc32) VALUES (?1, ?2, ?3, ?4)"
+            } else {
+                "INSERT INTO Metadata (id, designation, buffer) VALUES (?1, ?2, ?3)"
+            };
+            self.log_statement(sql, || {
+                format!(
+                    "[{id}, {:?}, <{} bytes>]",
+                    datum.designation,
+                    datum.buffer.len()
+                )
+            });
+            let mut stmt = tx.prepare_cached(sql)?;
+            if self.config.store_checksums {
+                stmt.raw_bind_parameter(4, crc32fast::hash(datum.buffer))?;
             }
-        };
-        db.initialize()?;
-        Ok(db)
+            stmt.raw_bind_parameter(1, id)?;
+            stmt.raw_bind_parameter(2, datum.designation)?;
+            stmt.raw_bind_parameter(3, datum.buffer)?;
+            stmt.raw_execute()?;
+            ids.push(id);
+        }
+
+        tx.commit()?;
+
+        Ok(ids)
     }
-    fn from_path(filename: &str) -> Result<Self> {
-        let conn = Connection::open(filename)?;
+    /// Bound coordinates of a `MetadataLocations` insert, for statement logging
+    fn describe_location(datum: &Metadata) -> String {
+        format!(
+            "{:?}",
+            [
+                datum.xmin, datum.xmax, datum.ymin, datum.ymax, datum.zmin, datum.zmax, datum.tmin,
+                datum.tmax,
+            ]
+        )
+    }
+    /// Wrap an open connection to an existing database, loading its designations.
+    fn from_connection(conn: Connection) -> Result<Self> {
         let mut designations = HashMap::new();
         {
             let mut stmt =
@@ -244,104 +316,96 @@ impl Database for SqlDatabase {
                 designations.insert(designation, spec);
             }
         }
+        let mut config = SqliteConfig::new();
+        // Databases created with checksums have the extra column, which older ones lack
+        if conn.prepare("SELECT crc32 FROM Metadata LIMIT 0").is_ok() {
+            config.store_checksums();
+        }
         Ok(SqlDatabase {
             conn: Arc::new(Mutex::new(conn)),
             designations,
-            config: SqliteConfig::new(),
+            config,
         })
     }
-    fn save_as(&self, filename: &str) -> Result<()> {
-        let conn = self.conn.lock()?;
-        conn.backup(rusqlite::DatabaseName::Main, filename, None)?;
-        Ok(())
+    /// Open an existing database without write access.
+    fn open_read_only(filename: &str) -> Result<Self> {
+        let flags = OpenFlags::SQLITE_OPEN_READ_ONLY
+            | OpenFlags::SQLITE_OPEN_URI
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        SqlDatabase::from_connection(Connection::open_with_flags(filename, flags)?)
     }
-
-    fn insert_spec_text(&mut self, designation: &str, spec: &str) -> Result<()> {
-        let designation_spec = DesignationSpecification::from_text(spec)?;
+    /// Flush the write-ahead log into the main database file, truncating the `-wal` file.
+    /// Has no effect when the database is not in WAL mode.
+    pub fn checkpoint(&self) -> Result<()> {
         let conn = self.conn.lock()?;
-        conn.execute(
-            "INSERT INTO designation_spec (designation, spec) VALUES (?1, ?2)",
-            (designation, spec),
-        )?;
-        self.designations
-            .insert(designation.to_string(), designation_spec);
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
         Ok(())
     }
-    fn insert_metadata(&mut self, datum: &Metadata) -> Result<()> {
-        let mut conn = self.conn.lock()?;
-        let tx = conn.transaction()?;
+    /// Decode every blob of `designation` in the bounding box with `spec`.
+    /// With the `parallel` feature, result sets of at least `PARALLEL_DECODE_THRESHOLD` records
+    /// are copied out of SQLite first and then decoded across threads.
+    fn decode_blobs_in_bb<'a>(
+        &self,
+        spec: &'a DesignationSpecification,
+        bounds: [f64; 8],
+        designation: &str,
+        epsilon: Option<f64>,
+    ) -> Result<Vec<Datum<'a>>> {
+        #[cfg(not(feature = "parallel"))]
         {
-            let mut stmt = tx.prepare_cached(
-                "INSERT INTO MetadataLocations (xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            )?;
-            stmt.execute([
-                datum.xmin, datum.xmax, datum.ymin, datum.ymax, datum.zmin, datum.zmax, datum.tmin,
-                datum.tmax,
-            ])?;
-            let mut stmt = tx.prepare_cached(
-                "INSERT INTO Metadata (id, designation, buffer) VALUES (last_insert_rowid(), ?1, ?2)",
-            )?;
-            stmt.raw_bind_parameter(1, datum.designation)?;
-            stmt.raw_bind_parameter(2, datum.buffer)?;
-            stmt.raw_execute()?;
+            let mut data = Vec::new();
+            self.for_each_blob_in_bb(bounds, designation, epsilon, RowWindow::All, |_, buffer| {
+                data.push(Datum::new(spec, spec.interpret_enum(buffer)?));
+                Ok(())
+            })?;
+            Ok(data)
         }
-
-        tx.commit()?;
-
-        Ok(())
-    }
-    fn insert_n_metadata(&mut self, data: &[Metadata]) -> Result<()> {
-        let mut conn = self.conn.lock()?;
-        let tx = conn.transaction()?;
-
-        for datum in data {
-            let mut stmt = tx.prepare_cached(
-                "INSERT INTO MetadataLocations (xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            )?;
-            stmt.execute([
-                datum.xmin, datum.xmax, datum.ymin, datum.ymax, datum.zmin, datum.zmax, datum.tmin,
-                datum.tmax,
-            ])?;
-            let mut stmt = tx.prepare_cached(
-                "INSERT INTO Metadata (id, designation, buffer) VALUES (last_insert_rowid(), ?1, ?2)",
-            )?;
-            stmt.raw_bind_parameter(1, datum.designation)?;
-            stmt.raw_bind_parameter(2, datum.buffer)?;
-            stmt.raw_execute()?;
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            let mut buffers = Vec::new();
+            self.for_each_blob_in_bb(bounds, designation, epsilon, RowWindow::All, |_, buffer| {
+                buffers.push(buffer.to_vec());
+                Ok(())
+            })?;
+            let decode = |buffer: &Vec<u8>| Ok(Datum::new(spec, spec.interpret_enum(buffer)?));
+            if buffers.len() < PARALLEL_DECODE_THRESHOLD {
+                buffers.iter().map(decode).collect()
+            } else {
+                buffers.par_iter().map(decode).collect()
+            }
         }
-
-        tx.commit()?;
-
-        Ok(())
     }
-    fn get_metadata_in_bb(
+
+    /// Run `f` on the `tmin` and buffer of every record of `designation` within the bounds
+    /// `[xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax]`, widened by `epsilon` as in [`widen_bounds`].
+    /// `window` selects which matching records are visited: [`RowWindow::All`] visits every one,
+    /// [`RowWindow::Page`] only records `offset..offset + limit` ordered by id, and
+    /// [`RowWindow::Limit`] at most `limit` records in an unspecified order.
+    fn for_each_blob_in_bb(
         &self,
-        xmin: f64,
-        xmax: f64,
-        ymin: f64,
-        ymax: f64,
-        zmin: f64,
-        zmax: f64,
-        tmin: f64,
-        tmax: f64,
+        bounds: [f64; 8],
         designation: &str,
         epsilon: Option<f64>,
-    ) -> Result<Vec<Datum>> {
-        let eps = epsilon.unwrap_or(0.0);
-        let xmin = xmin - eps;
-        let xmax = xmax + eps;
-        let ymin = ymin - eps;
-        let ymax = ymax + eps;
-        let zmin = zmin - eps;
-        let zmax = zmax + eps;
-        let tmin = tmin - eps;
-        let tmax = tmax + eps;
+        window: RowWindow,
+        mut f: impl FnMut(f64, &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax] = widen_bounds(bounds, epsilon)?;
 
-        let conn = self.conn.lock()?;
-        let mut stmt = conn.prepare_cached(
+        let paging = match window {
+            RowWindow::All => "",
+            RowWindow::Page { .. } => "ORDER BY ml.id LIMIT ?10 OFFSET ?11",
+            RowWindow::Limit(_) => "LIMIT ?10",
+        };
+        let checksum = if self.config.store_checksums {
+            ", m.crc32"
+        } else {
+            ""
+        };
+        let sql = format!(
             "SELECT 
                 ml.id, ml.xmin, ml.xmax, ml.ymin, ml.ymax, ml.zmin, ml.zmax, ml.tmin, ml.tmax,
-                m.designation, m.buffer
+                m.designation, m.buffer{checksum}
             FROM 
                 Metadata AS m
             JOIN 
@@ -354,8 +418,23 @@ impl Database for SqlDatabase {
                 ml.zmin >= ?5 AND ml.zmax <= ?6 AND
                 ml.tmin >= ?7 AND ml.tmax <= ?8 AND
                 m.designation = ?9
-            ",
-        )?;
+            {paging}
+            "
+        );
+        self.log_statement(&sql, || {
+            let mut params = format!(
+                "[{xmin:?}, {xmax:?}, {ymin:?}, {ymax:?}, {zmin:?}, {zmax:?}, {tmin:?}, {tmax:?}, {designation:?}"
+            );
+            match window {
+                RowWindow::All => {}
+                RowWindow::Page { limit, offset } => params.push_str(&format!(", {limit}, {offset}")),
+                RowWindow::Limit(limit) => params.push_str(&format!(", {limit}")),
+            }
+            params.push(']');
+            params
+        });
+        let conn = self.conn.lock()?;
+        let mut stmt = conn.prepare_cached(&sql)?;
 
         stmt.raw_bind_parameter(1, xmin)?;
         stmt.raw_bind_parameter(2, xmax)?;
@@ -366,140 +445,2067 @@ impl Database for SqlDatabase {
         stmt.raw_bind_parameter(7, tmin)?;
         stmt.raw_bind_parameter(8, tmax)?;
         stmt.raw_bind_parameter(9, designation)?;
+        match window {
+            RowWindow::All => {}
+            RowWindow::Page { limit, offset } => {
+                stmt.raw_bind_parameter(10, limit as i64)?;
+                stmt.raw_bind_parameter(11, offset as i64)?;
+            }
+            RowWindow::Limit(limit) => stmt.raw_bind_parameter(10, limit as i64)?,
+        }
 
         let mut rows = stmt.raw_query();
-        let mut data = Vec::new();
         while let Some(row) = rows.next()? {
             let buffer = match row.get_ref(10)? {
                 rusqlite::types::ValueRef::Blob(b) => b,
                 _ => unreachable!("We should always retrieve blobs!"),
             };
-            let d = self.designations.get(designation).unwrap();
-            data.push(d.interpret_enum(buffer).unwrap());
+            if self.config.store_checksums {
+                let stored: Option<u32> = row.get(11)?;
+                if stored != Some(crc32fast::hash(buffer)) {
+                    return Err(DatabaseError::ChecksumMismatch { id: row.get(0)? });
+                }
+            }
+            f(row.get(7)?, buffer)?;
         }
-        Ok(data)
-    }
-
-    fn get_metadata_blobs_in_bb(
-        &self,
-        _xmin: f64,
-        _xmax: f64,
-        _ymin: f64,
-        _ymax: f64,
-        _zmin: f64,
-        _zmax: f64,
-        _tmin: f64,
-        _tmax: f64,
-        _designation: &str,
-        _epsilon: Option<f64>,
-    ) -> Result<Vec<&Vec<u8>>> {
-        todo!();
-    }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-    use rand;
-    use rand::Rng;
-
-    struct TempFile {
-        pub filepath: String,
+        Ok(())
     }
-
-    impl TempFile {
-        pub fn new() -> Result<Self> {
-            let random_filename = random_identifier(10);
-            Self::from(&random_filename)
-        }
-        pub fn from(filename: &str) -> Result<Self> {
-            let temp_dir = std::env::temp_dir();
-            let file_dir = temp_dir.join(random_identifier(10));
-            let filepath = file_dir.join(filename);
-            let filepath = filepath.to_str().unwrap();
-            let _ = std::fs::create_dir_all(file_dir);
-            Ok(TempFile {
-                filepath: filepath.to_string(),
-            })
+    /// Decode every record of `designation` in insertion order, passing each to `f` as it is
+    /// read rather than collecting them first.
+    pub fn for_each_metadata_for_designation<'a>(
+        &'a self,
+        designation: &str,
+        mut f: impl FnMut(Datum<'a>) -> Result<()>,
+    ) -> Result<()> {
+        let spec = self
+            .designations
+            .get(designation)
+            .ok_or_else(|| DatabaseError::unregistered(designation))?;
+        let checksum = if self.config.store_checksums {
+            ", crc32"
+        } else {
+            ""
+        };
+        let conn = self.conn.lock()?;
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT id, buffer{checksum} FROM Metadata WHERE designation = ?1 ORDER BY id"
+        ))?;
+        stmt.raw_bind_parameter(1, designation)?;
+        let mut rows = stmt.raw_query();
+        while let Some(row) = rows.next()? {
+            let buffer = match row.get_ref(1)? {
+                rusqlite::types::ValueRef::Blob(b) => b,
+                _ => unreachable!("We should always retrieve blobs!"),
+            };
+            if self.config.store_checksums {
+                let stored: Option<u32> = row.get(2)?;
+                if stored != Some(crc32fast::hash(buffer)) {
+                    return Err(DatabaseError::ChecksumMismatch { id: row.get(0)? });
+                }
+            }
+            f(Datum::new(spec, spec.interpret_enum(buffer)?))?;
         }
+        Ok(())
     }
-
-    impl std::ops::Drop for TempFile {
-        fn drop(&mut self) {
-            let _ = std::fs::remove_file(&self.filepath);
-        }
+    /// Decode every record of `designation`, regardless of location, in insertion order.
+    pub fn get_all_metadata_for_designation(&self, designation: &str) -> Result<Vec<Datum<'_>>> {
+        let mut data = Vec::new();
+        self.for_each_metadata_for_designation(designation, |datum| {
+            data.push(datum);
+            Ok(())
+        })?;
+        Ok(data)
     }
+    /// Retrieve one page of the records `get_metadata_in_bb` would return.
+    /// Records are ordered by id so that successive pages are disjoint and together cover
+    /// the full result; without an ORDER BY, sqlite may return rows in any order and page
+    /// boundaries would not be stable between queries.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_metadata_in_bb_paged(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Datum<'_>>> {
+        let Some(d) = self.designations.get(designation) else {
+            return Ok(Vec::new());
+        };
+        let mut data = Vec::new();
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+            RowWindow::Page { limit, offset },
+            |_, buffer| {
+                data.push(Datum::new(d, d.interpret_enum(buffer)?));
+                Ok(())
+            },
+        )?;
+        Ok(data)
+    }
+    /// Retrieve at most `limit` of the records `get_metadata_in_bb` would return.
+    /// Which records are returned, and in what order, is unspecified: the query has no
+    /// ORDER BY, so sqlite can stop as soon as it has found `limit` matches. Use
+    /// [`SqlDatabase::get_metadata_in_bb_paged`] when a stable order is needed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_metadata_in_bb_limited(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+        limit: usize,
+    ) -> Result<Vec<Datum<'_>>> {
+        let Some(d) = self.designations.get(designation) else {
+            return Ok(Vec::new());
+        };
+        let mut data = Vec::new();
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+            RowWindow::Limit(limit),
+            |_, buffer| {
+                data.push(Datum::new(d, d.interpret_enum(buffer)?));
+                Ok(())
+            },
+        )?;
+        Ok(data)
+    }
+    /// Retrieve metadata within a bounding box as `get_metadata_in_bb` does, checking `cancel`
+    /// before each record is decoded. Once another thread sets the flag, the query stops and
+    /// returns [`DatabaseError::Cancelled`], discarding any records decoded so far.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_metadata_in_bb_cancellable(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+        cancel: &AtomicBool,
+    ) -> Result<Vec<Datum<'_>>> {
+        self.decode_blobs_until_cancelled(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+            || cancel.load(Ordering::Relaxed),
+        )
+    }
+    /// Decode the records of `designation` in a bounding box, calling `is_cancelled` before
+    /// each one and stopping with [`DatabaseError::Cancelled`] once it returns true.
+    fn decode_blobs_until_cancelled(
+        &self,
+        bounds: [f64; 8],
+        designation: &str,
+        epsilon: Option<f64>,
+        mut is_cancelled: impl FnMut() -> bool,
+    ) -> Result<Vec<Datum<'_>>> {
+        let spec = self
+            .designations
+            .get(designation)
+            .ok_or_else(|| DatabaseError::unregistered(designation))?;
+        let mut data = Vec::new();
+        self.for_each_blob_in_bb(bounds, designation, epsilon, RowWindow::All, |_, buffer| {
+            if is_cancelled() {
+                return Err(DatabaseError::Cancelled);
+            }
+            data.push(Datum::new(spec, spec.interpret_enum(buffer)?));
+            Ok(())
+        })?;
+        Ok(data)
+    }
+    /// Retrieve metadata within a bounding box, decoded with `interpret` rather than
+    /// `interpret_enum` so that callers get the full `as_*` conversion surface of
+    /// [`Representable`] on each member.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_metadata_repr_in_bb(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+    ) -> Result<Vec<HashMap<&str, Box<dyn Representable>>>> {
+        let Some(d) = self.designations.get(designation) else {
+            return Ok(Vec::new());
+        };
+        let mut data = Vec::new();
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+            RowWindow::All,
+            |_, buffer| {
+                data.push(d.interpret(buffer)?);
+                Ok(())
+            },
+        )?;
+        Ok(data)
+    }
+    /// Retrieve metadata within a bounding box, grouped into time windows of `bucket_width`.
+    /// Each record is assigned to bucket `floor(tmin / bucket_width)`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_metadata_bucketed_by_time(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+        bucket_width: f64,
+    ) -> Result<BTreeMap<i64, Vec<Datum<'_>>>> {
+        if !(bucket_width.is_finite() && bucket_width > 0.0) {
+            return Err(DatabaseError::ConfigError {
+                reason: format!("bucket width must be positive and finite, got {bucket_width}"),
+            });
+        }
+        let Some(d) = self.designations.get(designation) else {
+            return Ok(BTreeMap::new());
+        };
+        let mut buckets: BTreeMap<i64, Vec<Datum>> = BTreeMap::new();
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+            RowWindow::All,
+            |t, buffer| {
+                let bucket = (t / bucket_width).floor() as i64;
+                buckets
+                    .entry(bucket)
+                    .or_default()
+                    .push(Datum::new(d, d.interpret_enum(buffer)?));
+                Ok(())
+            },
+        )?;
+        Ok(buckets)
+    }
+    /// Write every record of `designation` to `writer` as newline-delimited JSON, one object
+    /// per record mapping member names to values. Records are decoded and written one at a
+    /// time. Returns the number of records written.
+    pub fn export_ndjson(&self, designation: &str, mut writer: impl Write) -> Result<usize> {
+        let spec = self
+            .designations
+            .get(designation)
+            .ok_or_else(|| DatabaseError::unregistered(designation))?;
+        let mut count = 0;
+        self.for_each_blob_in_bb(
+            [
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+            ],
+            designation,
+            None,
+            RowWindow::All,
+            |_, buffer| {
+                let datum = Datum::new(spec, spec.interpret_enum(buffer)?);
+                let object: serde_json::Map<String, serde_json::Value> = datum
+                    .iter()
+                    .map(|(name, value)| serde_json::to_value(value).map(|v| (name.to_string(), v)))
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(std::io::Error::from)?;
+                serde_json::to_writer(&mut writer, &object).map_err(std::io::Error::from)?;
+                writeln!(writer)?;
+                count += 1;
+                Ok(())
+            },
+        )?;
+        Ok(count)
+    }
+    /// Look up the specification of `designation`, checking that `member` is a numeric scalar.
+    fn numeric_scalar_spec(
+        &self,
+        designation: &str,
+        member: &str,
+    ) -> Result<&DesignationSpecification> {
+        let spec = self
+            .designations
+            .get(designation)
+            .ok_or_else(|| DatabaseError::unregistered(designation))?;
+        let is_numeric_scalar = spec.member(member).is_some_and(|m| {
+            *m.sizing() == Sizing::Singleton
+                && matches!(
+                    m.dtype(),
+                    Dtype::Byte
+                        | Dtype::UnsignedInteger16
+                        | Dtype::UnsignedInteger32
+                        | Dtype::UnsignedInteger64
+                        | Dtype::SignedInteger8
+                        | Dtype::SignedInteger16
+                        | Dtype::SignedInteger32
+                        | Dtype::SignedInteger64
+                        | Dtype::Float32
+                        | Dtype::Float64
+                        | Dtype::Duration
+                )
+        });
+        if !is_numeric_scalar {
+            Err(DatabaseError::SpecificationError {
+                reason: format!(
+                    "member \"{member}\" of designation \"{designation}\" is not a numeric scalar"
+                ),
+            })?;
+        }
+        Ok(spec)
+    }
+    /// Reduce a numeric scalar member over every record of `designation` in a bounding box.
+    /// Records are decoded and folded one at a time rather than collected first.
+    /// `Min`, `Max` and `Mean` yield `NaN` when no records match.
+    #[allow(clippy::too_many_arguments)]
+    pub fn aggregate_member(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+        member: &str,
+        agg: Aggregate,
+    ) -> Result<f64> {
+        let spec = self.numeric_scalar_spec(designation, member)?;
+
+        let mut count = 0usize;
+        let mut acc = match agg {
+            Aggregate::Min => f64::INFINITY,
+            Aggregate::Max => f64::NEG_INFINITY,
+            Aggregate::Mean | Aggregate::Sum | Aggregate::Count => 0.0,
+        };
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+            RowWindow::All,
+            |_, buffer| {
+                let values = spec.interpret_enum(buffer)?;
+                // Presence and type were checked against the spec above
+                let value = values[member].as_f64().unwrap();
+                acc = match agg {
+                    Aggregate::Min => acc.min(value),
+                    Aggregate::Max => acc.max(value),
+                    Aggregate::Mean | Aggregate::Sum => acc + value,
+                    Aggregate::Count => acc,
+                };
+                count += 1;
+                Ok(())
+            },
+        )?;
+
+        let result = match agg {
+            Aggregate::Count => count as f64,
+            Aggregate::Sum => acc,
+            _ if count == 0 => f64::NAN,
+            Aggregate::Mean => acc / count as f64,
+            Aggregate::Min | Aggregate::Max => acc,
+        };
+        Ok(result)
+    }
+    /// Collect the distinct values of the scalar `member` over every record of `designation` in
+    /// a bounding box, in the order they are first found. Values are compared with `==`, so
+    /// this is meant for low-cardinality members such as modes or categories.
+    #[allow(clippy::too_many_arguments)]
+    pub fn distinct_member_values(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+        member: &str,
+    ) -> Result<Vec<DataValue>> {
+        let spec = self
+            .designations
+            .get(designation)
+            .ok_or_else(|| DatabaseError::unregistered(designation))?;
+        if !spec
+            .member(member)
+            .is_some_and(|m| *m.sizing() == Sizing::Singleton)
+        {
+            Err(DatabaseError::SpecificationError {
+                reason: format!(
+                    "member \"{member}\" of designation \"{designation}\" is not a scalar"
+                ),
+            })?;
+        }
+
+        let mut distinct = Vec::new();
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+            RowWindow::All,
+            |_, buffer| {
+                // Presence was checked against the spec above
+                let value = spec
+                    .interpret_subset(buffer, &[member])?
+                    .remove(member)
+                    .unwrap();
+                if !distinct.contains(&value) {
+                    distinct.push(value);
+                }
+                Ok(())
+            },
+        )?;
+        Ok(distinct)
+    }
+    /// Retrieve metadata within a bounding box whose numeric scalar `member` lies within `tol`
+    /// of `target`. Filtering happens after decoding, so every record in the box is decoded.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_metadata_where_member_near(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        member: &str,
+        target: f64,
+        tol: f64,
+    ) -> Result<Vec<Datum<'_>>> {
+        let spec = self.numeric_scalar_spec(designation, member)?;
+        let mut data = Vec::new();
+        self.for_each_blob_in_bb(
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            None,
+            RowWindow::All,
+            |_, buffer| {
+                let values = spec.interpret_enum(buffer)?;
+                // Presence and type were checked against the spec above
+                let value = values[member].as_f64().unwrap();
+                if (value - target).abs() <= tol {
+                    data.push(Datum::new(spec, values));
+                }
+                Ok(())
+            },
+        )?;
+        Ok(data)
+    }
+    pub fn get_designations(&self) -> HashMap<String, DesignationSpecification> {
+        self.designations.clone()
+    }
+    /// Register an already parsed specification under `designation`, storing its text form.
+    pub fn register_designation(
+        &mut self,
+        designation: &str,
+        spec: DesignationSpecification,
+    ) -> Result<()> {
+        let conn = self.conn.lock()?;
+        conn.execute(
+            "INSERT INTO designation_spec (designation, spec) VALUES (?1, ?2)",
+            (designation, spec.to_string()),
+        )?;
+        self.designations.insert(designation.to_string(), spec);
+        Ok(())
+    }
+    /// Insert `datum` whose designation is described by `spec`, registering `spec` first if the
+    /// designation is new. Fails if the designation is already registered with a different
+    /// specification.
+    pub fn insert_metadata_with_spec(
+        &mut self,
+        datum: &Metadata,
+        spec: &DesignationSpecification,
+    ) -> Result<()> {
+        match self.designations.get(datum.designation) {
+            None => self.register_designation(datum.designation, spec.clone())?,
+            Some(existing) if existing != spec => {
+                return Err(DatabaseError::SpecificationError {
+                    reason: format!(
+                        "designation \"{}\" is registered as \"{existing}\", not \"{spec}\"",
+                        datum.designation
+                    ),
+                })
+            }
+            Some(_) => {}
+        }
+        self.insert_metadata(datum)
+    }
+    /// Replace the specification of a registered designation with `new_spec`, which must only
+    /// append members after the existing ones. Records stored before the migration do not
+    /// contain the appended members.
+    pub fn migrate_designation(&mut self, designation: &str, new_spec: &str) -> Result<()> {
+        let old = self
+            .designations
+            .get(designation)
+            .ok_or_else(|| DatabaseError::unregistered(designation))?;
+        let new = DesignationSpecification::from_text(new_spec)?;
+        let diff = old.diff(&new);
+        if !diff.is_append_only() {
+            return Err(DatabaseError::IncompatibleMigration {
+                designation: designation.to_string(),
+                reason: format!(
+                    "specification is not append-only (removed: {:?}, changed: {:?})",
+                    diff.removed, diff.changed
+                ),
+            });
+        }
+        let conn = self.conn.lock()?;
+        conn.execute(
+            "UPDATE designation_spec SET spec = ?2 WHERE designation = ?1",
+            (designation, new_spec),
+        )?;
+        self.designations.insert(designation.to_string(), new);
+        Ok(())
+    }
+    /// Count how many members use each dtype across all registered designations.
+    pub fn dtype_usage(&self) -> Result<HashMap<Dtype, usize>> {
+        let mut usage = HashMap::new();
+        for spec in self.designations.values() {
+            for dtype in spec.member_dtypes() {
+                *usage.entry(dtype.clone()).or_insert(0) += 1;
+            }
+        }
+        Ok(usage)
+    }
+    /// Total bytes of stored buffers for each designation with at least one record.
+    pub fn storage_bytes_by_designation(&self) -> Result<HashMap<String, u64>> {
+        let conn = self.conn.lock()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT designation, SUM(LENGTH(buffer)) FROM Metadata GROUP BY designation",
+        )?;
+        let totals = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            })?
+            .collect::<rusqlite::Result<HashMap<String, u64>>>()?;
+        Ok(totals)
+    }
+    /// Smallest box containing every record of `designation`, or `None` if it has no records.
+    /// The envelope is computed by sqlite from the stored locations, so no buffers are read.
+    pub fn bbox_extent(&self, designation: &str) -> Result<Option<BoundingBox>> {
+        let conn = self.conn.lock()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT
+                MIN(ml.xmin), MAX(ml.xmax), MIN(ml.ymin), MAX(ml.ymax),
+                MIN(ml.zmin), MAX(ml.zmax), MIN(ml.tmin), MAX(ml.tmax)
+            FROM
+                MetadataLocations AS ml
+            JOIN
+                Metadata AS m
+            ON
+                ml.id = m.id
+            WHERE
+                m.designation = ?1",
+        )?;
+        // Every aggregate is NULL when no records match
+        let bounds = stmt.query_row([designation], |row| {
+            (0..8)
+                .map(|i| row.get::<_, Option<f64>>(i))
+                .collect::<rusqlite::Result<Option<Vec<f64>>>>()
+        })?;
+        Ok(
+            bounds
+                .map(|b| BoundingBox::from_bounds(b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7])),
+        )
+    }
+    /// Attempt to decode every stored record against its designation's specification,
+    /// collecting a [`VerificationError`] for each record that fails rather than stopping at the
+    /// first. When checksums are stored, each buffer is also checked against its checksum before
+    /// it is decoded. A healthy database yields an empty vector.
+    pub fn verify_all(&self) -> Result<Vec<VerificationError>> {
+        let mut failures = Vec::new();
+        let checksum = if self.config.store_checksums {
+            ", crc32"
+        } else {
+            ""
+        };
+        let conn = self.conn.lock()?;
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT id, designation, buffer{checksum} FROM Metadata ORDER BY id"
+        ))?;
+        let mut rows = stmt.raw_query();
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let designation = row.get_ref(1)?.as_str()?;
+            let result = match row.get_ref(2)?.as_blob() {
+                Err(error) => Err(error.into()),
+                Ok(buffer)
+                    if self.config.store_checksums
+                        && row.get::<_, Option<u32>>(3)? != Some(crc32fast::hash(buffer)) =>
+                {
+                    Err(DatabaseError::ChecksumMismatch { id })
+                }
+                Ok(buffer) => match self.designations.get(designation) {
+                    Some(spec) => spec.interpret_enum(buffer).map(|_| ()).map_err(Into::into),
+                    None => Err(DatabaseError::unregistered(designation)),
+                },
+            };
+            if let Err(error) = result {
+                failures.push(VerificationError {
+                    id,
+                    designation: designation.to_string(),
+                    error,
+                });
+            }
+        }
+        Ok(failures)
+    }
+    pub fn get_all_metadata(&self) -> Result<Vec<MetadataClone>> {
+        let mut data = Vec::new();
+        let conn = self.conn.lock()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT 
+                ml.id, ml.xmin, ml.xmax, ml.ymin, ml.ymax, ml.zmin, ml.zmax, ml.tmin, ml.tmax,
+                m.designation, m.buffer
+            FROM 
+                Metadata AS m
+            JOIN 
+                MetadataLocations AS ml
+            ON 
+                ml.id = m.id
+            ",
+        )?;
+        let mut rows = stmt.raw_query();
+        while let Some(row) = rows.next()? {
+            let xmin = row.get_ref(1)?.as_f64()?;
+            let xmax = row.get_ref(2)?.as_f64()?;
+            let ymin = row.get_ref(3)?.as_f64()?;
+            let ymax = row.get_ref(4)?.as_f64()?;
+            let zmin = row.get_ref(5)?.as_f64()?;
+            let zmax = row.get_ref(6)?.as_f64()?;
+            let tmin = row.get_ref(7)?.as_f64()?;
+            let tmax = row.get_ref(8)?.as_f64()?;
+            let designation = row.get_ref(9)?.as_str()?;
+            let buffer = match row.get_ref(10)? {
+                rusqlite::types::ValueRef::Blob(b) => b,
+                _ => unreachable!("We should always retrieve blobs!"),
+            };
+            data.push(MetadataClone {
+                xmin,
+                xmax,
+                ymin,
+                ymax,
+                zmin,
+                zmax,
+                tmin,
+                tmax,
+                designation: designation.to_string(),
+                buffer: buffer.into(),
+            });
+        }
+        Ok(data)
+    }
+}
+
+impl Database for SqlDatabase {
+    fn new(filename: Option<&str>, config: Option<&DatabaseConfig>) -> Result<Self> {
+        let config = match config {
+            Some(dbcfg) => match &dbcfg {
+                DatabaseConfig::SqliteConfig(sqlcfg) => sqlcfg.clone(),
+                #[cfg(feature = "rtree")]
+                _ => Err(DatabaseError::ConfigError {
+                    reason: "Sqlite given config for incorrect backend.".to_string(),
+                })?,
+            },
+            None => SqliteConfig::new(),
+        };
+        let db = if let Some(name) = filename {
+            SqlDatabase {
+                conn: Arc::new(Mutex::new(Connection::open(name)?)),
+                designations: HashMap::new(),
+                config,
+            }
+        } else {
+            SqlDatabase {
+                conn: Arc::new(Mutex::new(Connection::open_in_memory()?)),
+                designations: HashMap::new(),
+                config,
+            }
+        };
+        db.initialize()?;
+        Ok(db)
+    }
+    fn from_path(filename: &str) -> Result<Self> {
+        SqlDatabase::from_connection(Connection::open(filename)?)
+    }
+    fn save_as(&self, filename: &str) -> Result<()> {
+        self.checkpoint()?;
+        let conn = self.conn.lock()?;
+        conn.backup(rusqlite::DatabaseName::Main, filename, None)?;
+        Ok(())
+    }
+
+    fn insert_spec_text(&mut self, designation: &str, spec: &str) -> Result<()> {
+        let designation_spec = DesignationSpecification::from_text(spec)?;
+        let conn = self.conn.lock()?;
+        conn.execute(
+            "INSERT INTO designation_spec (designation, spec) VALUES (?1, ?2)",
+            (designation, spec),
+        )?;
+        self.designations
+            .insert(designation.to_string(), designation_spec);
+        Ok(())
+    }
+    fn insert_specs(&mut self, specs: &[(String, String)]) -> Result<()> {
+        let parsed = parse_specs(specs, &self.designations)?;
+        let mut conn = self.conn.lock()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO designation_spec (designation, spec) VALUES (?1, ?2)",
+            )?;
+            for (designation, spec) in specs {
+                stmt.execute((designation, spec))?;
+            }
+        }
+        tx.commit()?;
+        for (designation, designation_spec) in parsed {
+            self.designations
+                .insert(designation.to_string(), designation_spec);
+        }
+        Ok(())
+    }
+    fn insert_metadata(&mut self, datum: &Metadata) -> Result<()> {
+        self.check_finite(datum)?;
+        let mut conn = self.conn.lock()?;
+        let tx = conn.transaction()?;
+        {
+            let sql = "INSERT INTO MetadataLocations (xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)";
+            self.log_statement(sql, || Self::describe_location(datum));
+            let mut stmt = tx.prepare_cached(sql)?;
+            stmt.execute([
+                datum.xmin, datum.xmax, datum.ymin, datum.ymax, datum.zmin, datum.zmax, datum.tmin,
+                datum.tmax,
+            ])?;
+            let sql = if self.config.store_checksums {
+                "INSERT INTO Metadata (id, designation, buffer, crc32) VALUES (last_insert_rowid(), ?1, ?2, ?3)"
+            } else {
+                "INSERT INTO Metadata (id, designation, buffer) VALUES (last_insert_rowid(), ?1, ?2)"
+            };
+            self.log_statement(sql, || {
+                format!("[{:?}, <{} bytes>]", datum.designation, datum.buffer.len())
+            });
+            let mut stmt = tx.prepare_cached(sql)?;
+            if self.config.store_checksums {
+                stmt.raw_bind_parameter(3, crc32fast::hash(datum.buffer))?;
+            }
+            stmt.raw_bind_parameter(1, datum.designation)?;
+            stmt.raw_bind_parameter(2, datum.buffer)?;
+            stmt.raw_execute()?;
+        }
+
+        tx.commit()?;
+
+        Ok(())
+    }
+    fn insert_n_metadata(&mut self, data: &[Metadata]) -> Result<()> {
+        self.insert_n_metadata_returning_ids(data)?;
+        Ok(())
+    }
+    fn get_metadata_in_bb(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+    ) -> Result<Vec<Datum<'_>>> {
+        let Some(d) = self.designations.get(designation) else {
+            return Ok(Vec::new());
+        };
+        self.decode_blobs_in_bb(
+            d,
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+        )
+    }
+
+    fn get_metadata_blobs_in_bb(
+        &self,
+        _xmin: f64,
+        _xmax: f64,
+        _ymin: f64,
+        _ymax: f64,
+        _zmin: f64,
+        _zmax: f64,
+        _tmin: f64,
+        _tmax: f64,
+        _designation: &str,
+        _epsilon: Option<f64>,
+    ) -> Result<Vec<Vec<u8>>> {
+        todo!();
+    }
+}
+
+/// A single writer connection and several read-only connections to one database file.
+/// The file is kept in WAL mode so that queries on the readers neither block nor are blocked
+/// by inserts on the writer, and concurrent queries are spread across the readers.
+pub struct SqlDatabasePool {
+    /// Connection all inserts go through
+    writer: Mutex<SqlDatabase>,
+    /// Connections opened with `SQLITE_OPEN_READ_ONLY`
+    readers: Vec<SqlDatabase>,
+    /// Reader to try first on the next query
+    next_reader: AtomicUsize,
+    /// Mapping of designations, used to interpret query results
+    designations: HashMap<String, DesignationSpecification>,
+}
+
+impl SqlDatabasePool {
+    /// Create a new database at `filename` with `n_readers` read-only connections.
+    pub fn new(filename: &str, n_readers: usize) -> Result<Self> {
+        let config = DatabaseConfig::SqliteConfig(SqliteConfig::new().use_wal());
+        let writer = SqlDatabase::new(Some(filename), Some(&config))?;
+        SqlDatabasePool::with_writer(filename, writer, n_readers)
+    }
+    /// Open an existing database at `filename` with `n_readers` read-only connections,
+    /// switching it to WAL mode if it is not already.
+    pub fn from_path(filename: &str, n_readers: usize) -> Result<Self> {
+        let mut writer = SqlDatabase::from_path(filename)?;
+        writer.config = writer.config.use_wal();
+        {
+            let conn = writer.conn.lock()?;
+            conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
+        }
+        SqlDatabasePool::with_writer(filename, writer, n_readers)
+    }
+    fn with_writer(filename: &str, writer: SqlDatabase, n_readers: usize) -> Result<Self> {
+        if n_readers == 0 {
+            return Err(DatabaseError::ConfigError {
+                reason: "A connection pool needs at least one reader.".to_string(),
+            });
+        }
+        let readers = (0..n_readers)
+            .map(|_| SqlDatabase::open_read_only(filename))
+            .collect::<Result<Vec<SqlDatabase>>>()?;
+        Ok(SqlDatabasePool {
+            designations: writer.get_designations(),
+            writer: Mutex::new(writer),
+            readers,
+            next_reader: AtomicUsize::new(0),
+        })
+    }
+    /// Pick a reader that is not running a query, starting from the round-robin position.
+    /// If every reader is busy, the round-robin reader is used and the caller waits on it.
+    fn reader(&self) -> &SqlDatabase {
+        let n = self.readers.len();
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed) % n;
+        (0..n)
+            .map(|i| &self.readers[(start + i) % n])
+            .find(|reader| reader.conn.try_lock().is_ok())
+            .unwrap_or(&self.readers[start])
+    }
+    pub fn insert_spec_text(&mut self, designation: &str, spec: &str) -> Result<()> {
+        let designation_spec = DesignationSpecification::from_text(spec)?;
+        self.writer.get_mut()?.insert_spec_text(designation, spec)?;
+        self.designations
+            .insert(designation.to_string(), designation_spec);
+        Ok(())
+    }
+    pub fn insert_metadata(&self, datum: &Metadata) -> Result<()> {
+        self.writer.lock()?.insert_metadata(datum)
+    }
+    pub fn insert_n_metadata(&self, data: &[Metadata]) -> Result<()> {
+        self.writer.lock()?.insert_n_metadata(data)
+    }
+    /// Retrieve metadata within a bounding box using one of the read-only connections.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_metadata_in_bb(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+    ) -> Result<Vec<Datum<'_>>> {
+        let Some(d) = self.designations.get(designation) else {
+            return Ok(Vec::new());
+        };
+        self.reader().decode_blobs_in_bb(
+            d,
+            [xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax],
+            designation,
+            epsilon,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand;
+    use rand::Rng;
+
+    struct TempFile {
+        pub filepath: String,
+    }
+
+    impl TempFile {
+        pub fn new() -> Result<Self> {
+            let random_filename = random_identifier(10);
+            Self::from(&random_filename)
+        }
+        pub fn from(filename: &str) -> Result<Self> {
+            let temp_dir = std::env::temp_dir();
+            let file_dir = temp_dir.join(random_identifier(10));
+            let filepath = file_dir.join(filename);
+            let filepath = filepath.to_str().unwrap();
+            let _ = std::fs::create_dir_all(file_dir);
+            Ok(TempFile {
+                filepath: filepath.to_string(),
+            })
+        }
+    }
+
+    impl std::ops::Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.filepath);
+        }
+    }
+
+    fn random_identifier(size: u8) -> String {
+        let mut rng = rand::thread_rng();
+        (0..size)
+            .map(|_| rng.gen_range(b'a'..=b'z') as char)
+            .collect()
+    }
+
+    mod config {
+        use super::*;
+
+        #[test]
+        fn to_and_from_json_ok() {
+            let cfg = SqliteConfig::new().use_wal().page_size(2048);
+            let temp_file = TempFile::from("temp.json").unwrap();
+            let _ = cfg.to_json_file(&temp_file.filepath);
+            let recovered_cfg = SqliteConfig::from_json_file(&temp_file.filepath).unwrap();
+            pretty_assertions::assert_eq!(cfg, recovered_cfg);
+        }
+    }
+
+    mod database {
+        use super::*;
+        use crate::error::DatabaseError;
+        use elucidator::value::DataValue;
+        use std::{collections::HashSet, ops::Deref};
+
+        #[test]
+        fn create_in_memory_ok() {
+            assert!(SqlDatabase::new(None, None).is_ok());
+        }
+
+        #[test]
+        fn create_with_filepath_ok() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let db = SqlDatabase::new(Some(&tempfile.filepath), None);
+            assert!(db.is_ok());
+        }
+
+        #[test]
+        fn from_empty_ok() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let db = SqlDatabase::new(Some(&tempfile.filepath), None);
+            drop(db);
+            let loaded_db = SqlDatabase::from_path(&tempfile.filepath);
+            assert!(loaded_db.is_ok());
+        }
+
+        #[test]
+        fn insert_designation_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let designation = "Foo";
+            let spec = "foo: u8";
+            let result = db.insert_spec_text(designation, spec);
+            pretty_assertions::assert_eq!(result, Ok(()));
+            let keys = db
+                .designations
+                .keys()
+                .map(String::deref)
+                .collect::<HashSet<&str>>();
+            pretty_assertions::assert_eq!(keys, HashSet::from(["Foo"]));
+        }
+
+        #[test]
+        fn insert_1_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+
+            let buffer: &[u8; 1] = &[100; 1];
+            let designation = "Foo";
+            let spec = "foo: u8";
+            let md = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation,
+                buffer,
+            };
+
+            let _ = db.insert_spec_text(designation, spec);
+            let result = db.insert_metadata(&md);
+
+            pretty_assertions::assert_eq!(result, Ok(()));
+        }
+
+        #[test]
+        fn insert_bad_designation_fails() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let designation = "Foo";
+            let spec = "foo u8";
+            let result = db.insert_spec_text(designation, spec);
+            let expected = DesignationSpecification::from_text(spec);
+            assert!(
+                expected.is_err(),
+                "Expected an error from bad designation spec, but got ok instead."
+            );
+            pretty_assertions::assert_eq!(
+                result,
+                Err(DatabaseError::ElucidatorError {
+                    reason: expected.unwrap_err()
+                })
+            );
+        }
+
+        #[test]
+        fn insert_specs_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let specs = vec![
+                ("Foo".to_string(), "foo: u8".to_string()),
+                ("Bar".to_string(), "bar: f32[3], baz: string".to_string()),
+            ];
+            let result = db.insert_specs(&specs);
+            pretty_assertions::assert_eq!(result, Ok(()));
+            let keys = db
+                .designations
+                .keys()
+                .map(String::deref)
+                .collect::<HashSet<&str>>();
+            pretty_assertions::assert_eq!(keys, HashSet::from(["Foo", "Bar"]));
+            let stored: usize = db
+                .conn
+                .lock()
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM designation_spec", [], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            pretty_assertions::assert_eq!(stored, 2);
+        }
+
+        #[test]
+        fn migrate_designation_append_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u8").unwrap();
+            db.migrate_designation("Foo", "foo: u8, bar: f32[]")
+                .unwrap();
+            let expected = DesignationSpecification::from_text("foo: u8, bar: f32[]").unwrap();
+            pretty_assertions::assert_eq!(db.designations["Foo"], expected);
+            let stored: String = db
+                .conn
+                .lock()
+                .unwrap()
+                .query_row(
+                    "SELECT spec FROM designation_spec WHERE designation = 'Foo'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            pretty_assertions::assert_eq!(stored, "foo: u8, bar: f32[]");
+        }
+
+        #[test]
+        fn migrate_designation_type_change_fails() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u8").unwrap();
+            let result = db.migrate_designation("Foo", "foo: u16, bar: u8");
+            assert!(matches!(
+                result,
+                Err(DatabaseError::IncompatibleMigration { .. })
+            ));
+            let expected = DesignationSpecification::from_text("foo: u8").unwrap();
+            pretty_assertions::assert_eq!(db.designations["Foo"], expected);
+        }
+
+        #[test]
+        fn insert_specs_with_bad_spec_fails() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let specs = vec![
+                ("Foo".to_string(), "foo: u8".to_string()),
+                ("Bar".to_string(), "bar f32".to_string()),
+            ];
+            let result = db.insert_specs(&specs);
+            match result {
+                Err(DatabaseError::SpecificationError { reason }) => {
+                    assert!(reason.contains("\"Bar\""), "{reason}");
+                    assert!(!reason.contains("\"Foo\""), "{reason}");
+                }
+                other => panic!("Expected a specification error, got {other:?}"),
+            }
+            assert!(db.designations.is_empty());
+            let stored: usize = db
+                .conn
+                .lock()
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM designation_spec", [], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            pretty_assertions::assert_eq!(stored, 0);
+        }
+
+        #[test]
+        fn insert_specs_duplicate_fails() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u8").unwrap();
+            let specs = vec![
+                ("Foo".to_string(), "foo: u16".to_string()),
+                ("Bar".to_string(), "bar: u8".to_string()),
+                ("Bar".to_string(), "bar: u16".to_string()),
+            ];
+            match db.insert_specs(&specs) {
+                Err(DatabaseError::SpecificationError { reason }) => {
+                    assert!(reason.contains("\"Foo\": already registered"), "{reason}");
+                    assert!(reason.contains("\"Bar\": given more than once"), "{reason}");
+                }
+                other => panic!("Expected a specification error, got {other:?}"),
+            }
+            pretty_assertions::assert_eq!(db.designations.len(), 1);
+            assert!(!db.designations.contains_key("Bar"));
+        }
+
+        #[test]
+        fn dtype_usage_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let _ = db.insert_spec_text("Foo", "foo: u8, bar: f32[3], baz: string");
+            let _ = db.insert_spec_text("Bar", "qux: f32, quux: u8[], name: string, id: u64");
+            let result = db.dtype_usage();
+            let expected = HashMap::from([
+                (Dtype::Byte, 2),
+                (Dtype::Float32, 2),
+                (Dtype::Str, 2),
+                (Dtype::UnsignedInteger64, 1),
+            ]);
+            pretty_assertions::assert_eq!(result, Ok(expected));
+        }
+
+        #[test]
+        fn insert_n_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+
+            let designation = "Foo";
+            let spec = "foo: u8";
+            let buffer: &[u8; 1] = &[100; 1];
+            let md1 = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation,
+                buffer,
+            };
+
+            let buffer: &[u8; 1] = &[150; 1];
+            let md2 = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation,
+                buffer,
+            };
+
+            let buffer: &[u8; 1] = &[200; 1];
+            let md3 = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation,
+                buffer,
+            };
+
+            let metadata: Vec<Metadata> = vec![md1, md2, md3];
+
+            let _ = db.insert_spec_text(designation, spec);
+            let result = db.insert_n_metadata(&metadata);
+
+            pretty_assertions::assert_eq!(result, Ok(()));
+        }
+
+        #[test]
+        fn space_search_ignores_time_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let designation = "Foo";
+            let buffers: Vec<[u8; 1]> = vec![[1], [2], [3], [4]];
+            let times = [-5.0e9, 0.0, 1.0, 3.0e7];
+            let mut metadata: Vec<Metadata> = buffers
+                .iter()
+                .zip(times.iter())
+                .map(|(buffer, t)| Metadata {
+                    xmin: 0.0,
+                    xmax: 1.0,
+                    ymin: 0.0,
+                    ymax: 1.0,
+                    zmin: 0.0,
+                    zmax: 1.0,
+                    tmin: *t,
+                    tmax: *t,
+                    designation,
+                    buffer,
+                })
+                .collect();
+            // Outside of the queried region
+            metadata.push(Metadata {
+                xmin: 5.0,
+                xmax: 6.0,
+                ..metadata[0].clone()
+            });
+            let _ = db.insert_spec_text(designation, "foo: u8");
+            let _ = db.insert_n_metadata(&metadata);
+
+            let result = db
+                .get_metadata_in_space(0.0, 1.0, 0.0, 1.0, 0.0, 1.0, designation, None)
+                .unwrap();
+            let mut found: Vec<u8> = result
+                .iter()
+                .map(|datum| datum.get_as::<u8>("foo").unwrap())
+                .collect();
+            found.sort();
+            pretty_assertions::assert_eq!(found, vec![1, 2, 3, 4]);
+        }
 
-    fn random_identifier(size: u8) -> String {
-        let mut rng = rand::thread_rng();
-        (0..size)
-            .map(|_| (rng.gen_range(b'a'..=b'z') as char))
-            .collect()
-    }
+        #[test]
+        fn bb_search_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
 
-    mod config {
-        use super::*;
+            let designation = "Foo";
+            let spec = "foo: u8, bar: f32";
+            let buffer: &[u8; 5] = &[100, 0, 0, 128, 63];
+            let md1 = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation,
+                buffer,
+            };
+
+            let buffer: &[u8; 5] = &[150, 0, 36, 116, 73];
+            let md2 = Metadata {
+                xmin: 0.0,
+                xmax: 1.0,
+                ymin: 0.0,
+                ymax: 1.0,
+                zmin: 0.0,
+                zmax: 1.0,
+                tmin: 0.0,
+                tmax: 1.0,
+                designation,
+                buffer,
+            };
+
+            let buffer: &[u8; 5] = &[200, 0, 0, 200, 194];
+            let md3 = Metadata {
+                xmin: 0.0,
+                xmax: 2.0,
+                ymin: 0.0,
+                ymax: 2.0,
+                zmin: 0.0,
+                zmax: 2.0,
+                tmin: 0.0,
+                tmax: 2.0,
+                designation,
+                buffer,
+            };
+
+            let metadata: Vec<Metadata> = vec![md1, md2, md3];
+
+            let _ = db.insert_spec_text(designation, spec);
+            let _ = db.insert_n_metadata(&metadata);
+
+            let result = db.get_metadata_in_bb(0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, "Foo", None);
+
+            let expected: Vec<HashMap<&str, DataValue>> = vec![
+                HashMap::from([
+                    ("foo", DataValue::Byte(100)),
+                    ("bar", DataValue::Float32(1.0)),
+                ]),
+                HashMap::from([
+                    ("foo", DataValue::Byte(150)),
+                    ("bar", DataValue::Float32(1000000.0)),
+                ]),
+            ];
+            pretty_assertions::assert_eq!(result.unwrap(), expected);
+        }
 
         #[test]
-        fn to_and_from_json_ok() {
-            let cfg = SqliteConfig::new().use_wal().page_size(2048);
-            let temp_file = TempFile::from("temp.json").unwrap();
-            let _ = cfg.to_json_file(&temp_file.filepath);
-            let recovered_cfg = SqliteConfig::from_json_file(&temp_file.filepath).unwrap();
-            pretty_assertions::assert_eq!(cfg, recovered_cfg);
+        fn get_metadata_unregistered_designation_empty() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &[1, 2]);
+            let result = db.get_metadata_in_bb(0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Bar", None);
+            pretty_assertions::assert_eq!(result, Ok(Vec::new()));
         }
-    }
 
-    mod database {
-        use super::*;
-        use crate::error::DatabaseError;
-        use elucidator::value::DataValue;
-        use std::{collections::HashSet, ops::Deref};
+        /// Insert `buffer` at the origin without checking it against `designation`
+        fn insert_point(db: &mut SqlDatabase, designation: &str, buffer: &[u8]) {
+            db.insert_metadata(&Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation,
+                buffer,
+            })
+            .unwrap();
+        }
+
+        fn insert_u64_records(db: &mut SqlDatabase, values: &[u64]) {
+            let buffers: Vec<[u8; 8]> = values.iter().map(|v| v.to_le_bytes()).collect();
+            let metadata: Vec<Metadata> = buffers
+                .iter()
+                .enumerate()
+                .map(|(i, buffer)| Metadata {
+                    xmin: i as f64,
+                    xmax: i as f64,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: 0.0,
+                    tmax: 0.0,
+                    designation: "Counter",
+                    buffer,
+                })
+                .collect();
+            let _ = db.insert_spec_text("Counter", "hits: u64");
+            let _ = db.insert_n_metadata(&metadata);
+        }
+
+        #[test]
+        fn aggregate_member_mean_and_max_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &[4, 10, 1, 5]);
+
+            let mean = db.aggregate_member(
+                0.0,
+                3.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                "Counter",
+                None,
+                "hits",
+                Aggregate::Mean,
+            );
+            pretty_assertions::assert_eq!(mean, Ok(5.0));
+            let max = db.aggregate_member(
+                0.0,
+                3.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                "Counter",
+                None,
+                "hits",
+                Aggregate::Max,
+            );
+            pretty_assertions::assert_eq!(max, Ok(10.0));
+            let max_subset = db.aggregate_member(
+                2.0,
+                3.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                "Counter",
+                None,
+                "hits",
+                Aggregate::Max,
+            );
+            pretty_assertions::assert_eq!(max_subset, Ok(5.0));
+        }
+
+        #[test]
+        fn member_near_filters_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "id: u8, temp: f32").unwrap();
+            let temps = [19.0f32, 20.4, 20.6, 21.5, -20.5];
+            let buffers: Vec<Vec<u8>> = temps
+                .iter()
+                .enumerate()
+                .map(|(i, t)| [vec![i as u8], t.to_le_bytes().to_vec()].concat())
+                .collect();
+            let data: Vec<Metadata> = buffers
+                .iter()
+                .map(|buffer| Metadata {
+                    xmin: 0.0,
+                    xmax: 0.0,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: 0.0,
+                    tmax: 0.0,
+                    designation: "Foo",
+                    buffer,
+                })
+                .collect();
+            db.insert_n_metadata(&data).unwrap();
+
+            let found = db
+                .get_metadata_where_member_near(
+                    0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", "temp", 20.5, 0.5,
+                )
+                .unwrap();
+            let ids: HashSet<u8> = found
+                .iter()
+                .map(|d| d.get_as::<u8>("id").unwrap())
+                .collect();
+            pretty_assertions::assert_eq!(ids, HashSet::from([1, 2]));
+        }
+
+        #[test]
+        fn distinct_member_values_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "mode: u8, temp: f32, xs: u8[]")
+                .unwrap();
+            let buffers: Vec<Vec<u8>> = [3u8, 1, 3, 2, 1, 3]
+                .iter()
+                .map(|mode| {
+                    [
+                        vec![*mode],
+                        (*mode as f32).to_le_bytes().to_vec(),
+                        1u64.to_le_bytes().to_vec(),
+                        vec![*mode],
+                    ]
+                    .concat()
+                })
+                .collect();
+            let data: Vec<Metadata> = buffers
+                .iter()
+                .map(|buffer| Metadata {
+                    xmin: 0.0,
+                    xmax: 0.0,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: 0.0,
+                    tmax: 0.0,
+                    designation: "Foo",
+                    buffer,
+                })
+                .collect();
+            db.insert_n_metadata(&data).unwrap();
+
+            let distinct = db
+                .distinct_member_values(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None, "mode")
+                .unwrap();
+            let modes: HashSet<u8> = distinct
+                .iter()
+                .map(|value| match value {
+                    DataValue::Byte(mode) => *mode,
+                    other => panic!("unexpected value {other:?}"),
+                })
+                .collect();
+            pretty_assertions::assert_eq!(distinct.len(), 3);
+            pretty_assertions::assert_eq!(modes, HashSet::from([1, 2, 3]));
+
+            for member in ["xs", "missing"] {
+                let result = db.distinct_member_values(
+                    0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None, member,
+                );
+                assert!(matches!(
+                    result,
+                    Err(DatabaseError::SpecificationError { .. })
+                ));
+            }
+        }
+
+        #[test]
+        fn member_near_non_numeric_fails() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "name: string, xs: f64[]")
+                .unwrap();
+            for member in ["name", "xs", "missing"] {
+                let result = db.get_metadata_where_member_near(
+                    0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", member, 0.0, 1.0,
+                );
+                assert!(matches!(
+                    result,
+                    Err(DatabaseError::SpecificationError { .. })
+                ));
+            }
+        }
+
+        #[test]
+        fn aggregate_member_non_numeric_fails() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let _ = db.insert_spec_text("Foo", "name: string, hits: u64[], grade: char");
+            let buffer = db.designations["Foo"]
+                .pack(&HashMap::from([
+                    ("name", DataValue::Str("a".to_string())),
+                    ("hits", DataValue::UnsignedInteger64Array(vec![1])),
+                    ("grade", DataValue::Char('A')),
+                ]))
+                .unwrap();
+            db.insert_metadata(&Metadata {
+                designation: "Foo",
+                buffer: &buffer,
+                xmin: 0.5,
+                xmax: 0.5,
+                ymin: 0.5,
+                ymax: 0.5,
+                zmin: 0.5,
+                zmax: 0.5,
+                tmin: 0.5,
+                tmax: 0.5,
+            })
+            .unwrap();
+            for member in ["name", "hits", "grade", "missing"] {
+                let result = db.aggregate_member(
+                    0.0,
+                    1.0,
+                    0.0,
+                    1.0,
+                    0.0,
+                    1.0,
+                    0.0,
+                    1.0,
+                    "Foo",
+                    None,
+                    member,
+                    Aggregate::Sum,
+                );
+                assert!(
+                    matches!(result, Err(DatabaseError::SpecificationError { .. })),
+                    "{member}: {result:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn insert_n_returning_ids_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let designation = "Foo";
+            let _ = db.insert_spec_text(designation, "foo: u8");
+            let buffers: Vec<[u8; 1]> = vec![[10], [20], [30]];
+            let metadata: Vec<Metadata> = buffers
+                .iter()
+                .map(|buffer| Metadata {
+                    xmin: 0.0,
+                    xmax: 0.0,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: 0.0,
+                    tmax: 0.0,
+                    designation,
+                    buffer,
+                })
+                .collect();
+
+            let ids = db.insert_n_metadata_returning_ids(&metadata).unwrap();
+
+            pretty_assertions::assert_eq!(ids.len(), 3);
+            assert!(ids.windows(2).all(|w| w[0] < w[1]), "{ids:?}");
+            let conn = db.conn.lock().unwrap();
+            for (id, buffer) in ids.iter().zip(buffers.iter()) {
+                let stored: Vec<u8> = conn
+                    .query_row("SELECT buffer FROM Metadata WHERE id = ?1", [id], |row| {
+                        row.get(0)
+                    })
+                    .unwrap();
+                pretty_assertions::assert_eq!(stored, buffer.to_vec());
+            }
+        }
+
+        #[test]
+        fn paged_search_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &(0..10).collect::<Vec<u64>>());
+
+            let hits = |limit, offset| -> Vec<u64> {
+                db.get_metadata_in_bb_paged(
+                    0.0, 9.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Counter", None, limit, offset,
+                )
+                .unwrap()
+                .iter()
+                .map(|datum| datum.get_as::<u64>("hits").unwrap())
+                .collect()
+            };
+            let first = hits(5, 0);
+            let second = hits(5, 5);
+            pretty_assertions::assert_eq!(first.len(), 5);
+            pretty_assertions::assert_eq!(second.len(), 5);
+            let combined: HashSet<u64> = first.iter().chain(second.iter()).copied().collect();
+            pretty_assertions::assert_eq!(combined, (0..10).collect::<HashSet<u64>>());
+            pretty_assertions::assert_eq!(hits(5, 10), Vec::<u64>::new());
+        }
+
+        #[test]
+        fn paged_search_unregistered_designation_empty() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_point(&mut db, "Ghost", &[1]);
+            let found = db.get_metadata_in_bb_paged(
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Ghost", None, 5, 0,
+            );
+            pretty_assertions::assert_eq!(found, Ok(Vec::new()));
+        }
+
+        #[test]
+        fn paged_search_corrupt_blob_err() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &[1]);
+            insert_point(&mut db, "Counter", &[1, 2, 3]);
+            let found = db.get_metadata_in_bb_paged(
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Counter", None, 5, 0,
+            );
+            assert!(
+                matches!(found, Err(DatabaseError::ElucidatorError { .. })),
+                "{found:?}"
+            );
+        }
+
+        #[test]
+        fn limited_search_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &(0..100).collect::<Vec<u64>>());
+
+            let found = db
+                .get_metadata_in_bb_limited(
+                    0.0, 99.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Counter", None, 5,
+                )
+                .unwrap();
+            pretty_assertions::assert_eq!(found.len(), 5);
+            let hits: HashSet<u64> = found
+                .iter()
+                .map(|datum| datum.get_as::<u64>("hits").unwrap())
+                .collect();
+            pretty_assertions::assert_eq!(hits.len(), 5);
+            assert!(hits.iter().all(|hit| *hit < 100));
+            let all = db
+                .get_metadata_in_bb_limited(
+                    0.0, 99.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Counter", None, 1000,
+                )
+                .unwrap();
+            pretty_assertions::assert_eq!(all.len(), 100);
+        }
+
+        #[test]
+        fn limited_search_unregistered_designation_empty() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_point(&mut db, "Ghost", &[1]);
+            let found = db.get_metadata_in_bb_limited(
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Ghost", None, 5,
+            );
+            pretty_assertions::assert_eq!(found, Ok(Vec::new()));
+        }
+
+        #[test]
+        fn limited_search_corrupt_blob_err() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &[1]);
+            insert_point(&mut db, "Counter", &[1, 2, 3]);
+            let found = db.get_metadata_in_bb_limited(
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Counter", None, 5,
+            );
+            assert!(
+                matches!(found, Err(DatabaseError::ElucidatorError { .. })),
+                "{found:?}"
+            );
+        }
+
+        #[test]
+        fn large_search_matches_serial_decode() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &(0..5000).collect::<Vec<u64>>());
+            let spec = db.designations.get("Counter").unwrap();
+            let bounds = [0.0, 4999.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+
+            let mut serial = Vec::new();
+            db.for_each_blob_in_bb(bounds, "Counter", None, RowWindow::All, |_, buffer| {
+                serial.push(Datum::new(spec, spec.interpret_enum(buffer)?));
+                Ok(())
+            })
+            .unwrap();
+            let found = db
+                .get_metadata_in_bb(0.0, 4999.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Counter", None)
+                .unwrap();
+            pretty_assertions::assert_eq!(found.len(), 5000);
+            pretty_assertions::assert_eq!(found, serial);
+        }
+
+        #[test]
+        fn negative_epsilon_excludes_boundary() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &[10, 11, 12, 13]);
+            let hits = |epsilon| {
+                db.get_metadata_in_bb(0.0, 3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Counter", epsilon)
+                    .map(|found| {
+                        let mut hits: Vec<u64> =
+                            found.iter().map(|d| d.get_as("hits").unwrap()).collect();
+                        hits.sort();
+                        hits
+                    })
+            };
+            pretty_assertions::assert_eq!(hits(None), Ok(vec![10, 11, 12, 13]));
+            // The y, z and t bounds are single points, which any negative epsilon collapses
+            assert!(matches!(
+                hits(Some(-0.5)),
+                Err(DatabaseError::InvalidQueryBox { .. })
+            ));
+
+            let found = db
+                .get_metadata_in_bb(
+                    0.0,
+                    3.0,
+                    -1.0,
+                    1.0,
+                    -1.0,
+                    1.0,
+                    -1.0,
+                    1.0,
+                    "Counter",
+                    Some(-0.5),
+                )
+                .unwrap();
+            let mut hits: Vec<u64> = found.iter().map(|d| d.get_as("hits").unwrap()).collect();
+            hits.sort();
+            pretty_assertions::assert_eq!(hits, vec![11, 12]);
+        }
+
+        #[test]
+        fn negative_epsilon_over_shrink_fails() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &[10, 11, 12, 13]);
+            assert!(matches!(
+                db.get_metadata_in_bb(
+                    0.0,
+                    3.0,
+                    -1.0,
+                    1.0,
+                    -1.0,
+                    1.0,
+                    -1.0,
+                    1.0,
+                    "Counter",
+                    Some(-1.5)
+                ),
+                Err(DatabaseError::InvalidQueryBox { .. })
+            ));
+        }
+
+        #[test]
+        fn cancellable_search_completes_unset() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &(0..10).collect::<Vec<u64>>());
+            let found = db
+                .get_metadata_in_bb_cancellable(
+                    0.0,
+                    9.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    "Counter",
+                    None,
+                    &AtomicBool::new(false),
+                )
+                .unwrap();
+            pretty_assertions::assert_eq!(found.len(), 10);
+        }
+
+        #[test]
+        fn cancellable_search_cancelled_mid_query() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &(0..10).collect::<Vec<u64>>());
+            // Stand in for another thread setting the flag once five records are decoded
+            let mut checked = 0;
+            let result = db.decode_blobs_until_cancelled(
+                [0.0, 9.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                "Counter",
+                None,
+                || {
+                    checked += 1;
+                    checked > 5
+                },
+            );
+            assert!(matches!(result, Err(DatabaseError::Cancelled)));
+            pretty_assertions::assert_eq!(checked, 6);
+        }
+
+        #[test]
+        fn cancellable_search_cancelled_before_query() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &[1, 2]);
+            let result = db.get_metadata_in_bb_cancellable(
+                0.0,
+                1.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                "Counter",
+                None,
+                &AtomicBool::new(true),
+            );
+            assert!(matches!(result, Err(DatabaseError::Cancelled)));
+        }
+
+        #[test]
+        fn register_prebuilt_spec_ok() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let mut db = SqlDatabase::new(Some(&tempfile.filepath), None).unwrap();
+            let spec = DesignationSpecification::from_text("foo: u16, bar: f32[2]").unwrap();
+            db.register_designation("Foo", spec.clone()).unwrap();
+
+            let buffer: Vec<u8> = [7u16.to_le_bytes().to_vec(), vec![0; 8]].concat();
+            let md = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation: "Foo",
+                buffer: &buffer,
+            };
+            db.insert_metadata_with_spec(&md, &spec).unwrap();
+
+            let found = db
+                .get_metadata_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None)
+                .unwrap();
+            pretty_assertions::assert_eq!(found.len(), 1);
+            pretty_assertions::assert_eq!(found[0].get_as::<u16>("foo"), Ok(7));
+
+            drop(found);
+            drop(db);
+            let reloaded = SqlDatabase::from_path(&tempfile.filepath).unwrap();
+            pretty_assertions::assert_eq!(reloaded.designations.get("Foo"), Some(&spec));
+        }
+
+        #[test]
+        fn insert_with_conflicting_spec_fails() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u8").unwrap();
+            let spec = DesignationSpecification::from_text("foo: u16").unwrap();
+            let buffer = [0u8; 2];
+            let md = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation: "Foo",
+                buffer: &buffer,
+            };
+            assert!(matches!(
+                db.insert_metadata_with_spec(&md, &spec),
+                Err(DatabaseError::SpecificationError { .. })
+            ));
+        }
+
+        #[test]
+        fn repr_search_converts_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u32").unwrap();
+            let buffer = 70000u32.to_le_bytes();
+            let md = Metadata {
+                xmin: 0.0,
+                xmax: 0.0,
+                ymin: 0.0,
+                ymax: 0.0,
+                zmin: 0.0,
+                zmax: 0.0,
+                tmin: 0.0,
+                tmax: 0.0,
+                designation: "Foo",
+                buffer: &buffer,
+            };
+            db.insert_metadata(&md).unwrap();
 
-        #[test]
-        fn create_in_memory_ok() {
-            assert!(SqlDatabase::new(None, None).is_ok());
+            let found = db
+                .get_metadata_repr_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None)
+                .unwrap();
+            pretty_assertions::assert_eq!(found.len(), 1);
+            pretty_assertions::assert_eq!(found[0]["foo"].as_i64(), Ok(70000));
         }
 
         #[test]
-        fn create_with_filepath_ok() {
-            let tempfile = TempFile::from("temp.db").unwrap();
-            let db = SqlDatabase::new(Some(&tempfile.filepath), None);
-            assert!(db.is_ok());
+        fn repr_search_unregistered_designation_empty() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_point(&mut db, "Ghost", &[1]);
+            let found = db
+                .get_metadata_repr_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Ghost", None)
+                .unwrap();
+            assert!(found.is_empty());
         }
 
         #[test]
-        fn from_empty_ok() {
-            let tempfile = TempFile::from("temp.db").unwrap();
-            let db = SqlDatabase::new(Some(&tempfile.filepath), None);
-            drop(db);
-            let loaded_db = SqlDatabase::from_path(&tempfile.filepath);
-            assert!(loaded_db.is_ok());
+        fn bucketed_by_time_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u8").unwrap();
+            let times = [0.0, 4.5, 5.0, 9.9, 12.0, -0.5];
+            let buffers: Vec<[u8; 1]> = (0..times.len() as u8).map(|i| [i]).collect();
+            let data: Vec<Metadata> = times
+                .iter()
+                .zip(&buffers)
+                .map(|(t, buffer)| Metadata {
+                    xmin: 0.0,
+                    xmax: 0.0,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: *t,
+                    tmax: *t,
+                    designation: "Foo",
+                    buffer,
+                })
+                .collect();
+            db.insert_n_metadata(&data).unwrap();
+
+            let buckets = db
+                .get_metadata_bucketed_by_time(
+                    0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -10.0, 20.0, "Foo", None, 5.0,
+                )
+                .unwrap();
+            let members: BTreeMap<i64, HashSet<u8>> = buckets
+                .iter()
+                .map(|(k, data)| {
+                    (
+                        *k,
+                        data.iter()
+                            .map(|d| d.get_as::<u8>("foo").unwrap())
+                            .collect(),
+                    )
+                })
+                .collect();
+            pretty_assertions::assert_eq!(
+                members,
+                BTreeMap::from([
+                    (-1, HashSet::from([5])),
+                    (0, HashSet::from([0, 1])),
+                    (1, HashSet::from([2, 3])),
+                    (2, HashSet::from([4])),
+                ])
+            );
         }
 
         #[test]
-        fn insert_designation_ok() {
-            let mut db = SqlDatabase::new(None, None).unwrap();
-            let designation = "Foo";
-            let spec = "foo: u8";
-            let result = db.insert_spec_text(designation, spec);
-            pretty_assertions::assert_eq!(result, Ok(()));
-            let keys = db
-                .designations
-                .keys()
-                .map(String::deref)
-                .collect::<HashSet<&str>>();
-            pretty_assertions::assert_eq!(keys, HashSet::from(["Foo"]));
+        fn bucketed_by_time_zero_width_fails() {
+            let db = SqlDatabase::new(None, None).unwrap();
+            let result = db.get_metadata_bucketed_by_time(
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None, 0.0,
+            );
+            assert!(matches!(result, Err(DatabaseError::ConfigError { .. })));
         }
 
         #[test]
-        fn insert_1_ok() {
+        fn bucketed_by_time_unregistered_designation_empty() {
             let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_point(&mut db, "Ghost", &[1]);
+            let result = db.get_metadata_bucketed_by_time(
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Ghost", None, 1.0,
+            );
+            pretty_assertions::assert_eq!(result, Ok(BTreeMap::new()));
+        }
 
-            let buffer: &[u8; 1] = &[100; 1];
-            let designation = "Foo";
-            let spec = "foo: u8";
-            let md = Metadata {
+        fn nan_metadata(buffer: &[u8]) -> Metadata<'_> {
+            Metadata {
                 xmin: 0.0,
                 xmax: 0.0,
                 ymin: 0.0,
@@ -508,43 +2514,438 @@ mod test {
                 zmax: 0.0,
                 tmin: 0.0,
                 tmax: 0.0,
-                designation,
+                designation: "Foo",
                 buffer,
-            };
+            }
+        }
 
-            let _ = db.insert_spec_text(designation, spec);
-            let result = db.insert_metadata(&md);
+        #[test]
+        fn reject_non_finite_floats_fails() {
+            let config =
+                DatabaseConfig::SqliteConfig(SqliteConfig::new().reject_non_finite_floats());
+            let mut db = SqlDatabase::new(None, Some(&config)).unwrap();
+            let _ = db.insert_spec_text("Foo", "foo: u8, bar: f64");
+            let buffer: Vec<u8> = [7u8].into_iter().chain(f64::NAN.to_le_bytes()).collect();
+
+            let result = db.insert_metadata(&nan_metadata(&buffer));
+            pretty_assertions::assert_eq!(
+                result,
+                Err(DatabaseError::NonFiniteValue {
+                    designation: "Foo".to_string(),
+                    member: "bar".to_string(),
+                })
+            );
+            let result = db.insert_n_metadata(&[nan_metadata(&buffer)]);
+            assert!(matches!(result, Err(DatabaseError::NonFiniteValue { .. })));
+            pretty_assertions::assert_eq!(db.get_all_metadata().unwrap(), vec![]);
+        }
+
+        #[test]
+        fn allow_non_finite_floats_by_default_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            let _ = db.insert_spec_text("Foo", "foo: u8, bar: f64");
+            let buffer: Vec<u8> = [7u8].into_iter().chain(f64::NAN.to_le_bytes()).collect();
 
+            let result = db.insert_metadata(&nan_metadata(&buffer));
             pretty_assertions::assert_eq!(result, Ok(()));
         }
 
         #[test]
-        fn insert_bad_designation_fails() {
+        fn export_ndjson_ok() {
             let mut db = SqlDatabase::new(None, None).unwrap();
             let designation = "Foo";
-            let spec = "foo u8";
-            let result = db.insert_spec_text(designation, spec);
-            let expected = DesignationSpecification::from_text(spec);
-            assert!(
-                expected.is_err(),
-                "Expected an error from bad designation spec, but got ok instead."
-            );
+            let _ = db.insert_spec_text(designation, "name: string, xs: f64[]");
+            let buffers: Vec<Vec<u8>> = [("a", vec![1.5]), ("b", vec![])]
+                .into_iter()
+                .map(|(name, xs)| {
+                    let mut buffer = DataValue::Str(name.to_string()).as_buffer();
+                    buffer.extend((xs.len() as u64).to_le_bytes());
+                    buffer.extend(DataValue::Float64Array(xs).as_buffer());
+                    buffer
+                })
+                .collect();
+            let metadata: Vec<Metadata> = buffers
+                .iter()
+                .map(|buffer| Metadata {
+                    xmin: 0.0,
+                    xmax: 0.0,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: 0.0,
+                    tmax: 0.0,
+                    designation,
+                    buffer,
+                })
+                .collect();
+            let _ = db.insert_n_metadata(&metadata);
+
+            let mut output: Vec<u8> = Vec::new();
+            let count = db.export_ndjson(designation, &mut output);
+            pretty_assertions::assert_eq!(count, Ok(2));
+
+            let lines: Vec<serde_json::Value> = String::from_utf8(output)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            let expected = [
+                serde_json::json!({"name": "a", "xs": [1.5]}),
+                serde_json::json!({"name": "b", "xs": []}),
+            ];
+            let found: HashSet<String> = lines.iter().map(|v| v.to_string()).collect();
             pretty_assertions::assert_eq!(
-                result,
-                Err(DatabaseError::ElucidatorError {
-                    reason: expected.unwrap_err()
+                found,
+                expected
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<HashSet<String>>()
+            );
+        }
+
+        #[test]
+        fn verify_all_reports_corrupt_record() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u32").unwrap();
+            let good = 1u32.to_le_bytes();
+            let corrupt = [1u8, 2];
+            for buffer in [&good[..], &corrupt[..]] {
+                db.insert_metadata(&Metadata {
+                    xmin: 0.0,
+                    xmax: 0.0,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: 0.0,
+                    tmax: 0.0,
+                    designation: "Foo",
+                    buffer,
+                })
+                .unwrap();
+            }
+            let failures = db.verify_all().unwrap();
+            pretty_assertions::assert_eq!(failures.len(), 1);
+            pretty_assertions::assert_eq!(failures[0].designation, "Foo");
+            assert!(matches!(
+                failures[0].error,
+                DatabaseError::ElucidatorError {
+                    reason: elucidator::error::ElucidatorError::BufferSizing { .. }
+                }
+            ));
+        }
+
+        #[test]
+        fn box_overloads_ok() {
+            use crate::database::BoundingBox;
+
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u32").unwrap();
+            let inside = BoundingBox::from_points([0.0, 0.0, 0.0, 0.0], [1.0, 1.0, 1.0, 1.0]);
+            let outside = BoundingBox::from_points([5.0, 5.0, 5.0, 5.0], [6.0, 6.0, 6.0, 6.0]);
+            db.insert_metadata_box(&inside, "Foo", &1u32.to_le_bytes())
+                .unwrap();
+            db.insert_metadata_box(&outside, "Foo", &2u32.to_le_bytes())
+                .unwrap();
+
+            let query = BoundingBox::from_bounds(-1.0, 2.0, -1.0, 2.0, -1.0, 2.0, -1.0, 2.0);
+            let found = db.get_metadata_in_box(&query, "Foo", None).unwrap();
+            pretty_assertions::assert_eq!(found.len(), 1);
+            pretty_assertions::assert_eq!(found[0].get_as::<u32>("foo"), Ok(1));
+        }
+
+        #[test]
+        fn storage_bytes_by_designation_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Small", "a: u16").unwrap();
+            db.insert_spec_text("Large", "a: f64[]").unwrap();
+            let small = 1u16.to_le_bytes().to_vec();
+            let mut large = 3u64.to_le_bytes().to_vec();
+            large.extend([0u8; 24]);
+            let records = [
+                ("Small", &small),
+                ("Small", &small),
+                ("Small", &small),
+                ("Large", &large),
+                ("Large", &large),
+            ];
+            let data: Vec<Metadata> = records
+                .iter()
+                .map(|(designation, buffer)| Metadata {
+                    xmin: 0.0,
+                    xmax: 0.0,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: 0.0,
+                    tmax: 0.0,
+                    designation,
+                    buffer,
+                })
+                .collect();
+            db.insert_n_metadata(&data).unwrap();
+            let expected = HashMap::from([("Small".to_string(), 6), ("Large".to_string(), 64)]);
+            pretty_assertions::assert_eq!(db.storage_bytes_by_designation(), Ok(expected));
+        }
+
+        #[test]
+        fn bbox_extent_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "a: u16").unwrap();
+            db.insert_spec_text("Bar", "a: u16").unwrap();
+            db.insert_spec_text("Empty", "a: u16").unwrap();
+            let buffer = 1u16.to_le_bytes().to_vec();
+            let boxes = [
+                ("Foo", [-3.0, -1.0, 2.0, 4.0, 0.0, 0.5, 10.0, 12.0]),
+                ("Foo", [1.0, 5.0, -6.0, -2.0, 1.0, 1.0, 8.0, 9.0]),
+                ("Foo", [0.0, 0.0, 0.0, 0.0, -0.25, 0.0, 11.0, 20.0]),
+                (
+                    "Bar",
+                    [100.0, 200.0, 100.0, 200.0, 100.0, 200.0, 100.0, 200.0],
+                ),
+            ];
+            let data: Vec<Metadata> = boxes
+                .iter()
+                .map(|(designation, b)| Metadata {
+                    xmin: b[0],
+                    xmax: b[1],
+                    ymin: b[2],
+                    ymax: b[3],
+                    zmin: b[4],
+                    zmax: b[5],
+                    tmin: b[6],
+                    tmax: b[7],
+                    designation,
+                    buffer: &buffer,
                 })
+                .collect();
+            db.insert_n_metadata(&data).unwrap();
+            pretty_assertions::assert_eq!(
+                db.bbox_extent("Foo"),
+                Ok(Some(BoundingBox::from_bounds(
+                    -3.0, 5.0, -6.0, 4.0, -0.25, 1.0, 8.0, 20.0
+                )))
             );
+            pretty_assertions::assert_eq!(db.bbox_extent("Empty"), Ok(None));
+            pretty_assertions::assert_eq!(db.bbox_extent("Missing"), Ok(None));
         }
 
         #[test]
-        fn insert_n_ok() {
+        fn all_metadata_for_designation_in_order() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u32").unwrap();
+            db.insert_spec_text("Bar", "bar: u8").unwrap();
+            let foo_buffers: Vec<[u8; 4]> = [30u32, 10, 20].map(u32::to_le_bytes).to_vec();
+            let bar_buffer = [7u8];
+            for (i, buffer) in foo_buffers.iter().enumerate() {
+                // Spread the records out so location order differs from insertion order
+                let x = 10.0 - i as f64;
+                for (designation, buffer) in [("Foo", &buffer[..]), ("Bar", &bar_buffer[..])] {
+                    db.insert_metadata(&Metadata {
+                        xmin: x,
+                        xmax: x,
+                        ymin: 0.0,
+                        ymax: 0.0,
+                        zmin: 0.0,
+                        zmax: 0.0,
+                        tmin: 0.0,
+                        tmax: 0.0,
+                        designation,
+                        buffer,
+                    })
+                    .unwrap();
+                }
+            }
+            let found: Vec<u32> = db
+                .get_all_metadata_for_designation("Foo")
+                .unwrap()
+                .iter()
+                .map(|datum| datum.get_as::<u32>("foo").unwrap())
+                .collect();
+            pretty_assertions::assert_eq!(found, vec![30, 10, 20]);
+
+            let mut count = 0;
+            db.for_each_metadata_for_designation("Bar", |datum| {
+                pretty_assertions::assert_eq!(datum.get_as::<u8>("bar"), Ok(7));
+                count += 1;
+                Ok(())
+            })
+            .unwrap();
+            pretty_assertions::assert_eq!(count, 3);
+        }
+
+        #[test]
+        fn batch_inserter_flushes_all_ok() {
+            let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Foo", "foo: u32").unwrap();
+            let buffers: Vec<[u8; 4]> = (0..25u32).map(u32::to_le_bytes).collect();
+            let mut inserter = db.batch_inserter(10);
+            for (i, buffer) in buffers.iter().enumerate() {
+                inserter
+                    .push(&Metadata {
+                        xmin: i as f64,
+                        xmax: i as f64,
+                        ymin: 0.0,
+                        ymax: 0.0,
+                        zmin: 0.0,
+                        zmax: 0.0,
+                        tmin: 0.0,
+                        tmax: 0.0,
+                        designation: "Foo",
+                        buffer,
+                    })
+                    .unwrap();
+            }
+            pretty_assertions::assert_eq!(inserter.pending(), 5);
+            inserter.finish().unwrap();
+            let mut found: Vec<u32> = db
+                .get_metadata_in_bb(0.0, 24.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None)
+                .unwrap()
+                .iter()
+                .map(|datum| datum.get_as::<u32>("foo").unwrap())
+                .collect();
+            found.sort();
+            pretty_assertions::assert_eq!(found, (0..25).collect::<Vec<u32>>());
+        }
+
+        #[test]
+        fn verify_all_healthy_empty() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let db = checksummed_db(&tempfile.filepath);
+            pretty_assertions::assert_eq!(db.verify_all(), Ok(Vec::new()));
+        }
+
+        #[test]
+        fn verify_all_reports_checksum_mismatch() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let db = checksummed_db(&tempfile.filepath);
+            let id: i64 = {
+                let conn = db.conn.lock().unwrap();
+                let id = conn
+                    .query_row("SELECT id FROM Metadata ORDER BY id LIMIT 1", [], |r| {
+                        r.get(0)
+                    })
+                    .unwrap();
+                // Still a valid u32, so only the checksum can catch it
+                conn.execute(
+                    "UPDATE Metadata SET buffer = ?2 WHERE id = ?1",
+                    (id, 99u32.to_le_bytes().to_vec()),
+                )
+                .unwrap();
+                id
+            };
+            pretty_assertions::assert_eq!(
+                db.verify_all(),
+                Ok(vec![VerificationError {
+                    id,
+                    designation: "Foo".to_string(),
+                    error: DatabaseError::ChecksumMismatch { id },
+                }])
+            );
+        }
+
+        #[test]
+        fn verify_all_reports_non_blob_buffer() {
             let mut db = SqlDatabase::new(None, None).unwrap();
+            insert_u64_records(&mut db, &[1, 2]);
+            {
+                let conn = db.conn.lock().unwrap();
+                conn.execute(
+                    "UPDATE Metadata SET buffer = 'text' WHERE id = (SELECT MIN(id) FROM Metadata)",
+                    [],
+                )
+                .unwrap();
+            }
+            let failures = db.verify_all().unwrap();
+            pretty_assertions::assert_eq!(failures.len(), 1);
+            assert!(
+                matches!(failures[0].error, DatabaseError::RusqliteError { .. }),
+                "{failures:?}"
+            );
+        }
+
+        fn checksummed_db(filepath: &str) -> SqlDatabase {
+            let cfg = DatabaseConfig::SqliteConfig(SqliteConfig::new().store_checksums());
+            let mut db = SqlDatabase::new(Some(filepath), Some(&cfg)).unwrap();
+            db.insert_spec_text("Foo", "foo: u32").unwrap();
+            let buffers: Vec<[u8; 4]> = [1u32, 2, 3].map(u32::to_le_bytes).to_vec();
+            let data: Vec<Metadata> = buffers
+                .iter()
+                .map(|buffer| Metadata {
+                    xmin: 0.0,
+                    xmax: 0.0,
+                    ymin: 0.0,
+                    ymax: 0.0,
+                    zmin: 0.0,
+                    zmax: 0.0,
+                    tmin: 0.0,
+                    tmax: 0.0,
+                    designation: "Foo",
+                    buffer,
+                })
+                .collect();
+            db.insert_n_metadata(&data).unwrap();
+            db
+        }
+
+        #[test]
+        fn checksums_verify_ok() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let db = checksummed_db(&tempfile.filepath);
+            let found = db
+                .get_metadata_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None)
+                .unwrap();
+            pretty_assertions::assert_eq!(found.len(), 3);
+        }
+
+        #[test]
+        fn corrupted_blob_checksum_mismatch() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let db = checksummed_db(&tempfile.filepath);
+            let id: i64 = {
+                let conn = db.conn.lock().unwrap();
+                let id = conn
+                    .query_row(
+                        "SELECT id FROM Metadata ORDER BY id LIMIT 1 OFFSET 1",
+                        [],
+                        |r| r.get(0),
+                    )
+                    .unwrap();
+                conn.execute(
+                    "UPDATE Metadata SET buffer = ?2 WHERE id = ?1",
+                    (id, 99u32.to_le_bytes().to_vec()),
+                )
+                .unwrap();
+                id
+            };
+            let result = db.get_metadata_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None);
+            pretty_assertions::assert_eq!(
+                result.map(|found| found.len()),
+                Err(DatabaseError::ChecksumMismatch { id })
+            );
+
+            // Verification is remembered when the database is reopened
+            drop(db);
+            let reopened = SqlDatabase::from_path(&tempfile.filepath).unwrap();
+            assert!(matches!(
+                reopened.get_metadata_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None),
+                Err(DatabaseError::ChecksumMismatch { .. })
+            ));
+        }
+
+        #[test]
+        fn checkpoint_wal_ok() {
+            let tempfile = TempFile::new().unwrap();
+            let copyfile = TempFile::from("copy.db").unwrap();
+            let cfg = DatabaseConfig::SqliteConfig(SqliteConfig::new().use_wal());
+            let mut db = SqlDatabase::new(Some(&tempfile.filepath), Some(&cfg)).unwrap();
 
             let designation = "Foo";
             let spec = "foo: u8";
             let buffer: &[u8; 1] = &[100; 1];
-            let md1 = Metadata {
+            let md = Metadata {
                 xmin: 0.0,
                 xmax: 0.0,
                 ymin: 0.0,
@@ -557,108 +2958,178 @@ mod test {
                 buffer,
             };
 
-            let buffer: &[u8; 1] = &[150; 1];
-            let md2 = Metadata {
-                xmin: 0.0,
-                xmax: 0.0,
-                ymin: 0.0,
-                ymax: 0.0,
-                zmin: 0.0,
-                zmax: 0.0,
-                tmin: 0.0,
-                tmax: 0.0,
-                designation,
-                buffer,
-            };
+            let _ = db.insert_spec_text(designation, spec);
+            let _ = db.insert_metadata(&md);
+            pretty_assertions::assert_eq!(db.checkpoint(), Ok(()));
 
-            let buffer: &[u8; 1] = &[200; 1];
-            let md3 = Metadata {
-                xmin: 0.0,
-                xmax: 0.0,
+            std::fs::copy(&tempfile.filepath, &copyfile.filepath).unwrap();
+            let copied = SqlDatabase::from_path(&copyfile.filepath).unwrap();
+            let keys = copied
+                .designations
+                .keys()
+                .map(String::deref)
+                .collect::<HashSet<&str>>();
+            pretty_assertions::assert_eq!(keys, HashSet::from(["Foo"]));
+            let recovered = copied.get_all_metadata().unwrap();
+            pretty_assertions::assert_eq!(recovered, vec![MetadataClone::from(&md)]);
+        }
+    }
+
+    mod statement_logging {
+        use super::*;
+        use std::sync::OnceLock;
+
+        /// Collects every logged message so tests can inspect them
+        struct CaptureLogger {
+            messages: Mutex<Vec<String>>,
+        }
+
+        impl log::Log for CaptureLogger {
+            fn enabled(&self, _metadata: &log::Metadata) -> bool {
+                true
+            }
+            fn log(&self, record: &log::Record) {
+                self.messages
+                    .lock()
+                    .unwrap()
+                    .push(record.args().to_string());
+            }
+            fn flush(&self) {}
+        }
+
+        fn logger() -> &'static CaptureLogger {
+            static LOGGER: OnceLock<CaptureLogger> = OnceLock::new();
+            LOGGER.get_or_init(|| {
+                let logger = CaptureLogger {
+                    messages: Mutex::new(Vec::new()),
+                };
+                log::set_max_level(log::LevelFilter::Debug);
+                logger
+            })
+        }
+
+        #[test]
+        fn insert_and_query_logged() {
+            let _ = log::set_logger(logger());
+            let cfg = DatabaseConfig::SqliteConfig(SqliteConfig::new().log_statements());
+            let mut db = SqlDatabase::new(None, Some(&cfg)).unwrap();
+            db.insert_spec_text("Logged", "foo: u32").unwrap();
+            let buffer = 7u32.to_le_bytes();
+            let md = Metadata {
+                xmin: 1.5,
+                xmax: 2.5,
                 ymin: 0.0,
                 ymax: 0.0,
                 zmin: 0.0,
                 zmax: 0.0,
                 tmin: 0.0,
                 tmax: 0.0,
-                designation,
-                buffer,
+                designation: "Logged",
+                buffer: &buffer,
             };
+            db.insert_metadata(&md).unwrap();
+            db.get_metadata_in_bb(0.0, 3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Logged", None)
+                .unwrap();
 
-            let metadata: Vec<Metadata> = vec![md1, md2, md3];
-
-            let _ = db.insert_spec_text(designation, spec);
-            let result = db.insert_n_metadata(&metadata);
-
-            pretty_assertions::assert_eq!(result, Ok(()));
+            let messages = logger().messages.lock().unwrap();
+            assert!(messages
+                .iter()
+                .any(|m| m.starts_with("INSERT INTO MetadataLocations")
+                    && m.contains("[1.5, 2.5, 0.0")));
+            assert!(messages
+                .iter()
+                .any(|m| m.starts_with("INSERT INTO Metadata ")
+                    && m.contains("[\"Logged\", <4 bytes>]")));
+            assert!(messages
+                .iter()
+                .any(|m| m.starts_with("SELECT") && m.contains("\"Logged\"]")));
         }
 
         #[test]
-        fn bb_search_ok() {
+        fn unconfigured_not_logged() {
+            let _ = log::set_logger(logger());
             let mut db = SqlDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Quiet", "foo: u8").unwrap();
+            db.get_metadata_in_bb(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Quiet", None)
+                .unwrap();
+            let messages = logger().messages.lock().unwrap();
+            assert!(!messages.iter().any(|m| m.contains("\"Quiet\"")));
+        }
+    }
 
-            let designation = "Foo";
-            let spec = "foo: u8, bar: f32";
-            let buffer: &[u8; 5] = &[100, 0, 0, 128, 63];
-            let md1 = Metadata {
-                xmin: 0.0,
-                xmax: 0.0,
+    mod pool {
+        use super::*;
+
+        fn metadata_at(x: f64, buffer: &[u8]) -> Metadata<'_> {
+            Metadata {
+                xmin: x,
+                xmax: x,
                 ymin: 0.0,
                 ymax: 0.0,
                 zmin: 0.0,
                 zmax: 0.0,
                 tmin: 0.0,
                 tmax: 0.0,
-                designation,
-                buffer,
-            };
-
-            let buffer: &[u8; 5] = &[150, 0, 36, 116, 73];
-            let md2 = Metadata {
-                xmin: 0.0,
-                xmax: 1.0,
-                ymin: 0.0,
-                ymax: 1.0,
-                zmin: 0.0,
-                zmax: 1.0,
-                tmin: 0.0,
-                tmax: 1.0,
-                designation,
+                designation: "Foo",
                 buffer,
-            };
+            }
+        }
 
-            let buffer: &[u8; 5] = &[200, 0, 0, 200, 194];
-            let md3 = Metadata {
-                xmin: 0.0,
-                xmax: 2.0,
-                ymin: 0.0,
-                ymax: 2.0,
-                zmin: 0.0,
-                zmax: 2.0,
-                tmin: 0.0,
-                tmax: 2.0,
-                designation,
-                buffer,
-            };
+        #[test]
+        fn zero_readers_fails() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            assert!(matches!(
+                SqlDatabasePool::new(&tempfile.filepath, 0),
+                Err(DatabaseError::ConfigError { .. })
+            ));
+        }
 
-            let metadata: Vec<Metadata> = vec![md1, md2, md3];
+        #[test]
+        fn concurrent_queries_during_inserts_ok() {
+            let tempfile = TempFile::from("temp.db").unwrap();
+            let mut pool = SqlDatabasePool::new(&tempfile.filepath, 4).unwrap();
+            pool.insert_spec_text("Foo", "foo: u32").unwrap();
 
-            let _ = db.insert_spec_text(designation, spec);
-            let _ = db.insert_n_metadata(&metadata);
+            let buffers: Vec<[u8; 4]> = (0..100u32).map(u32::to_le_bytes).collect();
+            let data: Vec<Metadata> = buffers
+                .iter()
+                .enumerate()
+                .map(|(i, b)| metadata_at(i as f64, b))
+                .collect();
+            pool.insert_n_metadata(&data).unwrap();
 
-            let result = db.get_metadata_in_bb(0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, "Foo", None);
+            let pool = &pool;
+            std::thread::scope(|s| {
+                s.spawn(move || {
+                    for i in 0..200u32 {
+                        let buffer = i.to_le_bytes();
+                        pool.insert_metadata(&metadata_at(1000.0 + i as f64, &buffer))
+                            .unwrap();
+                    }
+                });
+                for _ in 0..8 {
+                    s.spawn(move || {
+                        for _ in 0..50 {
+                            let found = pool
+                                .get_metadata_in_bb(
+                                    0.0, 99.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None,
+                                )
+                                .unwrap();
+                            let mut values: Vec<u32> = found
+                                .iter()
+                                .map(|d| d.get_as::<u32>("foo").unwrap())
+                                .collect();
+                            values.sort();
+                            pretty_assertions::assert_eq!(values, (0..100).collect::<Vec<u32>>());
+                        }
+                    });
+                }
+            });
 
-            let expected: Vec<HashMap<&str, DataValue>> = vec![
-                HashMap::from([
-                    ("foo", DataValue::Byte(100)),
-                    ("bar", DataValue::Float32(1.0)),
-                ]),
-                HashMap::from([
-                    ("foo", DataValue::Byte(150)),
-                    ("bar", DataValue::Float32(1000000.0)),
-                ]),
-            ];
-            pretty_assertions::assert_eq!(result, Ok(expected),);
+            let written = pool
+                .get_metadata_in_bb(1000.0, 1199.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, "Foo", None)
+                .unwrap();
+            pretty_assertions::assert_eq!(written.len(), 200);
         }
     }
 }
@@ -1,12 +1,128 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Deref};
 
 use crate::error::*;
-use elucidator::value::DataValue;
+use elucidator::{
+    designation::DesignationSpecification,
+    value::{DataValue, FromDataValue},
+};
+#[cfg(feature = "rtree")]
 use rstar::{RTreeObject, AABB};
 
-pub type Datum<'a> = HashMap<&'a str, DataValue>;
 pub type Result<T, E = DatabaseError> = std::result::Result<T, E>;
 
+/// A single decoded metadatum, along with the specification it was decoded with.
+/// Dereferences to the underlying map of member names to values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Datum<'a> {
+    spec: &'a DesignationSpecification,
+    values: HashMap<&'a str, DataValue>,
+}
+
+impl<'a> Datum<'a> {
+    pub fn new(spec: &'a DesignationSpecification, values: HashMap<&'a str, DataValue>) -> Self {
+        Datum { spec, values }
+    }
+    /// Get the value of the member with the given name, if present
+    pub fn get(&self, name: &str) -> Option<&DataValue> {
+        self.values.get(name)
+    }
+    /// Get the value of the named member as a concrete Rust type.
+    /// Fails if the member is absent or holds a different type.
+    pub fn get_as<T: FromDataValue>(&self, name: &str) -> Result<T> {
+        let value = self
+            .values
+            .get(name)
+            .ok_or_else(|| DatabaseError::SpecificationError {
+                reason: format!("no member named \"{name}\""),
+            })?;
+        Ok(T::from_data_value(value)?)
+    }
+    /// Iterate over member names and values in specification order
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &DataValue)> {
+        self.spec
+            .member_names()
+            .filter_map(|name| self.values.get(name).map(|v| (name, v)))
+    }
+    /// Get the specification this datum was decoded with
+    pub fn spec(&self) -> &'a DesignationSpecification {
+        self.spec
+    }
+    pub fn into_map(self) -> HashMap<&'a str, DataValue> {
+        self.values
+    }
+}
+
+impl<'a> Deref for Datum<'a> {
+    type Target = HashMap<&'a str, DataValue>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl<'a> PartialEq<HashMap<&'a str, DataValue>> for Datum<'a> {
+    fn eq(&self, other: &HashMap<&'a str, DataValue>) -> bool {
+        self.values == *other
+    }
+}
+
+/// Parse each `(designation, spec)` pair, collecting every failure into a single error
+/// that names the offending designations. A designation that is already in `registered`, or
+/// appears more than once in `specs`, is a failure too.
+#[cfg_attr(not(any(feature = "sqlite", feature = "rtree")), allow(dead_code))]
+pub(crate) fn parse_specs<'a>(
+    specs: &'a [(String, String)],
+    registered: &HashMap<String, DesignationSpecification>,
+) -> Result<Vec<(&'a str, DesignationSpecification)>> {
+    let mut parsed = Vec::with_capacity(specs.len());
+    let mut failures = Vec::new();
+    for (i, (designation, spec)) in specs.iter().enumerate() {
+        if registered.contains_key(designation) {
+            failures.push(format!("designation \"{designation}\": already registered"));
+            continue;
+        }
+        if specs[..i].iter().any(|(earlier, _)| earlier == designation) {
+            failures.push(format!(
+                "designation \"{designation}\": given more than once"
+            ));
+            continue;
+        }
+        match DesignationSpecification::from_text(spec) {
+            Ok(s) => parsed.push((designation.as_str(), s)),
+            Err(e) => failures.push(format!("designation \"{designation}\": {e}")),
+        }
+    }
+    if failures.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(DatabaseError::SpecificationError {
+            reason: failures.join("\n"),
+        })
+    }
+}
+
+/// Widen the bounds `[xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax]` by `epsilon` on every
+/// side. A negative `epsilon` shrinks the box instead; shrinking an axis past empty is an error
+/// rather than a query that silently matches nothing.
+#[cfg_attr(not(any(feature = "sqlite", feature = "rtree")), allow(dead_code))]
+pub(crate) fn widen_bounds(bounds: [f64; 8], epsilon: Option<f64>) -> Result<[f64; 8]> {
+    let eps = epsilon.unwrap_or(0.0);
+    let mut widened = bounds;
+    for (i, axis) in ["x", "y", "z", "t"].iter().enumerate() {
+        let (min, max) = (bounds[2 * i], bounds[2 * i + 1]);
+        if eps < 0.0 && min - eps > max + eps {
+            return Err(DatabaseError::InvalidQueryBox {
+                reason: format!(
+                    "epsilon {eps} shrinks the {axis} bounds [{min}, {max}] past empty"
+                ),
+            });
+        }
+        widened[2 * i] = min - eps;
+        widened[2 * i + 1] = max + eps;
+    }
+    Ok(widened)
+}
+
 #[derive(Debug, Clone)]
 pub struct Metadata<'a> {
     pub xmin: f64,
@@ -21,6 +137,116 @@ pub struct Metadata<'a> {
     pub buffer: &'a [u8],
 }
 
+impl Metadata<'_> {
+    /// Pack `data` with `spec` and pair the buffer with a bounding box and designation, so that
+    /// missing members and shape or type mismatches are caught before the record is inserted.
+    pub fn from_values(
+        bb: &BoundingBox,
+        designation: &str,
+        spec: &DesignationSpecification,
+        data: &HashMap<&str, DataValue>,
+    ) -> Result<MetadataClone> {
+        Ok(MetadataClone {
+            xmin: bb.xmin,
+            xmax: bb.xmax,
+            ymin: bb.ymin,
+            ymax: bb.ymax,
+            zmin: bb.zmin,
+            zmax: bb.zmax,
+            tmin: bb.tmin,
+            tmax: bb.tmax,
+            designation: designation.to_string(),
+            buffer: spec.pack(data)?,
+        })
+    }
+}
+
+/// Axis-aligned box in space and time that metadata occupies or that a query covers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    xmin: f64,
+    xmax: f64,
+    ymin: f64,
+    ymax: f64,
+    zmin: f64,
+    zmax: f64,
+    tmin: f64,
+    tmax: f64,
+}
+
+impl BoundingBox {
+    /// Make the smallest box containing two `[x, y, z, t]` corners, given in any order.
+    pub fn from_points(a: [f64; 4], b: [f64; 4]) -> Self {
+        BoundingBox {
+            xmin: a[0].min(b[0]),
+            xmax: a[0].max(b[0]),
+            ymin: a[1].min(b[1]),
+            ymax: a[1].max(b[1]),
+            zmin: a[2].min(b[2]),
+            zmax: a[2].max(b[2]),
+            tmin: a[3].min(b[3]),
+            tmax: a[3].max(b[3]),
+        }
+    }
+    /// Make a box from the bounds of each axis, in the order taken by `get_metadata_in_bb`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_bounds(
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        tmin: f64,
+        tmax: f64,
+    ) -> Self {
+        BoundingBox {
+            xmin,
+            xmax,
+            ymin,
+            ymax,
+            zmin,
+            zmax,
+            tmin,
+            tmax,
+        }
+    }
+    pub fn xmin(&self) -> f64 {
+        self.xmin
+    }
+    pub fn xmax(&self) -> f64 {
+        self.xmax
+    }
+    pub fn ymin(&self) -> f64 {
+        self.ymin
+    }
+    pub fn ymax(&self) -> f64 {
+        self.ymax
+    }
+    pub fn zmin(&self) -> f64 {
+        self.zmin
+    }
+    pub fn zmax(&self) -> f64 {
+        self.zmax
+    }
+    pub fn tmin(&self) -> f64 {
+        self.tmin
+    }
+    pub fn tmax(&self) -> f64 {
+        self.tmax
+    }
+}
+
+/// Reductions available when aggregating a single numeric member over many records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Min,
+    Max,
+    Mean,
+    Sum,
+    Count,
+}
+
 pub trait Database: Sync {
     fn new(filename: Option<&str>, config: Option<&DatabaseConfig>) -> Result<Self>
     where
@@ -30,8 +256,34 @@ pub trait Database: Sync {
         Self: Sized;
     fn save_as(&self, filename: &str) -> Result<()>;
     fn insert_spec_text(&mut self, designation: &str, spec: &str) -> Result<()>;
+    /// Register several `(designation, spec)` pairs at once.
+    /// All specs are validated before any are inserted; if any fail, nothing is inserted.
+    fn insert_specs(&mut self, specs: &[(String, String)]) -> Result<()>;
     fn insert_metadata(&mut self, datum: &Metadata) -> Result<()>;
     fn insert_n_metadata(&mut self, data: &[Metadata]) -> Result<()>;
+    /// Insert a single buffer occupying `bb`, as with `insert_metadata`.
+    fn insert_metadata_box(
+        &mut self,
+        bb: &BoundingBox,
+        designation: &str,
+        buffer: &[u8],
+    ) -> Result<()> {
+        self.insert_metadata(&Metadata {
+            xmin: bb.xmin,
+            xmax: bb.xmax,
+            ymin: bb.ymin,
+            ymax: bb.ymax,
+            zmin: bb.zmin,
+            zmax: bb.zmax,
+            tmin: bb.tmin,
+            tmax: bb.tmax,
+            designation,
+            buffer,
+        })
+    }
+    /// Retrieve metadata of `designation` lying within the bounds, each widened by `epsilon`.
+    /// A negative `epsilon` shrinks the box, excluding records on its boundary; shrinking an
+    /// axis past empty fails with [`DatabaseError::InvalidQueryBox`].
     #[allow(clippy::too_many_arguments)]
     fn get_metadata_in_bb(
         &self,
@@ -45,7 +297,53 @@ pub trait Database: Sync {
         tmax: f64,
         designation: &str,
         epsilon: Option<f64>,
-    ) -> Result<Vec<Datum>>;
+    ) -> Result<Vec<Datum<'_>>>;
+    /// Retrieve metadata within `bb`, as with `get_metadata_in_bb`.
+    fn get_metadata_in_box(
+        &self,
+        bb: &BoundingBox,
+        designation: &str,
+        epsilon: Option<f64>,
+    ) -> Result<Vec<Datum<'_>>> {
+        self.get_metadata_in_bb(
+            bb.xmin,
+            bb.xmax,
+            bb.ymin,
+            bb.ymax,
+            bb.zmin,
+            bb.zmax,
+            bb.tmin,
+            bb.tmax,
+            designation,
+            epsilon,
+        )
+    }
+    /// Retrieve metadata within a spatial bounding box, at any time.
+    #[allow(clippy::too_many_arguments)]
+    fn get_metadata_in_space(
+        &self,
+        xmin: f64,
+        xmax: f64,
+        ymin: f64,
+        ymax: f64,
+        zmin: f64,
+        zmax: f64,
+        designation: &str,
+        epsilon: Option<f64>,
+    ) -> Result<Vec<Datum<'_>>> {
+        self.get_metadata_in_bb(
+            xmin,
+            xmax,
+            ymin,
+            ymax,
+            zmin,
+            zmax,
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+            designation,
+            epsilon,
+        )
+    }
     #[allow(clippy::too_many_arguments)]
     fn get_metadata_blobs_in_bb(
         &self,
@@ -59,7 +357,61 @@ pub trait Database: Sync {
         tmax: f64,
         designation: &str,
         epsilon: Option<f64>,
-    ) -> Result<Vec<&Vec<u8>>>;
+    ) -> Result<Vec<Vec<u8>>>;
+    /// Buffer metadata pushed to the returned [`BatchInserter`] and insert it `batch_size`
+    /// records at a time, each batch as one `insert_n_metadata` call.
+    fn batch_inserter(&mut self, batch_size: usize) -> BatchInserter<'_, Self>
+    where
+        Self: Sized,
+    {
+        BatchInserter {
+            db: self,
+            batch_size: batch_size.max(1),
+            pending: Vec::with_capacity(batch_size),
+        }
+    }
+}
+
+/// Accumulates metadata and inserts it into a database in batches, so that callers ingesting
+/// many records need not manage transactions themselves. Call [`BatchInserter::finish`] to
+/// insert the final partial batch; records still pending when the inserter is dropped are lost.
+pub struct BatchInserter<'a, D: Database> {
+    db: &'a mut D,
+    batch_size: usize,
+    pending: Vec<MetadataClone>,
+}
+
+impl<'a, D: Database> BatchInserter<'a, D> {
+    /// Queue a record, inserting the batch once it holds `batch_size` records.
+    pub fn push(&mut self, metadata: &Metadata) -> Result<()> {
+        self.pending.push(metadata.into());
+        if self.pending.len() >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+    /// Number of records queued but not yet inserted
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+    /// Insert all queued records now.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let batch: Vec<Metadata> = self
+            .pending
+            .iter()
+            .map(MetadataClone::as_metadata)
+            .collect();
+        self.db.insert_n_metadata(&batch)?;
+        self.pending.clear();
+        Ok(())
+    }
+    /// Insert the remaining queued records.
+    pub fn finish(mut self) -> Result<()> {
+        self.flush()
+    }
 }
 
 pub trait Config {
@@ -75,10 +427,80 @@ pub trait Config {
 }
 
 pub enum DatabaseConfig {
+    #[cfg(feature = "rtree")]
     RTreeConfig(crate::backends::rtree::RTreeConfig),
+    #[cfg(feature = "sqlite")]
     SqliteConfig(crate::backends::sqlite::SqliteConfig),
 }
 
+/// Owned copy of a [`Metadata`] entry, as returned by backends that hand back stored records.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataClone {
+    pub xmin: f64,
+    pub xmax: f64,
+    pub ymin: f64,
+    pub ymax: f64,
+    pub zmin: f64,
+    pub zmax: f64,
+    pub tmin: f64,
+    pub tmax: f64,
+    pub designation: String,
+    pub buffer: Vec<u8>,
+}
+
+impl MetadataClone {
+    /// Borrow this entry as a [`Metadata`] for insertion.
+    pub fn as_metadata(&self) -> Metadata<'_> {
+        Metadata {
+            xmin: self.xmin,
+            xmax: self.xmax,
+            ymin: self.ymin,
+            ymax: self.ymax,
+            zmin: self.zmin,
+            zmax: self.zmax,
+            tmin: self.tmin,
+            tmax: self.tmax,
+            designation: &self.designation,
+            buffer: &self.buffer,
+        }
+    }
+}
+
+impl From<Metadata<'_>> for MetadataClone {
+    fn from(m: Metadata) -> Self {
+        MetadataClone {
+            xmin: m.xmin,
+            xmax: m.xmax,
+            ymin: m.ymin,
+            ymax: m.ymax,
+            zmin: m.zmin,
+            zmax: m.zmax,
+            tmin: m.tmin,
+            tmax: m.tmax,
+            designation: m.designation.to_string(),
+            buffer: m.buffer.into(),
+        }
+    }
+}
+
+impl From<&Metadata<'_>> for MetadataClone {
+    fn from(m: &Metadata) -> Self {
+        MetadataClone {
+            xmin: m.xmin,
+            xmax: m.xmax,
+            ymin: m.ymin,
+            ymax: m.ymax,
+            zmin: m.zmin,
+            zmax: m.zmax,
+            tmin: m.tmin,
+            tmax: m.tmax,
+            designation: m.designation.to_string(),
+            buffer: m.buffer.into(),
+        }
+    }
+}
+
+#[cfg(feature = "rtree")]
 impl<'a> RTreeObject for &Metadata<'a> {
     type Envelope = AABB<[f64; 4]>;
 
@@ -90,6 +512,7 @@ impl<'a> RTreeObject for &Metadata<'a> {
     }
 }
 
+#[cfg(feature = "rtree")]
 impl<'a> RTreeObject for Metadata<'a> {
     type Envelope = AABB<[f64; 4]>;
 
@@ -100,3 +523,234 @@ impl<'a> RTreeObject for Metadata<'a> {
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod parse_specs {
+        use super::*;
+
+        fn specs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(d, s)| (d.to_string(), s.to_string()))
+                .collect()
+        }
+
+        #[test]
+        fn duplicate_in_batch_err() {
+            let specs = specs(&[("Foo", "a: u8"), ("Bar", "b: u8"), ("Foo", "c: u16")]);
+            pretty_assertions::assert_eq!(
+                parse_specs(&specs, &HashMap::new()),
+                Err(DatabaseError::SpecificationError {
+                    reason: "designation \"Foo\": given more than once".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn already_registered_err() {
+            let registered = HashMap::from([(
+                "Foo".to_string(),
+                DesignationSpecification::from_text("a: u8").unwrap(),
+            )]);
+            let specs = specs(&[("Foo", "a: u8"), ("Bar", "b: u9")]);
+            let reason = match parse_specs(&specs, &registered) {
+                Err(DatabaseError::SpecificationError { reason }) => reason,
+                other => panic!("Expected a specification error, got {other:?}"),
+            };
+            assert!(
+                reason
+                    .starts_with("designation \"Foo\": already registered\ndesignation \"Bar\": "),
+                "{reason}"
+            );
+        }
+    }
+
+    mod widen_bounds {
+        use super::*;
+
+        #[test]
+        fn widen_and_shrink_ok() {
+            let bounds = [
+                0.0,
+                4.0,
+                -1.0,
+                1.0,
+                2.0,
+                2.0,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+            ];
+            pretty_assertions::assert_eq!(widen_bounds(bounds, None), Ok(bounds));
+            pretty_assertions::assert_eq!(
+                widen_bounds(bounds, Some(0.5)),
+                Ok([
+                    -0.5,
+                    4.5,
+                    -1.5,
+                    1.5,
+                    1.5,
+                    2.5,
+                    f64::NEG_INFINITY,
+                    f64::INFINITY
+                ])
+            );
+            let bounds = [0.0, 4.0, -1.0, 1.0, 0.0, 2.0, 0.0, 1.0];
+            pretty_assertions::assert_eq!(
+                widen_bounds(bounds, Some(-0.5)),
+                Ok([0.5, 3.5, -0.5, 0.5, 0.5, 1.5, 0.5, 0.5])
+            );
+        }
+
+        #[test]
+        fn over_shrink_fails() {
+            let bounds = [0.0, 4.0, -1.0, 1.0, 0.0, 2.0, 0.0, 1.0];
+            assert!(matches!(
+                widen_bounds(bounds, Some(-0.75)),
+                Err(DatabaseError::InvalidQueryBox { reason }) if reason.contains("t bounds")
+            ));
+        }
+    }
+
+    mod datum {
+        use super::*;
+        use elucidator::error::ElucidatorError;
+
+        #[test]
+        fn iter_in_spec_order_ok() {
+            let spec = DesignationSpecification::from_text("zeta: u64, alpha: f32[], mid: string")
+                .unwrap();
+            let values = HashMap::from([
+                ("alpha", DataValue::Float32Array(vec![1.0, 2.0])),
+                ("mid", DataValue::Str("cat".to_string())),
+                ("zeta", DataValue::UnsignedInteger64(7)),
+            ]);
+            let datum = Datum::new(&spec, values);
+            let names: Vec<&str> = datum.iter().map(|(name, _)| name).collect();
+            pretty_assertions::assert_eq!(names, vec!["zeta", "alpha", "mid"]);
+            pretty_assertions::assert_eq!(datum.spec(), &spec);
+        }
+
+        #[test]
+        fn get_ok() {
+            let spec = DesignationSpecification::from_text("hits: u64, misses: u64").unwrap();
+            let values = HashMap::from([
+                ("hits", DataValue::UnsignedInteger64(3)),
+                ("misses", DataValue::UnsignedInteger64(4)),
+            ]);
+            let datum = Datum::new(&spec, values.clone());
+            pretty_assertions::assert_eq!(
+                datum.get("hits"),
+                Some(&DataValue::UnsignedInteger64(3))
+            );
+            pretty_assertions::assert_eq!(datum.get("walks"), None);
+            // Still usable as the underlying map
+            pretty_assertions::assert_eq!(datum.len(), 2);
+            assert!(datum == values);
+        }
+
+        #[test]
+        fn get_as_ok() {
+            let spec = DesignationSpecification::from_text("hits: u64, pdf: f32[]").unwrap();
+            let values = HashMap::from([
+                ("hits", DataValue::UnsignedInteger64(3)),
+                ("pdf", DataValue::Float32Array(vec![0.25, 0.75])),
+            ]);
+            let datum = Datum::new(&spec, values);
+            pretty_assertions::assert_eq!(datum.get_as::<u64>("hits"), Ok(3));
+            pretty_assertions::assert_eq!(datum.get_as::<Vec<f32>>("pdf"), Ok(vec![0.25, 0.75]));
+        }
+
+        #[test]
+        fn get_as_mismatch_fails() {
+            let spec = DesignationSpecification::from_text("hits: u64").unwrap();
+            let values = HashMap::from([("hits", DataValue::UnsignedInteger64(3))]);
+            let datum = Datum::new(&spec, values);
+            pretty_assertions::assert_eq!(
+                datum.get_as::<f32>("hits"),
+                Err(DatabaseError::ElucidatorError {
+                    reason: ElucidatorError::Conversion {
+                        from: "u64".to_string(),
+                        to: "f32".to_string(),
+                    }
+                })
+            );
+            assert!(matches!(
+                datum.get_as::<u64>("misses"),
+                Err(DatabaseError::SpecificationError { .. })
+            ));
+        }
+    }
+
+    mod from_values {
+        use super::*;
+        use elucidator::error::ElucidatorError;
+
+        fn spec() -> DesignationSpecification {
+            DesignationSpecification::from_text("id: u32, temps: f32[2]").unwrap()
+        }
+
+        #[cfg(feature = "rtree")]
+        #[test]
+        fn insert_ok() {
+            use crate::backends::rtree::RTreeDatabase;
+
+            let bb = BoundingBox::from_points([0.0, 0.0, 0.0, 0.0], [1.0, 1.0, 1.0, 1.0]);
+            let values = HashMap::from([
+                ("id", DataValue::UnsignedInteger32(7)),
+                ("temps", DataValue::Float32Array(vec![20.5, 21.0])),
+            ]);
+            let metadata = Metadata::from_values(&bb, "Probe", &spec(), &values).unwrap();
+            pretty_assertions::assert_eq!(metadata.designation, "Probe");
+            pretty_assertions::assert_eq!((metadata.xmin, metadata.tmax), (0.0, 1.0));
+
+            let mut db = RTreeDatabase::new(None, None).unwrap();
+            db.insert_spec_text("Probe", &spec().to_string()).unwrap();
+            db.insert_metadata(&metadata.as_metadata()).unwrap();
+            let found = db
+                .get_metadata_in_bb(0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, "Probe", None)
+                .unwrap();
+            pretty_assertions::assert_eq!(found.len(), 1);
+            assert!(found[0] == values);
+        }
+
+        #[test]
+        fn mismatch_fails() {
+            let bb = BoundingBox::from_points([0.0; 4], [1.0; 4]);
+            let values = HashMap::from([
+                ("id", DataValue::UnsignedInteger32(7)),
+                ("temps", DataValue::Float32Array(vec![20.5, 21.0, 22.0])),
+            ]);
+            assert!(matches!(
+                Metadata::from_values(&bb, "Probe", &spec(), &values),
+                Err(DatabaseError::ElucidatorError {
+                    reason: ElucidatorError::Conversion { .. }
+                })
+            ));
+            let values = HashMap::from([("id", DataValue::UnsignedInteger32(7))]);
+            assert!(matches!(
+                Metadata::from_values(&bb, "Probe", &spec(), &values),
+                Err(DatabaseError::ElucidatorError {
+                    reason: ElucidatorError::MissingValue { .. }
+                })
+            ));
+        }
+    }
+
+    mod bounding_box {
+        use super::*;
+
+        #[test]
+        fn from_points_orders_corners() {
+            let bb = BoundingBox::from_points([1.0, -2.0, 3.0, 10.0], [-1.0, 2.0, 0.0, 5.0]);
+            pretty_assertions::assert_eq!(
+                bb,
+                BoundingBox::from_bounds(-1.0, 1.0, -2.0, 2.0, 0.0, 3.0, 5.0, 10.0)
+            );
+            pretty_assertions::assert_eq!((bb.xmin(), bb.xmax()), (-1.0, 1.0));
+            pretty_assertions::assert_eq!((bb.tmin(), bb.tmax()), (5.0, 10.0));
+        }
+    }
+}
@@ -1,16 +1,35 @@
-use std::fmt;
+use std::{error::Error, fmt, sync::Arc};
+
+/// Shared handle to the underlying cause of a [`DatabaseError`], which keeps the error `Clone`.
+/// Sources always compare equal; errors are compared by their `reason`.
+#[derive(Debug, Clone)]
+pub struct ErrorSource(Arc<dyn Error + Send + Sync>);
+
+impl ErrorSource {
+    fn new<E: Error + Send + Sync + 'static>(error: E) -> Self {
+        ErrorSource(Arc::new(error))
+    }
+}
+
+impl PartialEq for ErrorSource {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum DatabaseError {
     /// Errors related to creating databases.
     RusqliteError {
         reason: String,
+        source: ErrorSource,
     },
     ElucidatorError {
         reason: elucidator::error::ElucidatorError,
     },
     IOError {
         reason: String,
+        source: ErrorSource,
     },
     VersionError {
         reason: String,
@@ -21,18 +40,48 @@ pub enum DatabaseError {
     LockError {
         reason: String,
     },
+    SpecificationError {
+        reason: String,
+    },
+    NonFiniteValue {
+        designation: String,
+        member: String,
+    },
+    IncompatibleMigration {
+        designation: String,
+        reason: String,
+    },
+    /// A stored buffer no longer matches the checksum recorded when it was inserted
+    ChecksumMismatch {
+        id: i64,
+    },
+    /// A query was abandoned because its cancellation flag was set
+    Cancelled,
+    /// A negative epsilon shrank a query box past empty on some axis
+    InvalidQueryBox {
+        reason: String,
+    },
+}
+
+impl DatabaseError {
+    /// The error for a designation that has no registered specification.
+    pub fn unregistered(designation: &str) -> Self {
+        DatabaseError::SpecificationError {
+            reason: format!("designation \"{designation}\" is not registered"),
+        }
+    }
 }
 
 impl fmt::Display for DatabaseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let m = match self {
-            Self::RusqliteError { reason } => {
+            Self::RusqliteError { reason, .. } => {
                 format!("SQL Error: {reason}")
             }
             Self::ElucidatorError { reason } => {
                 format!("Elucidator Error: {reason}")
             }
-            Self::IOError { reason } => {
+            Self::IOError { reason, .. } => {
                 format!("IO Error: {reason}")
             }
             Self::VersionError { reason } => {
@@ -44,23 +93,59 @@ impl fmt::Display for DatabaseError {
             Self::LockError { reason } => {
                 format!("Lock Error: {reason}")
             }
+            Self::IncompatibleMigration {
+                designation,
+                reason,
+            } => {
+                format!("Cannot migrate designation {designation}: {reason}")
+            }
+            Self::SpecificationError { reason } => {
+                format!("Specification Error: {reason}")
+            }
+            Self::ChecksumMismatch { id } => {
+                format!("Checksum Mismatch: buffer of record {id} is corrupted")
+            }
+            Self::Cancelled => "Cancelled: query was abandoned before it finished".to_string(),
+            Self::InvalidQueryBox { reason } => {
+                format!("Invalid Query Box: {reason}")
+            }
+            Self::NonFiniteValue {
+                designation,
+                member,
+            } => {
+                format!("Non-finite Value: member {member} of designation {designation} is NaN or infinite")
+            }
         };
         write!(f, "{m}")
     }
 }
 
+impl Error for DatabaseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::RusqliteError { source, .. } | Self::IOError { source, .. } => Some(&*source.0),
+            Self::ElucidatorError { reason } => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
 impl From<rusqlite::Error> for DatabaseError {
     fn from(error: rusqlite::Error) -> Self {
         DatabaseError::RusqliteError {
             reason: format!("{error}"),
+            source: ErrorSource::new(error),
         }
     }
 }
 
+#[cfg(feature = "sqlite")]
 impl From<rusqlite::types::FromSqlError> for DatabaseError {
     fn from(error: rusqlite::types::FromSqlError) -> Self {
         DatabaseError::RusqliteError {
             reason: format!("{error}"),
+            source: ErrorSource::new(error),
         }
     }
 }
@@ -75,6 +160,7 @@ impl From<std::io::Error> for DatabaseError {
     fn from(error: std::io::Error) -> Self {
         DatabaseError::IOError {
             reason: format!("{error}"),
+            source: ErrorSource::new(error),
         }
     }
 }
@@ -86,3 +172,35 @@ impl<T> From<std::sync::PoisonError<T>> for DatabaseError {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn rusqlite_source_ok() {
+        let err = DatabaseError::from(rusqlite::Error::QueryReturnedNoRows);
+        let source = err
+            .source()
+            .expect("Expected a source for a wrapped rusqlite error");
+        pretty_assertions::assert_eq!(
+            source.downcast_ref::<rusqlite::Error>(),
+            Some(&rusqlite::Error::QueryReturnedNoRows)
+        );
+    }
+
+    #[test]
+    fn io_source_ok() {
+        let err = DatabaseError::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+        let source = err
+            .source()
+            .expect("Expected a source for a wrapped IO error");
+        pretty_assertions::assert_eq!(
+            source
+                .downcast_ref::<std::io::Error>()
+                .map(std::io::Error::kind),
+            Some(std::io::ErrorKind::NotFound)
+        );
+    }
+}
@@ -1,15 +1,10 @@
-use std::iter::Enumerate;
-
-use elucidator::value::DataValue;
-use elucidator::Representable;
+use elucidator::representable::BufferConcat;
 use elucidator_db::backends::rtree::RTreeDatabase;
 use elucidator_db::database::{Database, Metadata};
 use elucidator_db::error::DatabaseError;
 
 use rand::prelude::*;
 
-use std::vec::Vec;
-
 struct StepSummary {
     hits: u64,
     misses: u64,
@@ -44,12 +39,9 @@ fn run_experiment(
     n_steps: usize,
     samples_per_step: usize,
 ) -> Result<(), DatabaseError> {
-    const METADATA_BUFFER_SIZE: usize = size_of::<StepSummary>();
     for idx in 0..n_steps {
         let step = simulate_step(samples_per_step);
-        let mut buffer: Vec<u8> = Vec::with_capacity(METADATA_BUFFER_SIZE);
-        buffer.extend(step.hits.as_buffer());
-        buffer.extend(step.misses.as_buffer());
+        let buffer = (step.hits, step.misses).to_combined_buffer();
         let md = Metadata {
             xmin: -1.0,
             xmax: 1.0,
@@ -79,15 +71,13 @@ fn calc_pi_estimate(hits: f64, misses: f64) -> f64 {
     if hits + misses == 0.0 {
         return 0.0;
     }
-    hits as f64 / (hits + misses) as f64 * 4.0
+    hits / (hits + misses) * 4.0
 }
 
 fn analyze(db: &mut dyn Database, timestep: usize) -> Result<AnalysisResult, DatabaseError> {
     const Z_SCORE_95_CI: f64 = 1.959963984540054;
     let mut total_hits: u64 = 0;
     let mut total_misses: u64 = 0;
-    let pi_estimate: f64;
-    let pi_95_ci: (f64, f64);
     let data = db.get_metadata_in_bb(
         -1.0,
         1.0,
@@ -101,24 +91,12 @@ fn analyze(db: &mut dyn Database, timestep: usize) -> Result<AnalysisResult, Dat
         None,
     )?;
     for metadata in data {
-        let hits = metadata.get("hits").unwrap();
-        let misses = metadata.get("misses").unwrap();
-        match hits {
-            DataValue::UnsignedInteger64(h) => total_hits += h,
-            _ => {
-                unreachable!()
-            }
-        }
-        match misses {
-            DataValue::UnsignedInteger64(m) => total_misses += m,
-            _ => {
-                unreachable!()
-            }
-        }
+        total_hits += metadata.get_as::<u64>("hits")?;
+        total_misses += metadata.get_as::<u64>("misses")?;
     }
 
-    pi_estimate = calc_pi_estimate(total_hits as f64, total_misses as f64);
-    pi_95_ci = calc_confidence_interval(total_hits as f64, total_misses as f64, Z_SCORE_95_CI);
+    let pi_estimate = calc_pi_estimate(total_hits as f64, total_misses as f64);
+    let pi_95_ci = calc_confidence_interval(total_hits as f64, total_misses as f64, Z_SCORE_95_CI);
 
     Ok(AnalysisResult {
         timestep,
@@ -142,8 +120,13 @@ fn main() {
     for t in (DISPLAY_INTERVAL..=N_STEPS).step_by(DISPLAY_INTERVAL) {
         let analysis = analyze(&mut db, t).unwrap();
         println!(
-            "Timestep {t}: Pi ~= {}, 95% CI ({}, {})",
-            analysis.pi_estimate, analysis.pi_95_ci.0, analysis.pi_95_ci.1
+            "Timestep {}: Pi ~= {}, 95% CI ({}, {}) from {} hits and {} misses",
+            analysis.timestep,
+            analysis.pi_estimate,
+            analysis.pi_95_ci.0,
+            analysis.pi_95_ci.1,
+            analysis.total_hits,
+            analysis.total_misses,
         );
     }
 }
@@ -2,12 +2,12 @@ use std::collections::HashMap;
 
 use crate::{
     error::*,
-    member::{Dtype, MemberSpecification, Sizing},
+    member::{char_from_le, Dtype, MemberSpecification, Sizing},
     parsing,
     representable::Representable,
-    util::Buffer,
+    util::{fnv1a, Buffer},
     validating,
-    value::{DataValue, LeBufferRead},
+    value::{DataValue, FromDataValue, LeBufferRead},
 };
 
 type Result<T, E = ElucidatorError> = std::result::Result<T, E>;
@@ -28,12 +28,349 @@ pub struct DesignationSpecification {
     members: Vec<MemberSpecification>,
 }
 
+/// Characters permitted in member identifiers beyond ASCII alphanumerics and `_`.
+/// The default permits no extra characters.
+/// ```
+/// use elucidator::designation::{DesignationSpecification, IdentifierPolicy};
+///
+/// let policy = IdentifierPolicy { extra_chars: vec!['.'] };
+/// let spec = DesignationSpecification::from_text_with_policy("gps.lat: f64", &policy);
+///
+/// # assert!(spec.is_ok())
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct IdentifierPolicy {
+    pub extra_chars: Vec<char>,
+}
+
+/// How string members are decoded when a buffer is interpreted.
+/// `Strict` fails on invalid UTF-8, while `Lossy` substitutes the replacement character.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum StringPolicy {
+    #[default]
+    Strict,
+    Lossy,
+}
+
+/// How string members are laid out in a buffer.
+/// `LengthPrefixed` strings are preceded by their length in bytes as a little-endian `u64`, while
+/// `NulTerminated` strings run up to a `0x00` byte, as C producers commonly write them. Because
+/// the first NUL ends the string, a `NulTerminated` string cannot contain interior NULs.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum StringEncoding {
+    #[default]
+    LengthPrefixed,
+    NulTerminated,
+}
+
+/// How `pack` coerces an integer that lies outside the range of its member's dtype.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum PackOverflowPolicy {
+    /// Refuse to pack the value
+    #[default]
+    Error,
+    /// Clamp to the nearest representable value
+    Saturate,
+    /// Keep the low-order bits, as an `as` cast would
+    Wrap,
+}
+
+/// Options controlling how `pack_with_options` serializes values.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct PackOptions {
+    pub overflow_policy: PackOverflowPolicy,
+    pub string_encoding: StringEncoding,
+}
+
+/// Position and shape of one member within a buffer, as produced by
+/// [`DesignationSpecification::layout`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct LayoutEntry {
+    pub name: String,
+    /// Byte offset of the member from the start of the buffer, or `None` once an earlier
+    /// dynamic array or string makes the position depend on the buffer's contents
+    pub offset: Option<usize>,
+    pub dtype: Dtype,
+    /// Number of elements, or `None` for dynamic arrays. Strings count as one element.
+    pub element_count: Option<usize>,
+}
+
+/// A member that [`DesignationSpecification::interpret_enum_recovering`] could not decode.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MemberDecodeError {
+    pub member: String,
+    pub error: ElucidatorError,
+}
+
+/// Byte order that [`DesignationSpecification::detect_endianness`] judges a buffer to be in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EndiannessGuess {
+    Little,
+    Big,
+    /// Both byte orders are equally plausible, or neither can be decoded
+    Ambiguous,
+}
+
+/// How many times more bytes than remain a dynamic member's count must ask for before a
+/// missing length prefix is suspected
+const MISSING_PREFIX_FACTOR: usize = 1 << 20;
+
+/// Spot a dynamic member whose length prefix was probably never written. Its first elements
+/// are then read as the prefix, which usually gives a count needing vastly more bytes than
+/// remain. The count is only blamed on a missing prefix if its bytes also read as plausible
+/// elements, so that a corrupt prefix still surfaces as an oversized array. Every byte is a
+/// plausible single-byte element, so those dtypes are never blamed.
+pub(crate) fn check_length_prefix(
+    member: &MemberSpecification,
+    prefix: &[u8],
+    remaining: usize,
+) -> Result<()> {
+    let decoded_count = u64::from_le_bytes(prefix.try_into().unwrap());
+    let size = member.dtype.get_size().unwrap_or(1);
+    if size == 1 {
+        return Ok(());
+    }
+    let needed = (decoded_count as usize).saturating_mul(size);
+    if needed / MISSING_PREFIX_FACTOR > remaining
+        && prefix
+            .chunks_exact(size)
+            .all(|element| plausible_element(&member.dtype, element))
+    {
+        return Err(ElucidatorError::LikelyMissingLengthPrefix {
+            member: member.identifier.clone(),
+            decoded_count,
+        });
+    }
+    Ok(())
+}
+
+/// Read the length prefix of a dynamic member, rejecting a count whose elements could not fit
+/// in the rest of the buffer, or that exceeds `max_elements` when it is given.
+pub(crate) fn read_dynamic_len(
+    buf: &mut Buffer,
+    member: &MemberSpecification,
+    max_elements: Option<usize>,
+) -> Result<usize> {
+    let prefix = buf.grab_slice(8)?;
+    check_length_prefix(member, prefix, buf.remaining())?;
+    let n = u64::from_le_bytes(prefix.try_into().unwrap()) as usize;
+    let limit =
+        max_elements.unwrap_or_else(|| buf.remaining() / member.dtype.get_size().unwrap_or(1));
+    if n > limit {
+        return Err(ElucidatorError::ArrayTooLarge {
+            member: member.identifier.clone(),
+            requested: n,
+            limit,
+        });
+    }
+    Ok(n)
+}
+
+/// Whether a single numeric element, given as little-endian bytes, looks like a value a
+/// producer would plausibly write. Floats should be finite, normal, and of moderate magnitude;
+/// integers wider than a byte should fit in the lower half of their bits.
+fn plausible_element(dtype: &Dtype, le: &[u8]) -> bool {
+    let plausible_float = |v: f64| v == 0.0 || (v.is_normal() && (1e-30..1e30).contains(&v.abs()));
+    let fits_half = |v: i128, bits: u32| {
+        let half = 1i128 << (bits / 2);
+        (-half..half).contains(&v)
+    };
+    match dtype {
+        Dtype::Float32 => plausible_float(f32::from_le_bytes(le.try_into().unwrap()) as f64),
+        Dtype::Float64 => plausible_float(f64::from_le_bytes(le.try_into().unwrap())),
+        Dtype::Byte | Dtype::SignedInteger8 | Dtype::Str => true,
+        Dtype::Char => char_from_le(le).is_ok(),
+        Dtype::UnsignedInteger16 => {
+            fits_half(u16::from_le_bytes(le.try_into().unwrap()) as i128, 16)
+        }
+        Dtype::UnsignedInteger32 => {
+            fits_half(u32::from_le_bytes(le.try_into().unwrap()) as i128, 32)
+        }
+        Dtype::UnsignedInteger64 => {
+            fits_half(u64::from_le_bytes(le.try_into().unwrap()) as i128, 64)
+        }
+        Dtype::SignedInteger16 => fits_half(i16::from_le_bytes(le.try_into().unwrap()) as i128, 16),
+        Dtype::SignedInteger32 => fits_half(i32::from_le_bytes(le.try_into().unwrap()) as i128, 32),
+        Dtype::SignedInteger64 | Dtype::Duration => {
+            fits_half(i64::from_le_bytes(le.try_into().unwrap()) as i128, 64)
+        }
+    }
+}
+
+/// Options controlling how `interpret_enum_with_options` decodes a buffer.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct InterpretOptions {
+    pub string_policy: StringPolicy,
+    pub string_encoding: StringEncoding,
+    /// Number of leading header bytes to step over before the first member
+    pub skip_header_bytes: usize,
+    /// Largest element count accepted from a dynamic array's length prefix. When `None`, the
+    /// limit is the number of elements the rest of the buffer could hold.
+    pub max_dynamic_elements: Option<usize>,
+    /// Decode members with an enum mapping to the `Str` name of their variant. Values without a
+    /// variant are left as integers.
+    pub enum_names: bool,
+    /// Zero-extend a buffer shorter than `fixed_size_bytes` before decoding, for producers that
+    /// omit trailing zero fields. Only fixed-size specifications are padded; short buffers for
+    /// specifications with strings or dynamic arrays still fail.
+    pub pad_short_buffer: bool,
+}
+
+/// Reusable decoding storage for [`DesignationSpecification::interpret_enum_reuse`].
+/// Each decode overwrites the values of the previous one in place, so once a record of each
+/// shape has been seen, decoding further records reuses the existing array and string
+/// allocations instead of making new ones. Use a separate scratch for each specification.
+#[derive(Debug, Default)]
+pub struct DecodeScratch<'a> {
+    values: HashMap<&'a str, DataValue>,
+}
+
+impl<'a> DecodeScratch<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Overwrite `slot`, which already has the member's shape, with little-endian values read from
+/// `buf`: one value when `n` is `None`, otherwise `n` values reusing the slot's vector.
+macro_rules! refill {
+    ($slot:expr, $buf:expr, $n:expr, $($variant:ident, $array:ident, $tt:ty);* $(;)?) => {
+        match $slot {
+            $(
+                DataValue::$variant(v) => {
+                    *v = <$tt>::from_le_bytes($buf.grab_slice(<$tt>::bytes_needed(1))?.try_into().unwrap());
+                }
+                DataValue::$array(v) => {
+                    let n = $n.unwrap_or(0);
+                    let bytes = $buf.grab_slice(<$tt>::bytes_needed(n))?;
+                    v.clear();
+                    v.extend(
+                        bytes
+                            .chunks_exact(std::mem::size_of::<$tt>())
+                            .map(|c| <$tt>::from_le_bytes(c.try_into().unwrap())),
+                    );
+                }
+            )*
+            DataValue::Char(c) => {
+                *c = char_from_le($buf.grab_slice(4)?)?;
+            }
+            DataValue::Str(_) => unreachable!("Strings are refilled separately"),
+        }
+    };
+}
+
+/// Differences between two specifications, as produced by [`DesignationSpecification::diff`].
+/// Members are matched by identifier; documentation is ignored.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpecDiff {
+    /// Members present only in the new specification
+    pub added: Vec<String>,
+    /// Members present only in the old specification
+    pub removed: Vec<String>,
+    /// Members present in both whose dtype or sizing changed
+    pub changed: Vec<String>,
+    append_only: bool,
+}
+
+impl SpecDiff {
+    /// Whether the new specification only appends members after all of the old ones, leaving
+    /// the existing members and their order untouched.
+    pub fn is_append_only(&self) -> bool {
+        self.append_only
+    }
+}
+
+/// Advisory findings about a specification that parsed successfully, as produced by
+/// [`DesignationSpecification::from_text_with_warnings`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum SpecWarning {
+    /// A dynamic array or string is followed by other members, whose offsets therefore depend
+    /// on the buffer's contents
+    DynamicMemberNotLast {
+        member: String,
+        followed_by: Vec<String>,
+    },
+    /// A fixed-size array holds a single element, and could be a scalar instead
+    SingleElementArray { member: String },
+    /// The only member is a dynamic array, so the record could be stored as a raw blob
+    LoneDynamicArray { member: String },
+}
+
+impl SpecWarning {
+    /// The member this warning is about
+    pub fn member(&self) -> &str {
+        match self {
+            Self::DynamicMemberNotLast { member, .. }
+            | Self::SingleElementArray { member }
+            | Self::LoneDynamicArray { member } => member,
+        }
+    }
+
+    /// Columns of `text`, the specification that produced this warning, spanned by the
+    /// member's identifier and type, with the end exclusive.
+    /// ```
+    /// use elucidator::designation::DesignationSpecification;
+    ///
+    /// let text = "id: u32, foo: u8[1]";
+    /// let (_, warnings) = DesignationSpecification::from_text_with_warnings(text).unwrap();
+    /// assert_eq!(warnings[0].span(text), Some((9, 19)));
+    /// ```
+    pub fn span(&self, text: &str) -> Option<(usize, usize)> {
+        let parsed = parsing::get_metadataspec(text);
+        let output = parsed.member_outputs.iter().find(|output| {
+            output
+                .identifier
+                .as_ref()
+                .is_some_and(|ident| ident.data.data == self.member())
+        })?;
+        let start = output.identifier.as_ref()?.data.column_start;
+        let typespec = output.typespec.as_ref()?;
+        let end = match (&typespec.sizing, &typespec.dtype) {
+            // Include the closing bracket, which follows the sizing token
+            (Some(sizing), _) => sizing.data.column_end + 1,
+            (None, Some(dtype)) => dtype.data.column_end,
+            (None, None) => return None,
+        };
+        Some((start, end))
+    }
+}
+
+impl std::fmt::Display for SpecWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SingleElementArray { member } => write!(
+                f,
+                "Member {member} is an array of one element; a scalar is stored identically"
+            ),
+            Self::LoneDynamicArray { member } => write!(
+                f,
+                "Member {member} is the only member and a dynamic array; consider storing a raw blob"
+            ),
+            Self::DynamicMemberNotLast {
+                member,
+                followed_by,
+            } => write!(
+                f,
+                "Dynamic member {member} is followed by {}; their offsets are no longer fixed",
+                followed_by.join(", ")
+            ),
+        }
+    }
+}
+
+fn same_layout(a: &MemberSpecification, b: &MemberSpecification) -> bool {
+    a.identifier == b.identifier && a.dtype == b.dtype && a.sizing == b.sizing
+}
+
 fn subselect_text(text: &str, start: usize, end: usize) -> (&str, usize) {
     let end = if text.chars().count() <= end {
         text.chars().count() - 1
     } else {
         end
     };
+    // An error on a blank final member can start past the last character
+    let start = start.min(end);
     let (start_byte_pos, _) = text.char_indices().nth(start).unwrap();
     let (end_byte_pos, _) = text.char_indices().nth(end).unwrap();
     let last_comma_pos = text[..start_byte_pos].rfind(',');
@@ -67,7 +404,7 @@ fn produce_context(text: &str, start: usize, end: usize) -> String {
     format!("{selection}\n{caret_string}")
 }
 
-fn convert_error(error: &InternalError, text: &str) -> ElucidatorError {
+pub(crate) fn convert_error(error: &InternalError, text: &str) -> ElucidatorError {
     match error {
         InternalError::Parsing { offender, reason } => {
             let column_start = offender.column_start;
@@ -108,8 +445,11 @@ fn get_n_vals_from_buf<T: Representable + LeBufferRead>(
     T::get_n_le(&buffer.grab(T::bytes_needed(n))?, n)
 }
 
-fn get_box_dtype(buffer: &mut Buffer, dt: &Dtype) -> Result<Box<dyn Representable>> {
-    let b: Box<dyn Representable> = match dt {
+fn get_box_dtype(
+    buffer: &mut Buffer,
+    member: &MemberSpecification,
+) -> Result<Box<dyn Representable>> {
+    let b: Box<dyn Representable> = match member.dtype {
         Dtype::Byte => Box::new(get_val_from_buf::<u8>(buffer)?),
         Dtype::UnsignedInteger16 => Box::new(get_val_from_buf::<u16>(buffer)?),
         Dtype::UnsignedInteger32 => Box::new(get_val_from_buf::<u32>(buffer)?),
@@ -117,10 +457,16 @@ fn get_box_dtype(buffer: &mut Buffer, dt: &Dtype) -> Result<Box<dyn Representabl
         Dtype::SignedInteger8 => Box::new(get_val_from_buf::<i8>(buffer)?),
         Dtype::SignedInteger16 => Box::new(get_val_from_buf::<i16>(buffer)?),
         Dtype::SignedInteger32 => Box::new(get_val_from_buf::<i32>(buffer)?),
-        Dtype::SignedInteger64 => Box::new(get_val_from_buf::<i64>(buffer)?),
+        Dtype::SignedInteger64 | Dtype::Duration => Box::new(get_val_from_buf::<i64>(buffer)?),
         Dtype::Float32 => Box::new(get_val_from_buf::<f32>(buffer)?),
         Dtype::Float64 => Box::new(get_val_from_buf::<f64>(buffer)?),
-        Dtype::Str => Box::new(get_string_from_buf(buffer)?),
+        Dtype::Char => Box::new(char_from_le(&buffer.grab(4)?)?),
+        Dtype::Str => Box::new(get_string_from_buf(
+            buffer,
+            member,
+            StringPolicy::Strict,
+            StringEncoding::LengthPrefixed,
+        )?),
     };
     Ok(b)
 }
@@ -134,28 +480,65 @@ fn get_box_n_dtype(buffer: &mut Buffer, n: usize, dt: &Dtype) -> Result<Box<dyn
         Dtype::SignedInteger8 => Box::new(get_n_vals_from_buf::<i8>(buffer, n)?),
         Dtype::SignedInteger16 => Box::new(get_n_vals_from_buf::<i16>(buffer, n)?),
         Dtype::SignedInteger32 => Box::new(get_n_vals_from_buf::<i32>(buffer, n)?),
-        Dtype::SignedInteger64 => Box::new(get_n_vals_from_buf::<i64>(buffer, n)?),
+        Dtype::SignedInteger64 | Dtype::Duration => {
+            Box::new(get_n_vals_from_buf::<i64>(buffer, n)?)
+        }
         Dtype::Float32 => Box::new(get_n_vals_from_buf::<f32>(buffer, n)?),
         Dtype::Float64 => Box::new(get_n_vals_from_buf::<f64>(buffer, n)?),
-        Dtype::Str => {
-            unreachable!("Can't fetch arrays of strings");
+        Dtype::Str | Dtype::Char => {
+            return Err(ElucidatorError::UnsupportedArrayElement { dtype: dt.clone() });
         }
     };
     Ok(b)
 }
 
-fn get_string_from_buf(buffer: &mut Buffer) -> Result<String> {
-    let size = u64::from_le_bytes(buffer.grab(8)?.try_into().unwrap());
-    let databuf = buffer.grab(size as usize)?;
-    match String::from_utf8(databuf) {
-        Ok(s) => Ok(s),
-        Err(e) => Err(ElucidatorError::FromUtf8 { source: e }),
+/// Check a decoded string length against the cap of a `string(N)` member, if it has one.
+fn check_string_len(member: &MemberSpecification, found: usize) -> Result<()> {
+    match member.max_len {
+        Some(max) if found > max => Err(ElucidatorError::StringTooLong {
+            member: member.identifier.clone(),
+            max,
+            found,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn get_string_from_buf(
+    buffer: &mut Buffer,
+    member: &MemberSpecification,
+    policy: StringPolicy,
+    encoding: StringEncoding,
+) -> Result<String> {
+    let databuf = match encoding {
+        StringEncoding::LengthPrefixed => {
+            let size = u64::from_le_bytes(buffer.grab(8)?.try_into().unwrap());
+            // Check the prefix before grabbing so that an over-long string is never copied
+            check_string_len(member, size as usize)?;
+            buffer.grab(size as usize)?
+        }
+        StringEncoding::NulTerminated => {
+            let databuf = buffer.grab_until_nul()?.to_vec();
+            check_string_len(member, databuf.len())?;
+            databuf
+        }
+    };
+    match policy {
+        StringPolicy::Strict => match String::from_utf8(databuf) {
+            Ok(s) => Ok(s),
+            Err(e) => Err(ElucidatorError::FromUtf8 { source: e }),
+        },
+        StringPolicy::Lossy => Ok(String::from_utf8_lossy(&databuf).into_owned()),
     }
 }
 
 // DON'T USE THIS EXCEPT INSIDE OF INTERPRETING ENUMS
-fn get_singleton_from_buf(buffer: &mut Buffer, dt: &Dtype) -> Result<DataValue> {
-    match dt {
+pub(crate) fn get_singleton_from_buf(
+    buffer: &mut Buffer,
+    member: &MemberSpecification,
+    options: &InterpretOptions,
+) -> Result<DataValue> {
+    match member.dtype {
         Dtype::Byte => {
             let buf = buffer.grab(u8::bytes_needed(1))?;
             Ok(DataValue::Byte(u8::get_one_le(&buf)?))
@@ -196,20 +579,26 @@ fn get_singleton_from_buf(buffer: &mut Buffer, dt: &Dtype) -> Result<DataValue>
             let buf = buffer.grab(f64::bytes_needed(1))?;
             Ok(DataValue::Float64(f64::get_one_le(&buf)?))
         }
-        Dtype::Str => {
-            let string_length = u64::from_le_bytes(buffer.grab(8)?.try_into().unwrap());
-            let string_contents = buffer.grab(string_length as usize)?;
-            let s = match String::from_utf8(string_contents) {
-                Ok(o) => o,
-                Err(e) => Err(ElucidatorError::FromUtf8 { source: e })?,
-            };
-            Ok(DataValue::Str(s))
+        Dtype::Duration => {
+            let buf = buffer.grab(i64::bytes_needed(1))?;
+            Ok(DataValue::Duration(i64::get_one_le(&buf)?))
         }
+        Dtype::Char => Ok(DataValue::Char(char_from_le(&buffer.grab(4)?)?)),
+        Dtype::Str => Ok(DataValue::Str(get_string_from_buf(
+            buffer,
+            member,
+            options.string_policy,
+            options.string_encoding,
+        )?)),
     }
 }
 
 // DON'T USE THIS EXCEPT INSIDE OF INTERPRETING ENUMS
-fn get_array_from_buf(buffer: &mut Buffer, dt: &Dtype, items_to_read: usize) -> Result<DataValue> {
+pub(crate) fn get_array_from_buf(
+    buffer: &mut Buffer,
+    dt: &Dtype,
+    items_to_read: usize,
+) -> Result<DataValue> {
     match dt {
         Dtype::Byte => {
             let buf = &buffer.grab(u8::bytes_needed(items_to_read))?;
@@ -272,34 +661,142 @@ fn get_array_from_buf(buffer: &mut Buffer, dt: &Dtype, items_to_read: usize) ->
             let buf = &buffer.grab(f64::bytes_needed(items_to_read))?;
             Ok(DataValue::Float64Array(f64::get_n_le(buf, items_to_read)?))
         }
-        _ => {
-            unreachable!("Match statement has exhausted all array values for buffer reading");
+        Dtype::Duration => {
+            let buf = &buffer.grab(i64::bytes_needed(items_to_read))?;
+            Ok(DataValue::DurationArray(i64::get_n_le(buf, items_to_read)?))
+        }
+        Dtype::Str | Dtype::Char => {
+            Err(ElucidatorError::UnsupportedArrayElement { dtype: dt.clone() })
         }
     }
 }
 
 impl DesignationSpecification {
     pub fn from_text(text: &str) -> Result<Self> {
+        Self::from_text_with_policy(text, &IdentifierPolicy::default())
+    }
+
+    /// Parse a specification as with `from_text`, validating identifiers against `policy`.
+    pub fn from_text_with_policy(text: &str, policy: &IdentifierPolicy) -> Result<Self> {
         let parsed = parsing::get_metadataspec(text);
-        let validated = validating::validate_metadataspec(&parsed);
+        let validated = validating::validate_metadataspec_with_policy(&parsed, policy);
         match validated {
             Ok(members) => Ok(DesignationSpecification { members }),
             Err(e) => Err(convert_error(&e, text)),
         }
     }
 
+    /// Parse a specification as with `from_text`, but reject one with no members, such as an
+    /// empty or all-whitespace string. Use `from_text` where an empty specification is intended.
+    pub fn from_text_non_empty(text: &str) -> Result<Self> {
+        let spec = Self::from_text(text)?;
+        if spec.members.is_empty() {
+            Err(ElucidatorError::EmptySpecification)
+        } else {
+            Ok(spec)
+        }
+    }
+
+    /// Parse a specification as with `from_text`, also returning advisory warnings about
+    /// constructs that are valid but easy to misuse.
+    pub fn from_text_with_warnings(text: &str) -> Result<(Self, Vec<SpecWarning>)> {
+        let spec = Self::from_text(text)?;
+        let warnings = spec.warnings();
+        Ok((spec, warnings))
+    }
+
+    /// Advisory warnings about this specification; see [`SpecWarning`].
+    pub fn warnings(&self) -> Vec<SpecWarning> {
+        let dynamic = self.variable_size_members();
+        let mut warnings: Vec<SpecWarning> = self
+            .members
+            .iter()
+            .enumerate()
+            .filter(|(i, m)| dynamic.contains(&m.identifier) && i + 1 < self.members.len())
+            .map(|(i, m)| SpecWarning::DynamicMemberNotLast {
+                member: m.identifier.clone(),
+                followed_by: self.members[i + 1..]
+                    .iter()
+                    .map(|m| m.identifier.clone())
+                    .collect(),
+            })
+            .collect();
+        warnings.extend(
+            self.members
+                .iter()
+                .filter(|m| m.sizing == Sizing::Fixed(1))
+                .map(|m| SpecWarning::SingleElementArray {
+                    member: m.identifier.clone(),
+                }),
+        );
+        if let [only] = self.members.as_slice() {
+            if only.sizing == Sizing::Dynamic {
+                warnings.push(SpecWarning::LoneDynamicArray {
+                    member: only.identifier.clone(),
+                });
+            }
+        }
+        warnings
+    }
+
+    /// The members of this specification, in specification order.
+    pub fn members(&self) -> &[MemberSpecification] {
+        &self.members
+    }
+
+    /// Iterate over the identifiers of each member, in specification order.
+    pub fn member_names(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|m| m.identifier.as_str())
+    }
+
+    /// Get the specification of the member with the given identifier, if present.
+    pub fn member(&self, identifier: &str) -> Option<&MemberSpecification> {
+        self.members.iter().find(|m| m.identifier == identifier)
+    }
+
+    /// Iterate over the dtype of each member, in specification order.
+    pub fn member_dtypes(&self) -> impl Iterator<Item = &Dtype> {
+        self.members.iter().map(|m| &m.dtype)
+    }
+
+    /// Get the dtype and sizing of the member with the given identifier, if present.
+    pub fn dtype_of(&self, name: &str) -> Option<(Dtype, Sizing)> {
+        self.member(name)
+            .map(|m| (m.dtype.clone(), m.sizing.clone()))
+    }
+
     pub fn interpret(&self, buffer: &[u8]) -> Result<HashMap<&str, Box<dyn Representable>>> {
+        self.check_not_empty(buffer)?;
         let mut map = HashMap::new();
         let mut buf = Buffer::new(buffer);
         for member in &self.members {
+            if member.scaling.is_some() {
+                let value = match member.sizing {
+                    Sizing::Singleton => {
+                        get_singleton_from_buf(&mut buf, member, &InterpretOptions::default())?
+                    }
+                    Sizing::Fixed(n) => get_array_from_buf(&mut buf, &member.dtype, n as usize)?,
+                    Sizing::Dynamic => {
+                        let n = read_dynamic_len(&mut buf, member, None)?;
+                        get_array_from_buf(&mut buf, &member.dtype, n)?
+                    }
+                };
+                let val: Box<dyn Representable> = match member.apply_scaling(value) {
+                    DataValue::Float64(v) => Box::new(v),
+                    DataValue::Float64Array(v) => Box::new(v),
+                    _ => unreachable!("Scaled members always decode to f64"),
+                };
+                map.insert(member.identifier.as_str(), val);
+                continue;
+            }
             let val: Box<dyn Representable> = match member.sizing {
-                Sizing::Singleton => get_box_dtype(&mut buf, &member.dtype)?,
+                Sizing::Singleton => get_box_dtype(&mut buf, member)?,
                 Sizing::Fixed(n) => {
                     let n = n as usize;
                     get_box_n_dtype(&mut buf, n, &member.dtype)?
                 }
                 Sizing::Dynamic => {
-                    let n = u64::from_le_bytes(buf.grab(8)?.try_into().unwrap()) as usize;
+                    let n = read_dynamic_len(&mut buf, member, None)?;
                     get_box_n_dtype(&mut buf, n, &member.dtype)?
                 }
             };
@@ -308,610 +805,3366 @@ impl DesignationSpecification {
         Ok(map)
     }
 
-    pub fn interpret_enum(&self, buffer: &[u8]) -> Result<HashMap<&str, DataValue>> {
-        let mut map = HashMap::new();
-        let mut buf = Buffer::new(buffer);
-        for member in &self.members {
-            let member_name = member.identifier.as_str();
-            let value = match member.sizing {
-                Sizing::Singleton => get_singleton_from_buf(&mut buf, &member.dtype)?,
-                Sizing::Fixed(n) => get_array_from_buf(&mut buf, &member.dtype, n as usize)?,
-                Sizing::Dynamic => {
-                    let n = u64::from_le_bytes(buf.grab(8)?.try_into().unwrap());
-                    get_array_from_buf(&mut buf, &member.dtype, n as usize)?
-                }
-            };
-            map.insert(member_name, value);
+    /// Reject an entirely empty buffer up front, unless this specification has no members.
+    pub(crate) fn check_not_empty(&self, buffer: &[u8]) -> Result<()> {
+        if buffer.is_empty() && !self.members.is_empty() {
+            Err(ElucidatorError::EmptyBuffer {
+                expected_at_least: self.fixed_size_bytes(),
+            })
+        } else {
+            Ok(())
         }
-        Ok(map)
     }
-}
 
-impl std::fmt::Display for DesignationSpecification {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let m = self
-            .members
+    /// Number of bytes every buffer of this specification occupies regardless of its contents.
+    /// This counts singletons, fixed arrays, and the 8-byte length prefix of each dynamic
+    /// array or string, but not their contents.
+    pub fn fixed_size_bytes(&self) -> usize {
+        self.members
             .iter()
-            .map(MemberSpecification::to_string)
-            .collect::<Vec<String>>()
-            .join(", ");
-        write!(f, "{m}")
+            .map(|m| match (&m.sizing, m.dtype.get_size()) {
+                (Sizing::Singleton, Some(size)) => size,
+                (Sizing::Fixed(n), Some(size)) => size * *n as usize,
+                _ => 8,
+            })
+            .sum()
     }
-}
 
-#[cfg(test)]
-mod test {
-    use std::collections::HashSet;
+    /// Names of members whose size in bytes depends on the buffer contents.
+    fn variable_size_members(&self) -> Vec<String> {
+        self.layout()
+            .into_iter()
+            .filter(|entry| entry.element_count.is_none() || entry.dtype.get_size().is_none())
+            .map(|entry| entry.name)
+            .collect()
+    }
 
-    use super::*;
-    use crate::{
-        member::{Dtype, Sizing},
-        test_utils,
-        value::DataValue,
-    };
-    use pretty_assertions::assert_eq;
-    use rand::{random, Rng};
+    /// Check that `buffer` is exactly as long as a record of this fixed-size specification.
+    /// Specifications with dynamic arrays or strings cannot be checked this way; use
+    /// `interpret_enum` to validate those.
+    pub fn validate_buffer_length(&self, buffer: &[u8]) -> Result<()> {
+        let dynamic_members = self.variable_size_members();
+        if !dynamic_members.is_empty() {
+            return Err(ElucidatorError::NotFixedSize { dynamic_members });
+        }
+        let expected = self.fixed_size_bytes();
+        if buffer.len() == expected {
+            Ok(())
+        } else {
+            Err(ElucidatorError::BufferSizing {
+                expected,
+                found: buffer.len(),
+            })
+        }
+    }
 
-    type DataMap<'a> = HashMap<&'a str, Box<dyn Representable>>;
+    /// Whether both specifications have the same members, by name, dtype, and sizing, in any
+    /// order. This is semantic equivalence only: buffers are laid out in member order, so two
+    /// equivalent specifications with different orders are not wire-compatible.
+    pub fn equivalent_unordered(&self, other: &DesignationSpecification) -> bool {
+        self.members.len() == other.members.len()
+            && self.members.iter().all(|m| {
+                other
+                    .member(&m.identifier)
+                    .is_some_and(|o| o.dtype == m.dtype && o.sizing == m.sizing)
+            })
+    }
 
-    fn make_dyn_box<T: Representable + 'static>(item: T) -> Box<dyn Representable> {
-        Box::new(item)
+    /// Extract the member `name` from each of `records` as a `T`, in record order.
+    /// Fails on the first record lacking the member or holding a different type.
+    pub fn collect_member<T: FromDataValue>(
+        records: &[HashMap<&str, DataValue>],
+        name: &str,
+    ) -> Result<Vec<T>> {
+        records
+            .iter()
+            .map(|record| {
+                let value = record
+                    .get(name)
+                    .ok_or_else(|| ElucidatorError::MissingValue {
+                        member: name.to_string(),
+                    })?;
+                T::from_data_value(value)
+            })
+            .collect()
     }
 
-    fn compare_hashmap(left: &DataMap, right: &DataMap) {
-        let left_keys: HashSet<&str> = left.keys().copied().collect();
-        let right_keys: HashSet<&str> = right.keys().copied().collect();
+    /// Decode two buffers of this specification and find the first member, in specification
+    /// order, whose values differ, along with its value in `a_buf` and in `b_buf`.
+    /// Values are compared by their encoding, so NaNs with the same bits are equal while
+    /// `0.0` and `-0.0` are not. Fails if either buffer cannot be decoded.
+    /// ```
+    /// use elucidator::{designation::DesignationSpecification, value::DataValue};
+    ///
+    /// let spec = DesignationSpecification::from_text("a: u8, b: u16").unwrap();
+    /// let diff = spec.diff_records(&[1, 2, 0], &[1, 3, 0]).unwrap();
+    /// assert_eq!(
+    ///     diff,
+    ///     Some((
+    ///         "b".to_string(),
+    ///         DataValue::UnsignedInteger16(2),
+    ///         DataValue::UnsignedInteger16(3)
+    ///     ))
+    /// );
+    /// ```
+    pub fn diff_records(
+        &self,
+        a_buf: &[u8],
+        b_buf: &[u8],
+    ) -> Result<Option<(String, DataValue, DataValue)>> {
+        let mut a = self.interpret_enum(a_buf)?;
+        let mut b = self.interpret_enum(b_buf)?;
+        let Some(name) = self
+            .member_names()
+            .find(|name| a[name].as_buffer() != b[name].as_buffer())
+        else {
+            return Ok(None);
+        };
+        Ok(Some((
+            name.to_string(),
+            a.remove(name).unwrap(),
+            b.remove(name).unwrap(),
+        )))
+    }
 
-        pretty_assertions::assert_eq!(left_keys, right_keys);
+    /// Decode two buffers of this specification and list the members, in specification order,
+    /// whose values differ. Values are compared as in [`DesignationSpecification::diff_records`].
+    /// Fails if either buffer cannot be decoded.
+    /// ```
+    /// use elucidator::designation::DesignationSpecification;
+    ///
+    /// let spec = DesignationSpecification::from_text("a: u8, b: u16, c: u8").unwrap();
+    /// let changed = spec.changed_members(&[1, 2, 0, 5], &[1, 3, 0, 6]).unwrap();
+    /// assert_eq!(changed, vec!["b", "c"]);
+    /// ```
+    pub fn changed_members(&self, old_buf: &[u8], new_buf: &[u8]) -> Result<Vec<&str>> {
+        let old = self.interpret_enum(old_buf)?;
+        let new = self.interpret_enum(new_buf)?;
+        Ok(self
+            .member_names()
+            .filter(|name| old[name].as_buffer() != new[name].as_buffer())
+            .collect())
+    }
 
-        for key in left_keys {
-            let lvalue = left.get(key).unwrap();
-            let rvalue = right.get(key).unwrap();
+    /// Compare this specification against a `new` version of it.
+    pub fn diff(&self, new: &DesignationSpecification) -> SpecDiff {
+        let added = new
+            .members
+            .iter()
+            .filter(|m| self.member(&m.identifier).is_none())
+            .map(|m| m.identifier.clone())
+            .collect();
+        let removed = self
+            .members
+            .iter()
+            .filter(|m| new.member(&m.identifier).is_none())
+            .map(|m| m.identifier.clone())
+            .collect();
+        let changed = self
+            .members
+            .iter()
+            .filter(|m| {
+                new.member(&m.identifier)
+                    .is_some_and(|other| !same_layout(m, other))
+            })
+            .map(|m| m.identifier.clone())
+            .collect();
+        let append_only = new.members.len() >= self.members.len()
+            && self
+                .members
+                .iter()
+                .zip(&new.members)
+                .all(|(old, new)| same_layout(old, new));
+        SpecDiff {
+            added,
+            removed,
+            changed,
+            append_only,
+        }
+    }
 
-            pretty_assertions::assert_eq!(lvalue.get_dtype(), rvalue.get_dtype());
-            pretty_assertions::assert_eq!(lvalue.is_array(), rvalue.is_array());
+    /// Describe where each member sits in a buffer, in specification order.
+    /// The offset of a dynamic array or string points at its 8-byte length prefix.
+    pub fn layout(&self) -> Vec<LayoutEntry> {
+        let mut offset = Some(0);
+        self.members
+            .iter()
+            .map(|m| {
+                let (element_count, size) = match (&m.sizing, m.dtype.get_size()) {
+                    (Sizing::Singleton, Some(size)) => (Some(1), Some(size)),
+                    (Sizing::Fixed(n), Some(size)) => (Some(*n as usize), Some(size * *n as usize)),
+                    (Sizing::Singleton, None) => (Some(1), None),
+                    _ => (None, None),
+                };
+                let entry = LayoutEntry {
+                    name: m.identifier.clone(),
+                    offset,
+                    dtype: m.dtype.clone(),
+                    element_count,
+                };
+                offset = offset.zip(size).map(|(o, s)| o + s);
+                entry
+            })
+            .collect()
+    }
 
-            if lvalue.is_array() {
-                match lvalue.get_dtype() {
-                    Dtype::Byte => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_u8().unwrap(),
-                            rvalue.as_vec_u8().unwrap()
-                        );
-                    }
-                    Dtype::UnsignedInteger16 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_u16().unwrap(),
-                            rvalue.as_vec_u16().unwrap()
-                        );
-                    }
-                    Dtype::UnsignedInteger32 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_u32().unwrap(),
-                            rvalue.as_vec_u32().unwrap()
-                        );
-                    }
-                    Dtype::UnsignedInteger64 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_u64().unwrap(),
-                            rvalue.as_vec_u64().unwrap()
-                        );
-                    }
-                    Dtype::SignedInteger8 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_i8().unwrap(),
-                            rvalue.as_vec_i8().unwrap()
-                        );
-                    }
-                    Dtype::SignedInteger16 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_i16().unwrap(),
-                            rvalue.as_vec_i16().unwrap()
-                        );
-                    }
-                    Dtype::SignedInteger32 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_i32().unwrap(),
-                            rvalue.as_vec_i32().unwrap()
-                        );
-                    }
-                    Dtype::SignedInteger64 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_i64().unwrap(),
-                            rvalue.as_vec_i64().unwrap()
-                        );
-                    }
-                    Dtype::Float32 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_f32().unwrap(),
-                            rvalue.as_vec_f32().unwrap()
-                        );
-                    }
-                    Dtype::Float64 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_vec_f64().unwrap(),
-                            rvalue.as_vec_f64().unwrap()
-                        );
-                    }
-                    Dtype::Str => {
-                        unreachable!("String array");
-                    }
-                }
-            } else {
-                match lvalue.get_dtype() {
-                    Dtype::Byte => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_u8().unwrap(),
-                            rvalue.as_u8().unwrap()
-                        );
-                    }
-                    Dtype::UnsignedInteger16 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_u16().unwrap(),
-                            rvalue.as_u16().unwrap()
-                        );
-                    }
-                    Dtype::UnsignedInteger32 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_u32().unwrap(),
-                            rvalue.as_u32().unwrap()
-                        );
-                    }
-                    Dtype::UnsignedInteger64 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_u64().unwrap(),
-                            rvalue.as_u64().unwrap()
-                        );
-                    }
-                    Dtype::SignedInteger8 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_i8().unwrap(),
-                            rvalue.as_i8().unwrap()
-                        );
-                    }
-                    Dtype::SignedInteger16 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_i16().unwrap(),
-                            rvalue.as_i16().unwrap()
-                        );
-                    }
-                    Dtype::SignedInteger32 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_i32().unwrap(),
-                            rvalue.as_i32().unwrap()
-                        );
-                    }
-                    Dtype::SignedInteger64 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_i64().unwrap(),
-                            rvalue.as_i64().unwrap()
-                        );
-                    }
-                    Dtype::Float32 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_f32().unwrap(),
-                            rvalue.as_f32().unwrap()
-                        );
-                    }
-                    Dtype::Float64 => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_f64().unwrap(),
-                            rvalue.as_f64().unwrap()
-                        );
-                    }
-                    Dtype::Str => {
-                        pretty_assertions::assert_eq!(
-                            lvalue.as_string().unwrap(),
-                            rvalue.as_string().unwrap()
-                        );
-                    }
-                }
-            }
+    /// Number of bytes the record at the start of `buffer` occupies, found by walking its members
+    fn record_len(&self, buffer: &[u8]) -> Result<usize> {
+        let mut buf = Buffer::new(buffer);
+        for member in &self.members {
+            Self::skip_member(&mut buf, member)?;
         }
+        Ok(buf.position())
     }
 
-    #[test]
-    fn multiple_members_ok() {
-        let text = "foo: u32, bar: f32[10], baz: string";
-        let dspec = DesignationSpecification::from_text(text);
-        assert_eq!(
-            dspec,
-            Ok(DesignationSpecification {
-                members: vec![
-                    MemberSpecification::from_parts(
-                        "foo",
-                        &Sizing::Singleton,
-                        &Dtype::UnsignedInteger32,
-                    ),
-                    MemberSpecification::from_parts("bar", &Sizing::Fixed(10), &Dtype::Float32,),
-                    MemberSpecification::from_parts("baz", &Sizing::Singleton, &Dtype::Str,),
-                ]
-            })
-        );
+    /// Step over one member's bytes, including any length prefix, without decoding them
+    fn skip_member(buf: &mut Buffer, member: &MemberSpecification) -> Result<()> {
+        let n = match member.sizing {
+            Sizing::Singleton => 1,
+            Sizing::Fixed(n) => n as usize,
+            Sizing::Dynamic => read_dynamic_len(buf, member, None)?,
+        };
+        let bytes = match member.dtype.get_size() {
+            Some(size) => size.saturating_mul(n),
+            None => u64::from_le_bytes(buf.grab(8)?.try_into().unwrap()) as usize,
+        };
+        buf.skip(bytes)
     }
 
-    #[test]
-    fn simple_ok() {
-        let text = "foo: u32, bar: i32";
-        let dspec = DesignationSpecification::from_text(text).unwrap();
-        let expected: DataMap = HashMap::from([
-            ("foo", make_dyn_box(10_u32)),
-            ("bar", make_dyn_box(-10_i32)),
-        ]);
-        let buffer = expected
-            .get("foo")
-            .unwrap()
-            .as_u32()
-            .unwrap()
-            .to_le_bytes()
+    /// Number of leading members that can be read in full from a possibly truncated `buffer`,
+    /// stopping at the first member that would run past its end.
+    pub fn decodable_prefix(&self, buffer: &[u8]) -> usize {
+        let mut buf = Buffer::new(buffer);
+        self.members
             .iter()
-            .chain(
-                expected
-                    .get("bar")
-                    .unwrap()
-                    .as_i32()
-                    .unwrap()
-                    .to_le_bytes()
-                    .iter(),
-            )
-            .copied()
-            .collect::<Vec<u8>>();
-        let result = dspec.interpret(&buffer).unwrap();
-        compare_hashmap(&expected, &result);
+            .take_while(|member| Self::skip_member(&mut buf, member).is_ok())
+            .count()
     }
 
-    #[test]
-    fn simple_fixed_vec_ok() {
-        let text = "foo: u32[3], bar: string";
-        let dspec = DesignationSpecification::from_text(text).unwrap();
-        let expected: DataMap = HashMap::from([
-            ("foo", make_dyn_box(vec![2_u32, 10_u32, 0xDEADBEEF_u32])),
-            ("bar", make_dyn_box(test_utils::crab_emoji())),
-        ]);
-        let buffer = expected
-            .get("foo")
-            .unwrap()
-            .as_vec_u32()
-            .unwrap()
-            .as_buffer()
-            .iter()
-            .chain(
-                expected
-                    .get("bar")
-                    .unwrap()
-                    .as_string()
-                    .unwrap()
-                    .as_buffer()
-                    .iter(),
-            )
-            .copied()
-            .collect::<Vec<u8>>();
-        let result = dspec.interpret(&buffer).unwrap();
-        compare_hashmap(&expected, &result);
+    /// Split a buffer of back-to-back records into one slice per record.
+    /// Specifications without dynamic members are split every `fixed_size_bytes`; otherwise each
+    /// record's length is found by walking its members.
+    pub fn split_records<'a>(&self, buffer: &'a [u8]) -> Result<Vec<&'a [u8]>> {
+        let mut records = Vec::new();
+        if self.variable_size_members().is_empty() {
+            let size = self.fixed_size_bytes();
+            if size == 0 || !buffer.len().is_multiple_of(size) {
+                return Err(ElucidatorError::BufferSizing {
+                    expected: size * (buffer.len() / size.max(1) + 1),
+                    found: buffer.len(),
+                });
+            }
+            records.extend(buffer.chunks_exact(size));
+        } else {
+            let mut rest = buffer;
+            while !rest.is_empty() {
+                let (record, tail) = rest.split_at(self.record_len(rest)?);
+                records.push(record);
+                rest = tail;
+            }
+        }
+        Ok(records)
     }
 
-    #[test]
-    fn simple_dynamic_vec_ok() {
-        let text = "foo: u32[], bar: string";
-        let dspec = DesignationSpecification::from_text(text).unwrap();
-        let expected: DataMap = HashMap::from([
-            ("foo", make_dyn_box(vec![2_u32, 10_u32, 0xDEADBEEF_u32])),
-            ("bar", make_dyn_box(test_utils::crab_emoji())),
-        ]);
-        let buffer = [0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+    /// One-line summary of buffer sizing, e.g. `fixed: 20 bytes + dynamic (foo, bar)`.
+    /// Documented dynamic members are listed with their documentation.
+    pub fn describe_sizes(&self) -> String {
+        let dynamic: Vec<String> = self
+            .members
             .iter()
-            .chain(
-                expected
-                    .get("foo")
-                    .unwrap()
-                    .as_vec_u32()
-                    .unwrap()
-                    .as_buffer()
-                    .iter(),
-            )
-            .chain(
-                expected
-                    .get("bar")
-                    .unwrap()
-                    .as_string()
-                    .unwrap()
-                    .as_buffer()
-                    .iter(),
-            )
-            .copied()
-            .collect::<Vec<u8>>();
-        let result = dspec.interpret(&buffer).unwrap();
-        compare_hashmap(&expected, &result);
+            .filter(|m| m.sizing == Sizing::Dynamic || m.dtype == Dtype::Str)
+            .map(|m| match &m.doc {
+                Some(doc) => format!("{} \"{doc}\"", m.identifier),
+                None => m.identifier.clone(),
+            })
+            .collect();
+        let fixed = format!("fixed: {} bytes", self.fixed_size_bytes());
+        if dynamic.is_empty() {
+            fixed
+        } else {
+            format!("{fixed} + dynamic ({})", dynamic.join(", "))
+        }
     }
 
-    #[test]
-    fn interpret_u8_ok() {
-        let text = "foo: u8";
-        let dspec = DesignationSpecification::from_text(text).unwrap();
-        let val: u8 = 10;
-        let result = dspec.interpret(val.to_le_bytes().as_ref());
-        let result_val = result.unwrap().get("foo").unwrap().as_u8().unwrap();
-        assert_eq!(val, result_val);
+    /// Find the first float member of `buffer` holding a NaN or infinite value.
+    /// Only float members are decoded; all other members are skipped over.
+    pub fn first_non_finite_member(&self, buffer: &[u8]) -> Result<Option<&str>> {
+        let mut buf = Buffer::new(buffer);
+        for member in &self.members {
+            let n = match member.sizing {
+                Sizing::Singleton => 1,
+                Sizing::Fixed(n) => n as usize,
+                Sizing::Dynamic => read_dynamic_len(&mut buf, member, None)?,
+            };
+            let finite = match member.dtype {
+                Dtype::Float32 => get_n_vals_from_buf::<f32>(&mut buf, n)?
+                    .iter()
+                    .all(|v| v.is_finite()),
+                Dtype::Float64 => get_n_vals_from_buf::<f64>(&mut buf, n)?
+                    .iter()
+                    .all(|v| v.is_finite()),
+                Dtype::Str => {
+                    let size = u64::from_le_bytes(buf.grab(8)?.try_into().unwrap());
+                    buf.grab(size as usize)?;
+                    true
+                }
+                _ => {
+                    buf.grab(member.dtype.get_size().unwrap() * n)?;
+                    true
+                }
+            };
+            if !finite {
+                return Ok(Some(member.identifier.as_str()));
+            }
+        }
+        Ok(None)
     }
 
-    fn random_data_value(dt: &Dtype, sizing: &Sizing) -> DataValue {
-        let items = match sizing {
-            Sizing::Singleton => 1,
-            Sizing::Fixed(n) => *n,
-            Sizing::Dynamic => (random::<u8>() % 100 + 1) as u64,
+    /// Guess the byte order of `buffer` by decoding it both ways and counting implausible
+    /// values, such as non-finite floats or integers using their high-order bytes. This is a
+    /// best-effort diagnostic; specifications of only single bytes and strings are always
+    /// `Ambiguous`.
+    pub fn detect_endianness(&self, buffer: &[u8]) -> EndiannessGuess {
+        let little = self.implausible_elements(buffer, false);
+        let big = self.implausible_elements(buffer, true);
+        match (little, big) {
+            (Some(l), Some(b)) if l < b => EndiannessGuess::Little,
+            (Some(l), Some(b)) if b < l => EndiannessGuess::Big,
+            (Some(_), None) => EndiannessGuess::Little,
+            (None, Some(_)) => EndiannessGuess::Big,
+            _ => EndiannessGuess::Ambiguous,
+        }
+    }
+
+    /// Count the implausible elements of `buffer` read in the given byte order, or `None` if the
+    /// buffer cannot be walked that way. Leftover bytes count as one implausible element.
+    fn implausible_elements(&self, buffer: &[u8], big_endian: bool) -> Option<usize> {
+        let read_len = |buf: &mut Buffer| -> Option<usize> {
+            let bytes: [u8; 8] = buf.grab_slice(8).ok()?.try_into().unwrap();
+            let len = if big_endian {
+                u64::from_be_bytes(bytes)
+            } else {
+                u64::from_le_bytes(bytes)
+            };
+            usize::try_from(len).ok()
         };
-        match dt {
-            Dtype::Byte => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::Byte(random())
-                } else {
-                    DataValue::ByteArray((0..items).map(|_| random::<u8>()).collect())
+        let mut buf = Buffer::new(buffer);
+        let mut implausible = 0;
+        for member in &self.members {
+            let n = match member.sizing {
+                Sizing::Singleton => 1,
+                Sizing::Fixed(n) => n as usize,
+                Sizing::Dynamic => read_len(&mut buf)?,
+            };
+            let Some(size) = member.dtype.get_size() else {
+                let len = read_len(&mut buf)?;
+                buf.skip(len).ok()?;
+                continue;
+            };
+            let bytes = buf.grab_slice(size.checked_mul(n)?).ok()?;
+            for element in bytes.chunks_exact(size) {
+                let mut le = element.to_vec();
+                if big_endian {
+                    le.reverse();
                 }
-            }
-            Dtype::UnsignedInteger16 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::UnsignedInteger16(random())
-                } else {
-                    DataValue::UnsignedInteger16Array((0..items).map(|_| random::<u16>()).collect())
+                if !plausible_element(&member.dtype, &le) {
+                    implausible += 1;
                 }
             }
-            Dtype::UnsignedInteger32 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::UnsignedInteger32(random())
-                } else {
-                    DataValue::UnsignedInteger32Array((0..items).map(|_| random::<u32>()).collect())
-                }
+        }
+        if buf.remaining() > 0 {
+            implausible += 1;
+        }
+        Some(implausible)
+    }
+
+    pub fn interpret_enum(&self, buffer: &[u8]) -> Result<HashMap<&str, DataValue>> {
+        self.interpret_enum_with_policy(buffer, StringPolicy::default())
+    }
+
+    /// Decode the record at the start of `bytes`, returning it along with its length so that
+    /// the following record can be found. Any bytes after the record are left untouched, so
+    /// `bytes` may be a view into a much larger region such as a memory-mapped file.
+    /// ```
+    /// use elucidator::designation::DesignationSpecification;
+    ///
+    /// let spec = DesignationSpecification::from_text("a: u16").unwrap();
+    /// let bytes = [1, 0, 2, 0];
+    /// let (first, len) = spec.interpret_enum_from(&bytes).unwrap();
+    /// let (second, _) = spec.interpret_enum_from(&bytes[len..]).unwrap();
+    /// assert_eq!(first["a"].to_string(), "1");
+    /// assert_eq!(second["a"].to_string(), "2");
+    /// ```
+    pub fn interpret_enum_from(&self, bytes: &[u8]) -> Result<(HashMap<&str, DataValue>, usize)> {
+        let len = self.record_len(bytes)?;
+        Ok((self.interpret_enum(&bytes[..len])?, len))
+    }
+
+    /// Interpret only the members named in `names`, skipping over the rest without decoding them.
+    /// Names that are not members of this specification are ignored.
+    pub fn interpret_subset(
+        &self,
+        buffer: &[u8],
+        names: &[&str],
+    ) -> Result<HashMap<&str, DataValue>> {
+        let mut map = HashMap::new();
+        let mut buf = Buffer::new(buffer);
+        for member in &self.members {
+            if map.len() == names.len() {
+                break;
             }
-            Dtype::UnsignedInteger64 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::UnsignedInteger64(random())
-                } else {
-                    DataValue::UnsignedInteger64Array((0..items).map(|_| random::<u64>()).collect())
-                }
+            let member_name = member.identifier.as_str();
+            if names.contains(&member_name) {
+                let value = match member.sizing {
+                    Sizing::Singleton => {
+                        get_singleton_from_buf(&mut buf, member, &InterpretOptions::default())?
+                    }
+                    Sizing::Fixed(n) => get_array_from_buf(&mut buf, &member.dtype, n as usize)?,
+                    Sizing::Dynamic => {
+                        let n = read_dynamic_len(&mut buf, member, None)?;
+                        get_array_from_buf(&mut buf, &member.dtype, n)?
+                    }
+                };
+                map.insert(member_name, member.apply_scaling(value));
+                continue;
             }
-            Dtype::SignedInteger8 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::SignedInteger8(random())
-                } else {
-                    DataValue::SignedInteger8Array((0..items).map(|_| random::<i8>()).collect())
+            let n = match member.sizing {
+                Sizing::Singleton => 1,
+                Sizing::Fixed(n) => n as usize,
+                Sizing::Dynamic => read_dynamic_len(&mut buf, member, None)?,
+            };
+            match member.dtype.get_size() {
+                Some(size) => buf.grab(size.saturating_mul(n))?,
+                None => {
+                    let size = u64::from_le_bytes(buf.grab(8)?.try_into().unwrap());
+                    buf.grab(size as usize)?
                 }
-            }
-            Dtype::SignedInteger16 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::SignedInteger16(random())
-                } else {
-                    DataValue::SignedInteger16Array((0..items).map(|_| random::<i16>()).collect())
+            };
+        }
+        Ok(map)
+    }
+
+    /// Interpret a buffer as with `interpret_enum`, decoding string members according to `policy`.
+    pub fn interpret_enum_with_policy(
+        &self,
+        buffer: &[u8],
+        policy: StringPolicy,
+    ) -> Result<HashMap<&str, DataValue>> {
+        let options = InterpretOptions {
+            string_policy: policy,
+            ..Default::default()
+        };
+        self.interpret_enum_with_options(buffer, &options)
+    }
+
+    /// Interpret a buffer whose members start after a fixed-size header of `skip` bytes.
+    pub fn interpret_enum_after(
+        &self,
+        buffer: &[u8],
+        skip: usize,
+    ) -> Result<HashMap<&str, DataValue>> {
+        let options = InterpretOptions {
+            skip_header_bytes: skip,
+            ..Default::default()
+        };
+        self.interpret_enum_with_options(buffer, &options)
+    }
+
+    /// Interpret a buffer as with `interpret_enum`, carrying on past members that fail to
+    /// decode instead of discarding the whole record. A fixed-size member that fails is given
+    /// a zero sentinel of its dtype (an empty array for arrays) and reported in the returned
+    /// errors; decoding continues since the offsets of later members are still known. A string
+    /// or dynamic array that fails leaves later offsets unknown, so decoding stops there and
+    /// the remaining members are absent from the map.
+    pub fn interpret_enum_recovering(
+        &self,
+        buffer: &[u8],
+    ) -> (HashMap<&str, DataValue>, Vec<MemberDecodeError>) {
+        let mut map = HashMap::new();
+        let mut errors = Vec::new();
+        let mut buf = Buffer::new(buffer);
+        let options = InterpretOptions::default();
+        for member in &self.members {
+            let member_name = member.identifier.as_str();
+            let value = match member.sizing {
+                Sizing::Singleton => get_singleton_from_buf(&mut buf, member, &options),
+                Sizing::Fixed(n) => get_array_from_buf(&mut buf, &member.dtype, n as usize),
+                Sizing::Dynamic => read_dynamic_len(&mut buf, member, None)
+                    .and_then(|n| get_array_from_buf(&mut buf, &member.dtype, n)),
+            };
+            match value {
+                Ok(value) => {
+                    map.insert(member_name, member.apply_scaling(value));
                 }
-            }
-            Dtype::SignedInteger32 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::SignedInteger32(random())
-                } else {
-                    DataValue::SignedInteger32Array((0..items).map(|_| random::<i32>()).collect())
+                Err(error) => {
+                    errors.push(MemberDecodeError {
+                        member: member_name.to_string(),
+                        error,
+                    });
+                    if member.dtype == Dtype::Str || member.sizing == Sizing::Dynamic {
+                        break;
+                    }
+                    let sentinel =
+                        DataValue::zeroed(&member.dtype, member.sizing != Sizing::Singleton);
+                    map.insert(member_name, member.apply_scaling(sentinel));
                 }
             }
-            Dtype::SignedInteger64 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::SignedInteger64(random())
-                } else {
-                    DataValue::SignedInteger64Array((0..items).map(|_| random::<i64>()).collect())
-                }
+        }
+        (map, errors)
+    }
+
+    /// Interpret a buffer as with `interpret_enum`, renaming output keys according to `rename`.
+    /// Members absent from `rename` keep their own names. It is an error for two members to
+    /// end up with the same name.
+    /// ```
+    /// use std::collections::HashMap;
+    /// use elucidator::designation::DesignationSpecification;
+    ///
+    /// let spec = DesignationSpecification::from_text("lat: f64").unwrap();
+    /// let rename = HashMap::from([("lat", "latitude")]);
+    /// let values = spec.interpret_enum_renamed(&1.5f64.to_le_bytes(), &rename).unwrap();
+    ///
+    /// # assert!(values.contains_key("latitude"));
+    /// ```
+    pub fn interpret_enum_renamed(
+        &self,
+        buffer: &[u8],
+        rename: &HashMap<&str, &str>,
+    ) -> Result<HashMap<String, DataValue>> {
+        let values = self.interpret_enum(buffer)?;
+        let mut renamed = HashMap::with_capacity(values.len());
+        for (name, value) in values {
+            let new_name = rename.get(name).copied().unwrap_or(name);
+            if renamed.insert(new_name.to_string(), value).is_some() {
+                return Err(ElucidatorError::RenameCollision {
+                    name: new_name.to_string(),
+                });
             }
-            Dtype::Float32 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::Float32(random())
-                } else {
-                    DataValue::Float32Array((0..items).map(|_| random::<f32>()).collect())
+        }
+        Ok(renamed)
+    }
+
+    /// Interpret a buffer as with `interpret_enum`, according to `options`.
+    pub fn interpret_enum_with_options(
+        &self,
+        buffer: &[u8],
+        options: &InterpretOptions,
+    ) -> Result<HashMap<&str, DataValue>> {
+        self.check_not_empty(buffer)?;
+        let full_len = options.skip_header_bytes + self.fixed_size_bytes();
+        let padded;
+        let buffer = if options.pad_short_buffer
+            && buffer.len() < full_len
+            && self.variable_size_members().is_empty()
+        {
+            padded = {
+                let mut padded = buffer.to_vec();
+                padded.resize(full_len, 0);
+                padded
+            };
+            &padded
+        } else {
+            buffer
+        };
+        let mut map = HashMap::new();
+        let mut buf = Buffer::new(buffer);
+        buf.grab(options.skip_header_bytes)?;
+        for member in &self.members {
+            let member_name = member.identifier.as_str();
+            let value = match member.sizing {
+                Sizing::Singleton => get_singleton_from_buf(&mut buf, member, options)?,
+                Sizing::Fixed(n) => get_array_from_buf(&mut buf, &member.dtype, n as usize)?,
+                Sizing::Dynamic => {
+                    let n = read_dynamic_len(&mut buf, member, options.max_dynamic_elements)?;
+                    get_array_from_buf(&mut buf, &member.dtype, n)?
                 }
+            };
+            let value = match &member.enum_mapping {
+                Some(mapping) if options.enum_names => value.named(mapping),
+                _ => member.apply_scaling(value),
+            };
+            map.insert(member_name, value);
+        }
+        Ok(map)
+    }
+
+    /// Interpret a buffer as with `interpret_enum`, writing the values into `scratch` and
+    /// returning them from there. Array and string allocations from the previous decode are
+    /// reused, which avoids allocator churn when decoding many records in a loop. Members with
+    /// fixed-point scaling are still decoded into fresh values.
+    pub fn interpret_enum_reuse<'s, 'a>(
+        &'a self,
+        buffer: &[u8],
+        scratch: &'s mut DecodeScratch<'a>,
+    ) -> Result<&'s HashMap<&'a str, DataValue>> {
+        self.check_not_empty(buffer)?;
+        let mut buf = Buffer::new(buffer);
+        for member in &self.members {
+            let member_name = member.identifier.as_str();
+            if member.scaling.is_some() {
+                let value = match member.sizing {
+                    Sizing::Singleton => {
+                        get_singleton_from_buf(&mut buf, member, &InterpretOptions::default())?
+                    }
+                    Sizing::Fixed(n) => get_array_from_buf(&mut buf, &member.dtype, n as usize)?,
+                    Sizing::Dynamic => {
+                        let n = read_dynamic_len(&mut buf, member, None)?;
+                        get_array_from_buf(&mut buf, &member.dtype, n)?
+                    }
+                };
+                scratch
+                    .values
+                    .insert(member_name, member.apply_scaling(value));
+                continue;
             }
-            Dtype::Float64 => {
-                if sizing == &Sizing::Singleton {
-                    DataValue::Float64(random())
-                } else {
-                    DataValue::Float64Array((0..items).map(|_| random::<f64>()).collect())
+            if member.dtype == Dtype::Str {
+                if member.sizing != Sizing::Singleton {
+                    return Err(ElucidatorError::UnsupportedArrayElement { dtype: Dtype::Str });
                 }
+                let size = u64::from_le_bytes(buf.grab_slice(8)?.try_into().unwrap());
+                check_string_len(member, size as usize)?;
+                let bytes = buf.grab_slice(size as usize)?;
+                let text = std::str::from_utf8(bytes).map_err(|_| ElucidatorError::FromUtf8 {
+                    source: String::from_utf8(bytes.to_vec()).unwrap_err(),
+                })?;
+                match scratch.values.get_mut(member_name) {
+                    Some(DataValue::Str(old)) => {
+                        old.clear();
+                        old.push_str(text);
+                    }
+                    _ => {
+                        scratch
+                            .values
+                            .insert(member_name, DataValue::Str(text.to_string()));
+                    }
+                }
+                continue;
             }
-            Dtype::Str => {
-                let n_chars = random::<u8>() % 10;
-                let s = (0..n_chars).map(|_| random::<char>()).collect();
-                DataValue::Str(s)
+            let n = match member.sizing {
+                Sizing::Singleton => None,
+                Sizing::Fixed(n) => Some(n as usize),
+                Sizing::Dynamic => Some(read_dynamic_len(&mut buf, member, None)?),
+            };
+            let slot = scratch
+                .values
+                .entry(member_name)
+                .or_insert_with(|| DataValue::zeroed(&member.dtype, n.is_some()));
+            if slot.dtype() != member.dtype || slot.array_len().is_some() != n.is_some() {
+                *slot = DataValue::zeroed(&member.dtype, n.is_some());
             }
+            refill!(slot, buf, n,
+                Byte, ByteArray, u8;
+                UnsignedInteger16, UnsignedInteger16Array, u16;
+                UnsignedInteger32, UnsignedInteger32Array, u32;
+                UnsignedInteger64, UnsignedInteger64Array, u64;
+                SignedInteger8, SignedInteger8Array, i8;
+                SignedInteger16, SignedInteger16Array, i16;
+                SignedInteger32, SignedInteger32Array, i32;
+                SignedInteger64, SignedInteger64Array, i64;
+                Float32, Float32Array, f32;
+                Float64, Float64Array, f64;
+                Duration, DurationArray, i64;
+            );
+        }
+        Ok(&scratch.values)
+    }
+
+    /// Serialize `values` into a buffer laid out according to this specification.
+    /// Each value is coerced to its member's dtype; out-of-range integers are an error.
+    /// ```
+    /// use std::collections::HashMap;
+    /// use elucidator::{designation::DesignationSpecification, value::DataValue};
+    ///
+    /// let spec = DesignationSpecification::from_text("foo: u8, bar: f64[]").unwrap();
+    /// let values = HashMap::from([
+    ///     ("foo", DataValue::UnsignedInteger64(7)),
+    ///     ("bar", DataValue::Float64Array(vec![1.5])),
+    /// ]);
+    /// let buffer = spec.pack(&values).unwrap();
+    ///
+    /// # assert_eq!(spec.interpret_enum(&buffer).unwrap()["foo"], DataValue::Byte(7));
+    /// ```
+    pub fn pack(&self, values: &HashMap<&str, DataValue>) -> Result<Vec<u8>> {
+        self.pack_with_policy(values, PackOverflowPolicy::default())
+    }
+
+    /// Hash of the record in `buffer`, taken over the bytes `pack` writes for its decoded values.
+    /// This is a logical hash rather than a byte-literal one: buffers that decode to the same
+    /// values, such as one with trailing padding and one without, hash the same. The hash is
+    /// stable across platforms and releases, so it may be stored for deduplication.
+    /// ```
+    /// use elucidator::designation::DesignationSpecification;
+    ///
+    /// let spec = DesignationSpecification::from_text("a: u16").unwrap();
+    /// let hash = spec.content_hash(&[1, 0]).unwrap();
+    /// assert_eq!(spec.content_hash(&[1, 0, 0xff]), Ok(hash));
+    /// assert_ne!(spec.content_hash(&[2, 0]), Ok(hash));
+    /// ```
+    pub fn content_hash(&self, buffer: &[u8]) -> Result<u64> {
+        let canonical = self.pack(&self.interpret_enum(buffer)?)?;
+        Ok(fnv1a(&canonical))
+    }
+
+    /// Serialize as with `pack`, handling out-of-range integers according to `policy`.
+    pub fn pack_with_policy(
+        &self,
+        values: &HashMap<&str, DataValue>,
+        policy: PackOverflowPolicy,
+    ) -> Result<Vec<u8>> {
+        let options = PackOptions {
+            overflow_policy: policy,
+            ..Default::default()
+        };
+        self.pack_with_options(values, &options)
+    }
+
+    /// Serialize as with `pack`, according to `options`.
+    pub fn pack_with_options(
+        &self,
+        values: &HashMap<&str, DataValue>,
+        options: &PackOptions,
+    ) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        for member in &self.members {
+            let Some(value) = values.get(member.identifier.as_str()) else {
+                return Err(ElucidatorError::MissingValue {
+                    member: member.identifier.clone(),
+                });
+            };
+            pack_member(member, value, options, &mut buffer)?;
+        }
+        Ok(buffer)
+    }
+}
+
+/// Append `value` to `buffer` as `member`, converting it to the member's dtype and writing the
+/// length prefix of a dynamic array.
+fn pack_member(
+    member: &MemberSpecification,
+    value: &DataValue,
+    options: &PackOptions,
+    buffer: &mut Vec<u8>,
+) -> Result<()> {
+    let policy = options.overflow_policy;
+    let value = match &member.scaling {
+        Some(scaling) => value.unscaled(scaling, &member.dtype, policy)?,
+        None => value.coerce_to(&member.dtype, policy)?,
+    };
+    match (&member.sizing, value.array_len()) {
+        (Sizing::Singleton, None) => {}
+        (Sizing::Fixed(n), Some(len)) if *n as usize == len => {}
+        (Sizing::Dynamic, Some(len)) => buffer.extend((len as u64).to_le_bytes()),
+        _ => return ElucidatorError::new_conversion(&value.type_name(), &member.type_string()),
+    }
+    if let DataValue::Str(s) = &value {
+        check_string_len(member, s.len())?;
+    }
+    match (&value, options.string_encoding) {
+        (DataValue::Str(s), StringEncoding::NulTerminated) => {
+            if s.contains('\0') {
+                return Err(ElucidatorError::InteriorNul {
+                    member: member.identifier.clone(),
+                });
+            }
+            buffer.extend(s.as_bytes());
+            buffer.push(0);
+        }
+        _ => buffer.extend(value.as_buffer()),
+    }
+    Ok(())
+}
+
+/// Builds a buffer of a [`DesignationSpecification`] one member at a time, in specification
+/// order, for producers that have each value before the whole record. Every value is checked
+/// against its member as it is written, with the same conversions `pack` applies.
+/// ```
+/// use elucidator::designation::{DesignationSpecification, RecordWriter};
+///
+/// let spec = DesignationSpecification::from_text("id: u32, xs: f64[]").unwrap();
+/// let mut writer = RecordWriter::new(&spec);
+/// writer.write(7u32).unwrap();
+/// writer.write(vec![0.5, 1.5]).unwrap();
+/// let buffer = writer.finish().unwrap();
+/// assert_eq!(spec.interpret_enum(&buffer).unwrap()["xs"].to_string(), "[0.5, 1.5]");
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordWriter<'a> {
+    spec: &'a DesignationSpecification,
+    buffer: Vec<u8>,
+    /// Number of members written so far
+    written: usize,
+}
+
+impl<'a> RecordWriter<'a> {
+    pub fn new(spec: &'a DesignationSpecification) -> Self {
+        RecordWriter {
+            spec,
+            buffer: Vec::new(),
+            written: 0,
+        }
+    }
+
+    /// Identifier of the member the next `write` fills, or `None` once all have been written.
+    pub fn next_member(&self) -> Option<&str> {
+        self.spec
+            .members
+            .get(self.written)
+            .map(|m| m.identifier.as_str())
+    }
+
+    /// Append `value` as the next member. Dynamic arrays and strings get their length prefix
+    /// automatically. A value that does not convert to the member's dtype, or does not match
+    /// its sizing, is rejected and leaves the writer as it was, so the member can be retried.
+    pub fn write(&mut self, value: impl Representable) -> Result<()> {
+        let Some(member) = self.spec.members.get(self.written) else {
+            return Err(ElucidatorError::RecordComplete {
+                members: self.spec.members.len(),
+            });
+        };
+        let value = DataValue::from_representable(&value)?;
+        let len = self.buffer.len();
+        if let Err(e) = pack_member(member, &value, &PackOptions::default(), &mut self.buffer) {
+            self.buffer.truncate(len);
+            return Err(e);
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Take the finished buffer, failing with `MissingValue` for the first member that was
+    /// never written.
+    pub fn finish(self) -> Result<Vec<u8>> {
+        match self.next_member() {
+            Some(member) => Err(ElucidatorError::MissingValue {
+                member: member.to_string(),
+            }),
+            None => Ok(self.buffer),
+        }
+    }
+}
+
+impl std::fmt::Display for DesignationSpecification {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let m = self
+            .members
+            .iter()
+            .map(MemberSpecification::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(f, "{m}")
+    }
+}
+
+/// Parse `spec_text` and interpret `buffer` against it in a single call.
+/// Keys are owned so the result outlives the temporary specification.
+/// ```
+/// use elucidator::{interpret_text, value::DataValue};
+///
+/// let values = interpret_text("foo: u32", &7u32.to_le_bytes()).unwrap();
+///
+/// # assert_eq!(values["foo"], DataValue::UnsignedInteger32(7));
+/// ```
+pub fn interpret_text(spec_text: &str, buffer: &[u8]) -> Result<HashMap<String, DataValue>> {
+    let spec = DesignationSpecification::from_text(spec_text)?;
+    let values = spec.interpret_enum(buffer)?;
+    Ok(values
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect())
+}
+
+/// Find the first offset at which two buffers differ, along with the byte each holds there.
+/// When one buffer is a strict prefix of the other, the offset is the length of the shorter
+/// one and its missing byte is reported as `0`.
+/// ```
+/// use elucidator::diff_buffers;
+///
+/// assert_eq!(diff_buffers(&[1, 2, 3], &[1, 2, 3]), None);
+/// assert_eq!(diff_buffers(&[1, 2, 3], &[1, 5, 3]), Some((1, 2, 5)));
+/// assert_eq!(diff_buffers(&[1, 2], &[1, 2, 9]), Some((2, 0, 9)));
+/// ```
+pub fn diff_buffers(a: &[u8], b: &[u8]) -> Option<(usize, u8, u8)> {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| (i, a.get(i).copied(), b.get(i).copied()))
+        .find(|(_, x, y)| x != y)
+        .map(|(i, x, y)| (i, x.unwrap_or(0), y.unwrap_or(0)))
+}
+
+/// Split a document line into a designation name and its specification. The line is either
+/// `name = "Name": spec` or `Name = spec`, where the name in the latter form may be quoted.
+fn split_document_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    let keyed = line
+        .strip_prefix("name")
+        .and_then(|rest| rest.trim_start().strip_prefix('='))
+        .and_then(|rest| rest.trim_start().strip_prefix('"'));
+    let (name, rest) = match (keyed, line.strip_prefix('"')) {
+        (Some(quoted), _) => {
+            let (name, rest) = quoted.split_once('"')?;
+            (name, rest.trim_start().strip_prefix(':')?)
+        }
+        (None, Some(quoted)) => {
+            let (name, rest) = quoted.split_once('"')?;
+            (name, rest.trim_start().strip_prefix('=')?)
+        }
+        (None, None) => {
+            let (name, rest) = line.split_once('=')?;
+            (name.trim_end(), rest)
+        }
+    };
+    (!name.is_empty()).then_some((name, rest))
+}
+
+/// Parse a document defining several designations, one per line as `name = "Name": spec` or
+/// `Name = spec`. Names in the latter form may be quoted. A line starting with whitespace
+/// continues the specification of the line before it, and blank lines and lines starting with
+/// `#` are ignored. Defining a name twice is an error.
+/// ```
+/// use elucidator::designation::parse_document;
+///
+/// let document = "
+/// ## Sensor records
+/// name = \"FooBar\": foo: u32, bar: i8
+/// \"Baz Qux\" = a: u8,
+///     b: f64[]
+/// ";
+/// let specs = parse_document(document).unwrap();
+///
+/// # assert_eq!(specs["FooBar"].to_string(), "foo: u32, bar: i8");
+/// # assert_eq!(specs["Baz Qux"].to_string(), "a: u8, b: f64[]");
+/// ```
+pub fn parse_document(text: &str) -> Result<HashMap<String, DesignationSpecification>> {
+    let mut entries: Vec<(&str, String)> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            match entries.last_mut() {
+                Some((_, spec)) => {
+                    spec.push(' ');
+                    spec.push_str(trimmed);
+                    continue;
+                }
+                None => return Err(ElucidatorError::MalformedDocument { line: i + 1 }),
+            }
+        }
+        let (name, spec) =
+            split_document_line(line).ok_or(ElucidatorError::MalformedDocument { line: i + 1 })?;
+        entries.push((name, spec.trim().to_string()));
+    }
+
+    let mut specs = HashMap::with_capacity(entries.len());
+    for (name, spec) in entries {
+        if specs.contains_key(name) {
+            return Err(ElucidatorError::DuplicateDesignation {
+                name: name.to_string(),
+            });
+        }
+        let spec = DesignationSpecification::from_text(&spec).map_err(|error| {
+            ElucidatorError::InvalidDesignation {
+                name: name.to_string(),
+                error: Box::new(error),
+            }
+        })?;
+        specs.insert(name.to_string(), spec);
+    }
+    Ok(specs)
+}
+
+impl std::str::FromStr for DesignationSpecification {
+    type Err = ElucidatorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_text(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{
+        member::{Dtype, Sizing},
+        test_utils,
+        value::DataValue,
+    };
+    use pretty_assertions::assert_eq;
+    use rand::{random, Rng};
+
+    type DataMap<'a> = HashMap<&'a str, Box<dyn Representable>>;
+
+    fn make_dyn_box<T: Representable + 'static>(item: T) -> Box<dyn Representable> {
+        Box::new(item)
+    }
+
+    fn compare_hashmap(left: &DataMap, right: &DataMap) {
+        let left_keys: HashSet<&str> = left.keys().copied().collect();
+        let right_keys: HashSet<&str> = right.keys().copied().collect();
+
+        pretty_assertions::assert_eq!(left_keys, right_keys);
+
+        for key in left_keys {
+            let lvalue = left.get(key).unwrap();
+            let rvalue = right.get(key).unwrap();
+
+            assert!(
+                crate::representable::repr_eq(lvalue.as_ref(), rvalue.as_ref()),
+                "Values for {key} differ"
+            );
+        }
+    }
+
+    #[test]
+    fn multiple_members_ok() {
+        let text = "foo: u32, bar: f32[10], baz: string";
+        let dspec = DesignationSpecification::from_text(text);
+        assert_eq!(
+            dspec,
+            Ok(DesignationSpecification {
+                members: vec![
+                    MemberSpecification::from_parts(
+                        "foo",
+                        &Sizing::Singleton,
+                        &Dtype::UnsignedInteger32,
+                    ),
+                    MemberSpecification::from_parts("bar", &Sizing::Fixed(10), &Dtype::Float32,),
+                    MemberSpecification::from_parts("baz", &Sizing::Singleton, &Dtype::Str,),
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn simple_ok() {
+        let text = "foo: u32, bar: i32";
+        let dspec = DesignationSpecification::from_text(text).unwrap();
+        let expected: DataMap = HashMap::from([
+            ("foo", make_dyn_box(10_u32)),
+            ("bar", make_dyn_box(-10_i32)),
+        ]);
+        let buffer = expected
+            .get("foo")
+            .unwrap()
+            .as_u32()
+            .unwrap()
+            .to_le_bytes()
+            .iter()
+            .chain(
+                expected
+                    .get("bar")
+                    .unwrap()
+                    .as_i32()
+                    .unwrap()
+                    .to_le_bytes()
+                    .iter(),
+            )
+            .copied()
+            .collect::<Vec<u8>>();
+        let result = dspec.interpret(&buffer).unwrap();
+        compare_hashmap(&expected, &result);
+    }
+
+    #[test]
+    fn simple_fixed_vec_ok() {
+        let text = "foo: u32[3], bar: string";
+        let dspec = DesignationSpecification::from_text(text).unwrap();
+        let expected: DataMap = HashMap::from([
+            ("foo", make_dyn_box(vec![2_u32, 10_u32, 0xDEADBEEF_u32])),
+            ("bar", make_dyn_box(test_utils::crab_emoji())),
+        ]);
+        let buffer = expected
+            .get("foo")
+            .unwrap()
+            .as_vec_u32()
+            .unwrap()
+            .as_buffer()
+            .iter()
+            .chain(
+                expected
+                    .get("bar")
+                    .unwrap()
+                    .as_string()
+                    .unwrap()
+                    .as_buffer()
+                    .iter(),
+            )
+            .copied()
+            .collect::<Vec<u8>>();
+        let result = dspec.interpret(&buffer).unwrap();
+        compare_hashmap(&expected, &result);
+    }
+
+    #[test]
+    fn simple_dynamic_vec_ok() {
+        let text = "foo: u32[], bar: string";
+        let dspec = DesignationSpecification::from_text(text).unwrap();
+        let expected: DataMap = HashMap::from([
+            ("foo", make_dyn_box(vec![2_u32, 10_u32, 0xDEADBEEF_u32])),
+            ("bar", make_dyn_box(test_utils::crab_emoji())),
+        ]);
+        let buffer = [0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+            .iter()
+            .chain(
+                expected
+                    .get("foo")
+                    .unwrap()
+                    .as_vec_u32()
+                    .unwrap()
+                    .as_buffer()
+                    .iter(),
+            )
+            .chain(
+                expected
+                    .get("bar")
+                    .unwrap()
+                    .as_string()
+                    .unwrap()
+                    .as_buffer()
+                    .iter(),
+            )
+            .copied()
+            .collect::<Vec<u8>>();
+        let result = dspec.interpret(&buffer).unwrap();
+        compare_hashmap(&expected, &result);
+    }
+
+    #[test]
+    fn interpret_u8_ok() {
+        let text = "foo: u8";
+        let dspec = DesignationSpecification::from_text(text).unwrap();
+        let val: u8 = 10;
+        let result = dspec.interpret(val.to_le_bytes().as_ref());
+        let result_val = result.unwrap().get("foo").unwrap().as_u8().unwrap();
+        assert_eq!(val, result_val);
+    }
+
+    fn random_data_value(dt: &Dtype, sizing: &Sizing) -> DataValue {
+        let items = match sizing {
+            Sizing::Singleton => 1,
+            Sizing::Fixed(n) => *n,
+            Sizing::Dynamic => (random::<u8>() % 100 + 1) as u64,
+        };
+        match dt {
+            Dtype::Byte => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::Byte(random())
+                } else {
+                    DataValue::ByteArray((0..items).map(|_| random::<u8>()).collect())
+                }
+            }
+            Dtype::UnsignedInteger16 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::UnsignedInteger16(random())
+                } else {
+                    DataValue::UnsignedInteger16Array((0..items).map(|_| random::<u16>()).collect())
+                }
+            }
+            Dtype::UnsignedInteger32 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::UnsignedInteger32(random())
+                } else {
+                    DataValue::UnsignedInteger32Array((0..items).map(|_| random::<u32>()).collect())
+                }
+            }
+            Dtype::UnsignedInteger64 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::UnsignedInteger64(random())
+                } else {
+                    DataValue::UnsignedInteger64Array((0..items).map(|_| random::<u64>()).collect())
+                }
+            }
+            Dtype::SignedInteger8 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::SignedInteger8(random())
+                } else {
+                    DataValue::SignedInteger8Array((0..items).map(|_| random::<i8>()).collect())
+                }
+            }
+            Dtype::SignedInteger16 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::SignedInteger16(random())
+                } else {
+                    DataValue::SignedInteger16Array((0..items).map(|_| random::<i16>()).collect())
+                }
+            }
+            Dtype::SignedInteger32 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::SignedInteger32(random())
+                } else {
+                    DataValue::SignedInteger32Array((0..items).map(|_| random::<i32>()).collect())
+                }
+            }
+            Dtype::SignedInteger64 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::SignedInteger64(random())
+                } else {
+                    DataValue::SignedInteger64Array((0..items).map(|_| random::<i64>()).collect())
+                }
+            }
+            Dtype::Float32 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::Float32(random())
+                } else {
+                    DataValue::Float32Array((0..items).map(|_| random::<f32>()).collect())
+                }
+            }
+            Dtype::Float64 => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::Float64(random())
+                } else {
+                    DataValue::Float64Array((0..items).map(|_| random::<f64>()).collect())
+                }
+            }
+            Dtype::Duration => {
+                if sizing == &Sizing::Singleton {
+                    DataValue::Duration(random())
+                } else {
+                    DataValue::DurationArray((0..items).map(|_| random::<i64>()).collect())
+                }
+            }
+            Dtype::Str => {
+                let n_chars = random::<u8>() % 10;
+                let s = (0..n_chars).map(|_| random::<char>()).collect();
+                DataValue::Str(s)
+            }
+            Dtype::Char => DataValue::Char(random()),
+        }
+    }
+
+    fn random_sizing() -> Sizing {
+        let num = random::<u8>() % 3;
+        match num {
+            0 => Sizing::Singleton,
+            1 => Sizing::Fixed((random::<u8>() % 100 + 1) as u64),
+            2 => Sizing::Dynamic,
+            _ => {
+                unreachable!();
+            }
+        }
+    }
+
+    fn random_dtype() -> Dtype {
+        let num = random::<u8>() % 13; // There are 13 variants in the Dtype enum
+        match num {
+            0 => Dtype::Byte,
+            1 => Dtype::UnsignedInteger16,
+            2 => Dtype::UnsignedInteger32,
+            3 => Dtype::UnsignedInteger64,
+            4 => Dtype::SignedInteger8,
+            5 => Dtype::SignedInteger16,
+            6 => Dtype::SignedInteger32,
+            7 => Dtype::SignedInteger64,
+            8 => Dtype::Float32,
+            9 => Dtype::Float64,
+            10 => Dtype::Str,
+            11 => Dtype::Duration,
+            12 => Dtype::Char,
+            _ => unreachable!(),
+        }
+    }
+
+    fn random_dtype_sizing() -> (Sizing, Dtype) {
+        let dtype = random_dtype();
+        let sizing = if let Dtype::Str | Dtype::Char = dtype {
+            Sizing::Singleton
+        } else {
+            random_sizing()
+        };
+        (sizing, dtype)
+    }
+
+    fn random_identifier() -> String {
+        let mut rng = rand::thread_rng();
+        let length = (random::<u8>() % 5) + 1;
+        (0..length)
+            .map(|_| rng.gen_range(b'a'..=b'z') as char)
+            .collect()
+    }
+
+    fn random_member_specification() -> MemberSpecification {
+        let (sizing, dtype) = random_dtype_sizing();
+        let identifier = random_identifier();
+        MemberSpecification {
+            identifier,
+            sizing,
+            dtype,
+            doc: None,
+            scaling: None,
+            enum_mapping: None,
+            max_len: None,
+        }
+    }
+
+    fn random_designation_specification() -> DesignationSpecification {
+        let num_members = random::<u8>() % 20 + 1;
+        let members = loop {
+            let candidates = (0..num_members)
+                .map(|_| random_member_specification())
+                .collect::<Vec<_>>();
+            let unique_names = candidates
+                .iter()
+                .map(|x| x.identifier.clone())
+                .collect::<HashSet<_>>();
+            if candidates.len() == unique_names.len() {
+                break candidates;
+            }
+        };
+        DesignationSpecification { members }
+    }
+
+    fn generate_random_designation_specification_data(
+        designation_spec: &DesignationSpecification,
+    ) -> HashMap<&str, DataValue> {
+        let mut data_map = HashMap::new();
+
+        for member in &designation_spec.members {
+            let data_value = random_data_value(&member.dtype, &member.sizing);
+            data_map.insert(member.identifier.as_str(), data_value);
+        }
+
+        data_map
+    }
+
+    fn into_blob(dv: &DataValue, sizing: &Sizing) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        if let Sizing::Dynamic = sizing {
+            let num_elements = match dv {
+                DataValue::ByteArray(v) => v.len() as u64,
+                DataValue::UnsignedInteger16Array(v) => v.len() as u64,
+                DataValue::UnsignedInteger32Array(v) => v.len() as u64,
+                DataValue::UnsignedInteger64Array(v) => v.len() as u64,
+                DataValue::SignedInteger8Array(v) => v.len() as u64,
+                DataValue::SignedInteger16Array(v) => v.len() as u64,
+                DataValue::SignedInteger32Array(v) => v.len() as u64,
+                DataValue::SignedInteger64Array(v) => v.len() as u64,
+                DataValue::Float32Array(v) => v.len() as u64,
+                DataValue::Float64Array(v) => v.len() as u64,
+                DataValue::DurationArray(v) => v.len() as u64,
+                _ => {
+                    unreachable!("Only arrays should have dynamic sizing");
+                }
+            };
+            buffer.extend_from_slice(&num_elements.to_le_bytes());
+        }
+
+        buffer.extend_from_slice(&dv.as_buffer());
+        buffer
+    }
+
+    fn generate_designation_and_perform_round_trip() {
+        let designation = random_designation_specification();
+        let n_data = random::<u8>() % 50;
+        let data_vec: Vec<HashMap<&str, DataValue>> = (0..n_data)
+            .map(|_| generate_random_designation_specification_data(&designation))
+            .collect();
+        for datum in &data_vec {
+            let blob_vec: Vec<Vec<u8>> = designation
+                .members
+                .iter()
+                .map(|member| {
+                    let dv = datum.get(member.identifier.as_str()).unwrap();
+                    let sizing = &member.sizing;
+                    into_blob(dv, sizing)
+                })
+                .collect();
+            let buffer: Vec<u8> = blob_vec.iter().flat_map(|x| x.iter()).copied().collect();
+            let map = designation.interpret_enum(&buffer);
+            let dr: Result<HashMap<&str, DataValue>> = Ok(datum.clone());
+            pretty_assertions::assert_eq!(
+                map,
+                dr,
+                "{designation:#?}\nBuffer size {}",
+                buffer.len()
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn compare_dv_hm() {
+        let left = HashMap::from([
+            ("foo", DataValue::Byte(9)),
+            ("bar", DataValue::Float32Array(vec![-5.0, -10.0, 3.14])),
+        ]);
+        let right = HashMap::from([
+            ("foo", DataValue::Byte(9)),
+            ("bar", DataValue::Float32Array(vec![-5.0, -10.0, 3.14])),
+        ]);
+        pretty_assertions::assert_eq!(left, right,);
+    }
+
+    #[test]
+    fn describe_sizes_ok() {
+        let designation =
+            DesignationSpecification::from_text("a: u32, foo: f32[], b: u16[4], bar: string")
+                .unwrap();
+        assert_eq!(designation.fixed_size_bytes(), 4 + 8 + 8 + 8);
+        assert_eq!(
+            designation.describe_sizes(),
+            "fixed: 28 bytes + dynamic (foo, bar)"
+        );
+        let designation = DesignationSpecification::from_text("a: u8, b: f64[2]").unwrap();
+        assert_eq!(designation.describe_sizes(), "fixed: 17 bytes");
+    }
+
+    #[test]
+    fn first_non_finite_member_ok() {
+        let designation =
+            DesignationSpecification::from_text("name: string, xs: i16[], ys: f32[2], z: f64")
+                .unwrap();
+        let buffer = |ys: Vec<f32>, z: f64| -> Vec<u8> {
+            [
+                DataValue::Str("probe".to_string()).as_buffer(),
+                2u64.to_le_bytes().to_vec(),
+                DataValue::SignedInteger16Array(vec![-1, 1]).as_buffer(),
+                DataValue::Float32Array(ys).as_buffer(),
+                DataValue::Float64(z).as_buffer(),
+            ]
+            .concat()
+        };
+        assert_eq!(
+            designation.first_non_finite_member(&buffer(vec![1.0, 2.0], 3.0)),
+            Ok(None)
+        );
+        assert_eq!(
+            designation.first_non_finite_member(&buffer(vec![1.0, f32::INFINITY], 3.0)),
+            Ok(Some("ys"))
+        );
+        assert_eq!(
+            designation.first_non_finite_member(&buffer(vec![1.0, 2.0], f64::NAN)),
+            Ok(Some("z"))
+        );
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn simple_interpret_enum() {
+        let hm = HashMap::from([
+            ("foo", DataValue::Byte(9)),
+            ("bar", DataValue::Float32Array(vec![-5.0, -10.0, 3.14])),
+        ]);
+        let buff_foo = hm.get("foo").unwrap().as_buffer();
+        let buff_bar = hm.get("bar").unwrap().as_buffer();
+        let buffer: Vec<u8> = buff_foo.iter().chain(buff_bar.iter()).copied().collect();
+        let designation = DesignationSpecification::from_text("foo: u8, bar: f32[3]").unwrap();
+        let result = designation.interpret_enum(&buffer);
+        pretty_assertions::assert_eq!(result, Ok(hm),);
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn complex_interpret_enum() {
+        let foo_vec: Vec<i16> = vec![-1, 2, 1025];
+        let bar_vec: Vec<f64> = vec![3.1415, 2.71];
+        let baz_vec: Vec<i8> = vec![-3, 4, -5, 6];
+        let hm = HashMap::from([
+            ("foo", DataValue::SignedInteger16Array(foo_vec.clone())),
+            ("bar", DataValue::Float64Array(bar_vec)),
+            ("baz", DataValue::SignedInteger8Array(baz_vec.clone())),
+        ]);
+        let foo_size_buf = (foo_vec.len() as u64).to_le_bytes();
+        let baz_size_buf = (baz_vec.len() as u64).to_le_bytes();
+        let buffer: Vec<u8> = foo_size_buf
+            .iter()
+            .chain(hm.get("foo").unwrap().as_buffer().iter())
+            .chain(hm.get("bar").unwrap().as_buffer().iter())
+            .chain(baz_size_buf.iter())
+            .chain(hm.get("baz").unwrap().as_buffer().iter())
+            .copied()
+            .collect();
+        let designation =
+            DesignationSpecification::from_text("foo: i16[], bar: f64[2], baz: i8[]").unwrap();
+        let result = designation.interpret_enum(&buffer);
+        pretty_assertions::assert_eq!(result, Ok(hm),);
+    }
+
+    #[test]
+    fn interpret_text_ok() {
+        let result = interpret_text("foo: u32", &[1, 1, 0, 0]);
+        let expected = HashMap::from([("foo".to_string(), DataValue::UnsignedInteger32(257))]);
+        assert_eq!(result, Ok(expected));
+        assert!(interpret_text("foo u32", &[1, 1, 0, 0]).is_err());
+        assert!(interpret_text("foo: u32", &[1, 1]).is_err());
+    }
+
+    #[test]
+    fn documented_member_ok() {
+        let text = r#"count: u32 "the number of foos, [approx]", xs: f64[] "samples: raw""#;
+        let spec = DesignationSpecification::from_text(text).unwrap();
+        let expected = DesignationSpecification {
+            members: vec![
+                MemberSpecification::from_parts(
+                    "count",
+                    &Sizing::Singleton,
+                    &Dtype::UnsignedInteger32,
+                )
+                .with_doc("the number of foos, [approx]"),
+                MemberSpecification::from_parts("xs", &Sizing::Dynamic, &Dtype::Float64)
+                    .with_doc("samples: raw"),
+            ],
+        };
+        assert_eq!(spec, expected);
+        assert_eq!(spec.to_string(), text);
+        assert_eq!(
+            DesignationSpecification::from_text(&spec.to_string()),
+            Ok(spec.clone())
+        );
+        assert_eq!(
+            spec.describe_sizes(),
+            r#"fixed: 12 bytes + dynamic (xs "samples: raw")"#
+        );
+    }
+
+    #[test]
+    fn documented_member_err() {
+        assert!(DesignationSpecification::from_text(r#"foo: u32 "unterminated"#).is_err());
+        assert!(DesignationSpecification::from_text(r#"foo: u32 "doc" extra"#).is_err());
+    }
+
+    mod diff {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn spec(text: &str) -> DesignationSpecification {
+            DesignationSpecification::from_text(text).unwrap()
+        }
+
+        #[test]
+        fn append_ok() {
+            let diff = spec("a: u8, b: f32").diff(&spec(r#"a: u8 "doc", b: f32, c: u64[]"#));
+            assert_eq!(diff.added, vec!["c".to_string()]);
+            assert!(diff.removed.is_empty());
+            assert!(diff.changed.is_empty());
+            assert!(diff.is_append_only());
+        }
+
+        #[test]
+        fn type_change_not_append_only() {
+            let diff = spec("a: u8, b: f32").diff(&spec("a: u8, b: f64, c: u8"));
+            assert_eq!(diff.changed, vec!["b".to_string()]);
+            assert!(!diff.is_append_only());
+        }
+
+        #[test]
+        fn reorder_and_removal_not_append_only() {
+            let diff = spec("a: u8, b: f32").diff(&spec("b: f32, a: u8"));
+            assert!(diff.changed.is_empty());
+            assert!(!diff.is_append_only());
+            let diff = spec("a: u8, b: f32").diff(&spec("a: u8"));
+            assert_eq!(diff.removed, vec!["b".to_string()]);
+            assert!(!diff.is_append_only());
+        }
+    }
+
+    mod diff_records {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn spec() -> DesignationSpecification {
+            DesignationSpecification::from_text("id: u32, temp: f64, name: string").unwrap()
+        }
+
+        fn record(temp: f64) -> Vec<u8> {
+            spec()
+                .pack(&HashMap::from([
+                    ("id", DataValue::UnsignedInteger32(7)),
+                    ("temp", DataValue::Float64(temp)),
+                    ("name", DataValue::Str("probe".to_string())),
+                ]))
+                .unwrap()
+        }
+
+        #[test]
+        fn identical_none() {
+            let buffer = record(21.5);
+            assert_eq!(diff_buffers(&buffer, &buffer), None);
+            assert_eq!(spec().diff_records(&buffer, &buffer), Ok(None));
+            let nan = record(f64::NAN);
+            assert_eq!(spec().diff_records(&nan, &nan), Ok(None));
+        }
+
+        #[test]
+        fn second_member_differs() {
+            let (a, b) = (record(21.5), record(-3.0));
+            // 21.5 and -3.0 first differ in the sixth byte of temp, after the four of id
+            assert_eq!(diff_buffers(&a, &b), Some((9, 0x80, 0x00)));
+            assert_eq!(
+                spec().diff_records(&a, &b),
+                Ok(Some((
+                    "temp".to_string(),
+                    DataValue::Float64(21.5),
+                    DataValue::Float64(-3.0)
+                )))
+            );
+        }
+
+        #[test]
+        fn changed_members_one_of_three() {
+            let (a, b) = (record(21.5), record(-3.0));
+            assert_eq!(spec().changed_members(&a, &b), Ok(vec!["temp"]));
+            assert_eq!(spec().changed_members(&a, &a), Ok(vec![]));
+        }
+
+        #[test]
+        fn undecodable_err() {
+            let a = record(21.5);
+            assert!(spec().diff_records(&a, &a[..6]).is_err());
+            assert!(spec().changed_members(&a[..6], &a).is_err());
+            assert_eq!(diff_buffers(&a, &a[..6]), Some((6, a[6], 0)));
+        }
+    }
+
+    mod decodable_prefix {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn covers_two_of_three() {
+            let spec = DesignationSpecification::from_text("a: u8, b: u32, c: f64").unwrap();
+            let buffer = [1, 2, 0, 0, 0, 3, 0, 0];
+            assert_eq!(spec.decodable_prefix(&buffer), 2);
+        }
+
+        #[test]
+        fn complete_and_empty() {
+            let spec = DesignationSpecification::from_text("a: u8, s: string").unwrap();
+            let mut buffer = vec![1];
+            buffer.extend(2u64.to_le_bytes());
+            buffer.extend(b"hi");
+            assert_eq!(spec.decodable_prefix(&buffer), 2);
+            assert_eq!(spec.decodable_prefix(&buffer[..buffer.len() - 1]), 1);
+            assert_eq!(spec.decodable_prefix(&[]), 0);
+        }
+    }
+
+    mod split_records {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn fixed_records_ok() {
+            let spec = DesignationSpecification::from_text("a: u8, b: u16").unwrap();
+            let buffer = [1, 2, 0, 3, 4, 0, 5, 6, 0];
+            assert_eq!(
+                spec.split_records(&buffer).unwrap(),
+                vec![&buffer[0..3], &buffer[3..6], &buffer[6..9]]
+            );
+        }
+
+        #[test]
+        fn fixed_records_partial_err() {
+            let spec = DesignationSpecification::from_text("a: u8, b: u16").unwrap();
+            assert_eq!(
+                spec.split_records(&[1, 2, 0, 3]),
+                Err(ElucidatorError::BufferSizing {
+                    expected: 6,
+                    found: 4
+                })
+            );
+        }
+
+        #[test]
+        fn dynamic_records_ok() {
+            let spec =
+                DesignationSpecification::from_text("id: u8, xs: i16[], name: string").unwrap();
+            let records: Vec<Vec<u8>> = [
+                (1, vec![], "a"),
+                (2, vec![7, 8, 9], ""),
+                (3, vec![4], "xyz"),
+            ]
+            .into_iter()
+            .map(|(id, xs, name)| {
+                let values = HashMap::from([
+                    ("id", DataValue::Byte(id)),
+                    ("xs", DataValue::SignedInteger16Array(xs)),
+                    ("name", DataValue::Str(name.to_string())),
+                ]);
+                spec.pack(&values).unwrap()
+            })
+            .collect();
+            let buffer = records.concat();
+            let split = spec.split_records(&buffer).unwrap();
+            assert_eq!(
+                split,
+                records.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>()
+            );
+        }
+
+        #[test]
+        fn dynamic_records_truncated_err() {
+            let spec = DesignationSpecification::from_text("xs: u8[]").unwrap();
+            let mut buffer = 2u64.to_le_bytes().to_vec();
+            buffer.extend([1, 2]);
+            buffer.extend(5u64.to_le_bytes());
+            buffer.extend([1]);
+            assert!(spec.split_records(&buffer).is_err());
+        }
+    }
+
+    mod interpret_after {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn header_prefix_ok() {
+            let spec = DesignationSpecification::from_text("a: u16, b: f32[]").unwrap();
+            let values = HashMap::from([
+                ("a", DataValue::UnsignedInteger16(513)),
+                ("b", DataValue::Float32Array(vec![1.5, -2.0])),
+            ]);
+            let mut buffer = b"EL\x01\x00".to_vec();
+            buffer.extend(spec.pack(&values).unwrap());
+            assert_eq!(spec.interpret_enum_after(&buffer, 4).unwrap(), values);
+        }
+
+        #[test]
+        fn header_longer_than_buffer_err() {
+            let spec = DesignationSpecification::from_text("a: u8").unwrap();
+            assert_eq!(
+                spec.interpret_enum_after(&[1, 2], 4),
+                Err(ElucidatorError::BufferSizing {
+                    expected: 4,
+                    found: 2
+                })
+            );
+        }
+    }
+
+    mod empty_buffer {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn nonempty_spec_err() {
+            let spec = DesignationSpecification::from_text("a: u32, b: f64[]").unwrap();
+            let expected = ElucidatorError::EmptyBuffer {
+                expected_at_least: 12,
+            };
+            assert_eq!(spec.interpret_enum(&[]), Err(expected.clone()));
+            assert!(matches!(spec.interpret(&[]), Err(e) if e == expected));
+        }
+
+        #[test]
+        fn empty_spec_ok() {
+            let spec = DesignationSpecification::from_text("").unwrap();
+            assert_eq!(spec.interpret_enum(&[]), Ok(HashMap::new()));
+            assert!(spec.interpret(&[]).unwrap().is_empty());
+        }
+    }
+
+    mod from_text_non_empty {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn empty_err() {
+            assert_eq!(
+                DesignationSpecification::from_text_non_empty(""),
+                Err(ElucidatorError::EmptySpecification)
+            );
+        }
+
+        #[test]
+        fn whitespace_err() {
+            assert_eq!(
+                DesignationSpecification::from_text_non_empty("   "),
+                Err(ElucidatorError::EmptySpecification)
+            );
+            assert!(DesignationSpecification::from_text("   ").is_ok());
+        }
+
+        #[test]
+        fn real_spec_ok() {
+            assert_eq!(
+                DesignationSpecification::from_text_non_empty("foo: u8"),
+                DesignationSpecification::from_text("foo: u8")
+            );
+        }
+    }
+
+    mod warnings {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn dynamic_before_fixed_warns() {
+            let (_, warnings) =
+                DesignationSpecification::from_text_with_warnings("foo: u8[], bar: u32").unwrap();
+            assert_eq!(
+                warnings,
+                vec![SpecWarning::DynamicMemberNotLast {
+                    member: "foo".to_string(),
+                    followed_by: vec!["bar".to_string()],
+                }]
+            );
+        }
+
+        #[test]
+        fn string_before_fixed_warns() {
+            let (_, warnings) = DesignationSpecification::from_text_with_warnings(
+                "name: string, a: u8, tail: f32[]",
+            )
+            .unwrap();
+            assert_eq!(
+                warnings,
+                vec![SpecWarning::DynamicMemberNotLast {
+                    member: "name".to_string(),
+                    followed_by: vec!["a".to_string(), "tail".to_string()],
+                }]
+            );
+        }
+
+        #[test]
+        fn trailing_dynamic_no_warning() {
+            let (spec, warnings) =
+                DesignationSpecification::from_text_with_warnings("bar: u32, foo: u8[]").unwrap();
+            assert_eq!(warnings, vec![]);
+            assert_eq!(
+                spec,
+                DesignationSpecification::from_text("bar: u32, foo: u8[]").unwrap()
+            );
+        }
+
+        #[test]
+        fn invalid_spec_err() {
+            assert!(DesignationSpecification::from_text_with_warnings("foo: u9").is_err());
+        }
+
+        #[test]
+        fn single_element_array_warns() {
+            let text = "id: u32, foo: u8[1]";
+            let (_, warnings) = DesignationSpecification::from_text_with_warnings(text).unwrap();
+            assert_eq!(
+                warnings,
+                vec![SpecWarning::SingleElementArray {
+                    member: "foo".to_string()
+                }]
+            );
+            assert_eq!(warnings[0].span(text), Some((9, 19)));
+            assert!(warnings[0].to_string().contains("foo"));
+        }
+
+        #[test]
+        fn two_element_array_no_warning() {
+            let (_, warnings) =
+                DesignationSpecification::from_text_with_warnings("foo: u8[2]").unwrap();
+            assert_eq!(warnings, vec![]);
+        }
+
+        #[test]
+        fn lone_dynamic_array_warns() {
+            let text = "  blob: u8[ ]";
+            let (_, warnings) = DesignationSpecification::from_text_with_warnings(text).unwrap();
+            assert_eq!(
+                warnings,
+                vec![SpecWarning::LoneDynamicArray {
+                    member: "blob".to_string()
+                }]
+            );
+            assert_eq!(warnings[0].span(text), Some((2, 13)));
+            let (_, warnings) =
+                DesignationSpecification::from_text_with_warnings("id: u8, blob: u8[]").unwrap();
+            assert_eq!(warnings, vec![]);
+        }
+
+        #[test]
+        fn existing_warning_span_ok() {
+            let text = "foo: u8[], bar: u32";
+            let (_, warnings) = DesignationSpecification::from_text_with_warnings(text).unwrap();
+            assert_eq!(warnings[0].member(), "foo");
+            assert_eq!(warnings[0].span(text), Some((0, 9)));
+        }
+    }
+
+    mod string_arrays {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        /// Validation rejects string arrays, so build one directly
+        fn string_array_spec(sizing: Sizing) -> DesignationSpecification {
+            DesignationSpecification {
+                members: vec![MemberSpecification {
+                    identifier: "names".to_string(),
+                    sizing,
+                    dtype: Dtype::Str,
+                    doc: None,
+                    scaling: None,
+                    enum_mapping: None,
+                    max_len: None,
+                }],
+            }
+        }
+
+        #[test]
+        fn interpret_err() {
+            let expected = ElucidatorError::UnsupportedArrayElement { dtype: Dtype::Str };
+            for sizing in [Sizing::Fixed(2), Sizing::Dynamic] {
+                let spec = string_array_spec(sizing);
+                let buffer = [0u8; 16];
+                assert!(matches!(spec.interpret(&buffer), Err(e) if e == expected));
+                assert_eq!(spec.interpret_enum(&buffer), Err(expected.clone()));
+                assert_eq!(
+                    spec.interpret_enum_reuse(&buffer, &mut DecodeScratch::new())
+                        .cloned(),
+                    Err(expected.clone())
+                );
+            }
+        }
+    }
+
+    mod interpret_enum_reuse {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn record(spec: &DesignationSpecification, id: u32, xs: &[f64], name: &str) -> Vec<u8> {
+            spec.pack(&HashMap::from([
+                ("id", DataValue::UnsignedInteger32(id)),
+                ("xs", DataValue::Float64Array(xs.to_vec())),
+                ("name", DataValue::Str(name.to_string())),
+                ("ys", DataValue::SignedInteger16Array(vec![id as i16, -1])),
+                ("q", DataValue::Float64(id as f64 / 2.0)),
+            ]))
+            .unwrap()
+        }
+
+        #[test]
+        fn matches_interpret_enum() {
+            let spec = DesignationSpecification::from_text(
+                "id: u32, xs: f64[], name: string, ys: i16[2], q: i8 @ q7.1",
+            )
+            .unwrap();
+            let buffers = [
+                record(&spec, 1, &[1.0, 2.0, 3.0], "first"),
+                record(&spec, 2, &[], ""),
+                record(&spec, 3, &[4.5], "third record"),
+            ];
+            let mut scratch = DecodeScratch::new();
+            for buffer in &buffers {
+                let expected = spec.interpret_enum(buffer).unwrap();
+                let found = spec.interpret_enum_reuse(buffer, &mut scratch).unwrap();
+                assert_eq!(found, &expected);
+            }
+        }
+
+        #[test]
+        fn reuses_array_allocation() {
+            let spec = DesignationSpecification::from_text("id: u32, xs: f64[]").unwrap();
+            let pack = |xs: Vec<f64>| {
+                spec.pack(&HashMap::from([
+                    ("id", DataValue::UnsignedInteger32(0)),
+                    ("xs", DataValue::Float64Array(xs)),
+                ]))
+                .unwrap()
+            };
+            let mut scratch = DecodeScratch::new();
+            let first = spec
+                .interpret_enum_reuse(&pack(vec![0.0; 16]), &mut scratch)
+                .unwrap();
+            let DataValue::Float64Array(xs) = &first["xs"] else {
+                panic!("expected an f64 array");
+            };
+            let ptr = xs.as_ptr();
+            let second = spec
+                .interpret_enum_reuse(&pack(vec![1.0, 2.0]), &mut scratch)
+                .unwrap();
+            assert_eq!(second["xs"], DataValue::Float64Array(vec![1.0, 2.0]));
+            let DataValue::Float64Array(xs) = &second["xs"] else {
+                panic!("expected an f64 array");
+            };
+            assert_eq!(xs.as_ptr(), ptr);
+        }
+
+        #[test]
+        fn short_buffer_err() {
+            let spec = DesignationSpecification::from_text("id: u32, xs: f64[]").unwrap();
+            let mut scratch = DecodeScratch::new();
+            let mut buffer = 1u32.to_le_bytes().to_vec();
+            buffer.extend(10u64.to_le_bytes());
+            assert!(matches!(
+                spec.interpret_enum_reuse(&buffer, &mut scratch),
+                Err(ElucidatorError::ArrayTooLarge { .. })
+            ));
+        }
+
+        #[test]
+        fn scaled_huge_prefix_err() {
+            let spec = DesignationSpecification::from_text("xs: i32[] @ q16.16").unwrap();
+            let mut scratch = DecodeScratch::new();
+            let mut buffer = i64::MAX.to_le_bytes().to_vec();
+            buffer.extend(1i32.to_le_bytes());
+            assert_eq!(
+                spec.interpret_enum_reuse(&buffer, &mut scratch).err(),
+                Some(ElucidatorError::ArrayTooLarge {
+                    member: "xs".to_string(),
+                    requested: i64::MAX as usize,
+                    limit: 1,
+                })
+            );
+        }
+    }
+
+    mod scaling {
+        use super::*;
+        use crate::member::Scaling;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn q16_16_decodes_to_float() {
+            let spec = DesignationSpecification::from_text("x: i32 @ q16.16").unwrap();
+            let raw: i32 = -147456; // -2.25 * 2^16
+            let values = spec.interpret_enum(&raw.to_le_bytes()).unwrap();
+            assert_eq!(values["x"], DataValue::Float64(-2.25));
+            let boxed = spec.interpret(&raw.to_le_bytes()).unwrap();
+            assert_eq!(boxed["x"].as_f64(), Ok(-2.25));
+        }
+
+        #[test]
+        fn unannotated_stays_raw() {
+            let spec = DesignationSpecification::from_text("x: i32").unwrap();
+            let values = spec.interpret_enum(&98304i32.to_le_bytes()).unwrap();
+            assert_eq!(values["x"], DataValue::SignedInteger32(98304));
+        }
+
+        #[test]
+        fn array_decodes_to_floats() {
+            let spec = DesignationSpecification::from_text("xs: u16[2] @ q8.8").unwrap();
+            let buffer: Vec<u8> = [384u16, 64].iter().flat_map(|x| x.to_le_bytes()).collect();
+            let values = spec.interpret_enum(&buffer).unwrap();
+            assert_eq!(values["xs"], DataValue::Float64Array(vec![1.5, 0.25]));
+        }
+
+        #[test]
+        fn pack_round_trip_ok() {
+            let spec = DesignationSpecification::from_text("x: i32 @ q16.16, n: u8").unwrap();
+            let buffer = spec
+                .pack(&HashMap::from([
+                    ("x", DataValue::Float64(1.5)),
+                    ("n", DataValue::Byte(3)),
+                ]))
+                .unwrap();
+            assert_eq!(buffer[..4], 98304i32.to_le_bytes());
+            assert_eq!(
+                spec.interpret_enum(&buffer).unwrap()["x"],
+                DataValue::Float64(1.5)
+            );
+        }
+
+        #[test]
+        fn display_round_trip_ok() {
+            let text = "x: i32 @ q16.16 \"position\"";
+            let spec = DesignationSpecification::from_text(text).unwrap();
+            assert_eq!(spec.to_string(), text);
+            assert_eq!(
+                spec.member("x").unwrap().scaling(),
+                Some(&Scaling {
+                    int_bits: 16,
+                    frac_bits: 16
+                })
+            );
+            assert_eq!(
+                DesignationSpecification::from_text(&spec.to_string()),
+                Ok(spec)
+            );
+        }
+
+        #[test]
+        fn float_dtype_err() {
+            assert!(DesignationSpecification::from_text("x: f32 @ q16.16").is_err());
+        }
+
+        #[test]
+        fn malformed_annotation_err() {
+            assert!(DesignationSpecification::from_text("x: i32 @ q16").is_err());
+            assert!(DesignationSpecification::from_text("x: i32 @").is_err());
+        }
+    }
+
+    mod enum_mapping {
+        use super::*;
+        use crate::member::EnumMapping;
+        use pretty_assertions::assert_eq;
+
+        const TEXT: &str = "mode: enum u8 { idle=0, active=1, error=2 }, count: u16";
+
+        fn named() -> InterpretOptions {
+            InterpretOptions {
+                enum_names: true,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn parse_ok() {
+            let spec = DesignationSpecification::from_text(TEXT).unwrap();
+            let mode = spec.member("mode").unwrap();
+            assert_eq!(mode.dtype(), &Dtype::Byte);
+            assert_eq!(mode.sizing(), &Sizing::Singleton);
+            assert_eq!(
+                mode.enum_mapping(),
+                Some(&EnumMapping::new(&[
+                    ("idle", 0),
+                    ("active", 1),
+                    ("error", 2)
+                ]))
+            );
+            assert_eq!(spec.member("count").unwrap().enum_mapping(), None);
+        }
+
+        #[test]
+        fn display_round_trip_ok() {
+            let spec = DesignationSpecification::from_text(TEXT).unwrap();
+            assert_eq!(spec.to_string(), TEXT);
+            assert_eq!(
+                DesignationSpecification::from_text(&spec.to_string()),
+                Ok(spec)
+            );
+        }
+
+        #[test]
+        fn mapped_value_decodes_to_name() {
+            let spec = DesignationSpecification::from_text(TEXT).unwrap();
+            let buffer = [1, 7, 0];
+            let values = spec.interpret_enum_with_options(&buffer, &named()).unwrap();
+            assert_eq!(values["mode"], DataValue::Str("active".to_string()));
+            assert_eq!(values["count"], DataValue::UnsignedInteger16(7));
+        }
+
+        #[test]
+        fn unmapped_value_stays_raw() {
+            let spec = DesignationSpecification::from_text(TEXT).unwrap();
+            let values = spec
+                .interpret_enum_with_options(&[9, 0, 0], &named())
+                .unwrap();
+            assert_eq!(values["mode"], DataValue::Byte(9));
+        }
+
+        #[test]
+        fn names_off_by_default() {
+            let spec = DesignationSpecification::from_text(TEXT).unwrap();
+            let values = spec.interpret_enum(&[1, 0, 0]).unwrap();
+            assert_eq!(values["mode"], DataValue::Byte(1));
+        }
+
+        #[test]
+        fn duplicate_discriminant_err() {
+            let text = "mode: enum u8 { idle=0, active=1, error=1 }";
+            let err = DesignationSpecification::from_text(text).unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("Enum discriminant 1 is given to more than one variant"),
+                "{err}"
+            );
+        }
+
+        #[test]
+        fn non_integer_err() {
+            for text in [
+                "mode: enum f32 { idle=0 }",
+                "mode: enum string { idle=0 }",
+                "mode: enum u8[2] { idle=0 }",
+                "mode: enum i32 { idle=0 } @ q16.16",
+            ] {
+                assert!(DesignationSpecification::from_text(text).is_err(), "{text}");
+            }
+        }
+
+        #[test]
+        fn malformed_mapping_err() {
+            for text in [
+                "mode: enum u8",
+                "mode: enum u8 { idle=0",
+                "mode: enum u8 { idle }",
+                "mode: enum u8 { }",
+            ] {
+                assert!(DesignationSpecification::from_text(text).is_err(), "{text}");
+            }
+        }
+    }
+
+    mod duration {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_ok() {
+            let spec =
+                DesignationSpecification::from_text("dt: duration, dts: duration[]").unwrap();
+            assert_eq!(
+                spec.dtype_of("dt"),
+                Some((Dtype::Duration, Sizing::Singleton))
+            );
+            assert_eq!(
+                spec.dtype_of("dts"),
+                Some((Dtype::Duration, Sizing::Dynamic))
+            );
+            assert_eq!(spec.to_string(), "dt: duration, dts: duration[]");
+        }
+
+        #[test]
+        fn round_trip_ok() {
+            let spec =
+                DesignationSpecification::from_text("dt: duration, dts: duration[2]").unwrap();
+            let values = HashMap::from([
+                ("dt", DataValue::Duration(-1_500)),
+                ("dts", DataValue::DurationArray(vec![1, i64::MAX])),
+            ]);
+            let buffer = spec.pack(&values).unwrap();
+            let expected: Vec<u8> = [-1_500, 1, i64::MAX]
+                .iter()
+                .flat_map(|v: &i64| v.to_le_bytes())
+                .collect();
+            assert_eq!(buffer, expected);
+            let decoded = spec.interpret_enum(&buffer).unwrap();
+            assert_eq!(decoded["dt"], values["dt"]);
+            assert_eq!(decoded["dts"], values["dts"]);
+            assert_eq!(spec.interpret(&buffer).unwrap()["dt"].as_i64(), Ok(-1_500));
+        }
+
+        #[test]
+        fn packs_from_i64_and_std_duration() {
+            let spec = DesignationSpecification::from_text("dt: duration").unwrap();
+            let from_i64 = spec
+                .pack(&HashMap::from([("dt", DataValue::SignedInteger64(2_000))]))
+                .unwrap();
+            let std_duration = std::time::Duration::from_micros(2);
+            assert_eq!(from_i64, std_duration.as_buffer());
+            assert_eq!(std_duration.get_dtype(), Dtype::Duration);
+        }
+
+        #[test]
+        fn as_std_duration_ok() {
+            let spec = DesignationSpecification::from_text("dt: duration").unwrap();
+            let decoded = spec.interpret_enum(&3_000_000_i64.to_le_bytes()).unwrap();
+            assert_eq!(
+                decoded["dt"].as_std_duration(),
+                Some(std::time::Duration::from_millis(3))
+            );
+            assert_eq!(DataValue::Duration(-3).as_std_duration(), None);
+            assert_eq!(DataValue::SignedInteger64(3).as_std_duration(), None);
+        }
+    }
+
+    mod parse_document {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn two_designations_ok() {
+            let document = "FooBar = foo: u32, bar: i8\n\n# Continued on the next line\n\"Baz\" = a: u8,\n  b: f64[]\n";
+            let specs = parse_document(document).unwrap();
+            assert_eq!(specs.len(), 2);
+            assert_eq!(
+                specs["FooBar"],
+                DesignationSpecification::from_text("foo: u32, bar: i8").unwrap()
+            );
+            assert_eq!(
+                specs["Baz"],
+                DesignationSpecification::from_text("a: u8, b: f64[]").unwrap()
+            );
+        }
+
+        #[test]
+        fn keyed_names_ok() {
+            let document = "name = \"FooBar\": foo: u32, bar: i8\nname = \"Baz Qux\": a: u8,\n  b: f64[]\nname = c: u16";
+            let specs = parse_document(document).unwrap();
+            assert_eq!(specs.len(), 3);
+            assert_eq!(
+                specs["FooBar"],
+                DesignationSpecification::from_text("foo: u32, bar: i8").unwrap()
+            );
+            assert_eq!(
+                specs["Baz Qux"],
+                DesignationSpecification::from_text("a: u8, b: f64[]").unwrap()
+            );
+            assert_eq!(
+                specs["name"],
+                DesignationSpecification::from_text("c: u16").unwrap()
+            );
+        }
+
+        #[test]
+        fn duplicate_name_err() {
+            let document = "Foo = a: u8\nBar = b: u8\n\"Foo\" = c: u16";
+            assert_eq!(
+                parse_document(document),
+                Err(ElucidatorError::DuplicateDesignation {
+                    name: "Foo".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn malformed_line_err() {
+            for (document, line) in [
+                ("Foo = a: u8\nBar a: u8", 2),
+                ("  a: u8", 1),
+                ("= a: u8", 1),
+                ("Foo = a: u8\nname = \"Bar\" a: u8", 2),
+            ] {
+                assert_eq!(
+                    parse_document(document),
+                    Err(ElucidatorError::MalformedDocument { line }),
+                    "{document}"
+                );
+            }
+        }
+
+        #[test]
+        fn invalid_spec_names_designation() {
+            let err = parse_document("Foo = a: u8\nBar = b: u9").unwrap_err();
+            assert!(
+                matches!(&err, ElucidatorError::InvalidDesignation { name, .. } if name == "Bar"),
+                "{err}"
+            );
+        }
+    }
+
+    mod char {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_ok() {
+            let spec = DesignationSpecification::from_text("c: char").unwrap();
+            assert_eq!(spec.dtype_of("c"), Some((Dtype::Char, Sizing::Singleton)));
+            assert_eq!(spec.fixed_size_bytes(), 4);
+            assert_eq!(spec.to_string(), "c: char");
+        }
+
+        #[test]
+        fn arrays_and_scaling_err() {
+            assert!(DesignationSpecification::from_text("c: char[2]").is_err());
+            assert!(DesignationSpecification::from_text("c: char[]").is_err());
+            assert!(DesignationSpecification::from_text("c: char @ q8.8").is_err());
+        }
+
+        #[test]
+        fn round_trip_ok() {
+            let spec = DesignationSpecification::from_text("c: char, n: u8").unwrap();
+            let values = HashMap::from([("c", DataValue::Char('é')), ("n", DataValue::Byte(1))]);
+            let buffer = spec.pack(&values).unwrap();
+            assert_eq!(buffer, [0xe9, 0, 0, 0, 1]);
+            assert_eq!('é'.as_buffer(), buffer[..4]);
+            let decoded = spec.interpret_enum(&buffer).unwrap();
+            assert_eq!(decoded["c"], DataValue::Char('é'));
+            let interpreted = spec.interpret(&buffer).unwrap();
+            assert_eq!(interpreted["c"].as_u32(), Ok(0xe9));
+            assert_eq!(interpreted["c"].as_string(), Ok("é".to_string()));
+        }
+
+        #[test]
+        fn invalid_scalar_value_err() {
+            let spec = DesignationSpecification::from_text("c: char").unwrap();
+            for value in [0xd800u32, 0x110000] {
+                let expected = Err(ElucidatorError::InvalidChar { value });
+                assert_eq!(spec.interpret_enum(&value.to_le_bytes()), expected);
+                assert!(spec.interpret(&value.to_le_bytes()).is_err());
+            }
+        }
+
+        #[test]
+        fn packs_to_u32_and_string() {
+            let spec = DesignationSpecification::from_text("n: u32, s: string").unwrap();
+            let values = HashMap::from([("n", DataValue::Char('A')), ("s", DataValue::Char('A'))]);
+            let decoded = spec.interpret_enum(&spec.pack(&values).unwrap()).unwrap();
+            assert_eq!(decoded["n"], DataValue::UnsignedInteger32(65));
+            assert_eq!(decoded["s"], DataValue::Str("A".to_string()));
+        }
+    }
+
+    mod collect_member {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn records(spec: &DesignationSpecification, times: &[u64]) -> Vec<Vec<u8>> {
+            times
+                .iter()
+                .map(|t| {
+                    spec.pack(&HashMap::from([
+                        ("time", DataValue::UnsignedInteger64(*t)),
+                        ("flag", DataValue::Byte(1)),
+                    ]))
+                    .unwrap()
+                })
+                .collect()
+        }
+
+        #[test]
+        fn u64_column_ok() {
+            let spec = DesignationSpecification::from_text("time: u64, flag: u8").unwrap();
+            let buffers = records(&spec, &[5, 3, 9]);
+            let decoded: Vec<HashMap<&str, DataValue>> = buffers
+                .iter()
+                .map(|b| spec.interpret_enum(b).unwrap())
+                .collect();
+            assert_eq!(
+                DesignationSpecification::collect_member::<u64>(&decoded, "time"),
+                Ok(vec![5, 3, 9])
+            );
+        }
+
+        #[test]
+        fn type_mismatch_err() {
+            let spec = DesignationSpecification::from_text("time: u64, flag: u8").unwrap();
+            let buffers = records(&spec, &[5]);
+            let decoded: Vec<HashMap<&str, DataValue>> = buffers
+                .iter()
+                .map(|b| spec.interpret_enum(b).unwrap())
+                .collect();
+            assert_eq!(
+                DesignationSpecification::collect_member::<u32>(&decoded, "time"),
+                Err(ElucidatorError::Conversion {
+                    from: "u64".to_string(),
+                    to: "u32".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn missing_member_err() {
+            let records = vec![HashMap::from([("time", DataValue::UnsignedInteger64(1))])];
+            assert_eq!(
+                DesignationSpecification::collect_member::<u64>(&records, "flag"),
+                Err(ElucidatorError::MissingValue {
+                    member: "flag".to_string()
+                })
+            );
+        }
+    }
+
+    mod interpret_enum_recovering {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn truncated_fixed_member_recovers() {
+            let spec = DesignationSpecification::from_text("a: u8, b: u32, c: f32").unwrap();
+            let buffer = [7u8, 1, 2];
+            let (values, errors) = spec.interpret_enum_recovering(&buffer);
+            assert_eq!(
+                values,
+                HashMap::from([
+                    ("a", DataValue::Byte(7)),
+                    ("b", DataValue::UnsignedInteger32(0)),
+                    ("c", DataValue::Float32(0.0)),
+                ])
+            );
+            let failed: Vec<&str> = errors.iter().map(|e| e.member.as_str()).collect();
+            assert_eq!(failed, vec!["b", "c"]);
+            assert_eq!(
+                errors[0].error,
+                ElucidatorError::BufferSizing {
+                    expected: 4,
+                    found: 2
+                }
+            );
+        }
+
+        #[test]
+        fn bad_dynamic_member_aborts() {
+            let spec = DesignationSpecification::from_text("a: u8, b: f32[], c: u8").unwrap();
+            let mut buffer = vec![7u8];
+            buffer.extend(1000u64.to_le_bytes());
+            buffer.extend([0u8; 5]);
+            let (values, errors) = spec.interpret_enum_recovering(&buffer);
+            assert_eq!(values, HashMap::from([("a", DataValue::Byte(7))]));
+            assert_eq!(
+                errors,
+                vec![MemberDecodeError {
+                    member: "b".to_string(),
+                    error: ElucidatorError::ArrayTooLarge {
+                        member: "b".to_string(),
+                        requested: 1000,
+                        limit: 1,
+                    },
+                }]
+            );
+        }
+
+        #[test]
+        fn clean_buffer_no_errors() {
+            let spec = DesignationSpecification::from_text("a: u8, s: string").unwrap();
+            let values = HashMap::from([
+                ("a", DataValue::Byte(1)),
+                ("s", DataValue::Str("ok".to_string())),
+            ]);
+            let buffer = spec.pack(&values).unwrap();
+            assert_eq!(
+                spec.interpret_enum_recovering(&buffer),
+                (values, Vec::new())
+            );
+        }
+    }
+
+    mod interpret_enum_renamed {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn buffer() -> Vec<u8> {
+            let mut buffer = 7u8.to_le_bytes().to_vec();
+            buffer.extend(2.5f32.to_le_bytes());
+            buffer
+        }
+
+        #[test]
+        fn rename_one_ok() {
+            let spec = DesignationSpecification::from_text("id: u8, temp: f32").unwrap();
+            let rename = HashMap::from([("temp", "temperature")]);
+            let values = spec.interpret_enum_renamed(&buffer(), &rename).unwrap();
+            let expected = HashMap::from([
+                ("id".to_string(), DataValue::Byte(7)),
+                ("temperature".to_string(), DataValue::Float32(2.5)),
+            ]);
+            assert_eq!(values, expected);
+        }
+
+        #[test]
+        fn collision_err() {
+            let spec = DesignationSpecification::from_text("id: u8, temp: f32").unwrap();
+            let rename = HashMap::from([("temp", "id")]);
+            let result = spec.interpret_enum_renamed(&buffer(), &rename);
+            assert_eq!(
+                result,
+                Err(ElucidatorError::RenameCollision {
+                    name: "id".to_string()
+                })
+            );
+        }
+    }
+
+    mod string_encoding {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn nul_options() -> InterpretOptions {
+            InterpretOptions {
+                string_encoding: StringEncoding::NulTerminated,
+                ..Default::default()
+            }
+        }
+
+        fn nul_pack_options() -> PackOptions {
+            PackOptions {
+                string_encoding: StringEncoding::NulTerminated,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn nul_terminated_round_trip_ok() {
+            let spec = DesignationSpecification::from_text("name: string, id: u16").unwrap();
+            let values = HashMap::from([
+                ("name", DataValue::Str("probe".to_string())),
+                ("id", DataValue::UnsignedInteger16(9)),
+            ]);
+            let buffer = spec
+                .pack_with_options(&values, &nul_pack_options())
+                .unwrap();
+            let mut expected = b"probe\0".to_vec();
+            expected.extend(9u16.to_le_bytes());
+            assert_eq!(buffer, expected);
+            let decoded = spec
+                .interpret_enum_with_options(&buffer, &nul_options())
+                .unwrap();
+            assert_eq!(decoded, values);
+        }
+
+        #[test]
+        fn empty_nul_terminated_ok() {
+            let spec = DesignationSpecification::from_text("name: string").unwrap();
+            let decoded = spec
+                .interpret_enum_with_options(&[0], &nul_options())
+                .unwrap();
+            assert_eq!(decoded["name"], DataValue::Str(String::new()));
+        }
+
+        #[test]
+        fn missing_terminator_err() {
+            let spec = DesignationSpecification::from_text("id: u8, name: string").unwrap();
+            let result = spec.interpret_enum_with_options(b"\x01abc", &nul_options());
+            assert_eq!(result, Err(ElucidatorError::MissingNulTerminator));
+        }
+
+        #[test]
+        fn interior_nul_err() {
+            let spec = DesignationSpecification::from_text("name: string").unwrap();
+            let values = HashMap::from([("name", DataValue::Str("a\0b".to_string()))]);
+            let result = spec.pack_with_options(&values, &nul_pack_options());
+            assert_eq!(
+                result,
+                Err(ElucidatorError::InteriorNul {
+                    member: "name".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn length_prefixed_default_unchanged() {
+            let spec = DesignationSpecification::from_text("name: string").unwrap();
+            let values = HashMap::from([("name", DataValue::Str("ab".to_string()))]);
+            let buffer = spec
+                .pack_with_options(&values, &PackOptions::default())
+                .unwrap();
+            assert_eq!(buffer, spec.pack(&values).unwrap());
+            assert_eq!(&buffer[..8], &2u64.to_le_bytes());
+        }
+    }
+
+    mod string_max_len {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn capped() -> DesignationSpecification {
+            DesignationSpecification::from_text("id: u8, name: string(4)").unwrap()
+        }
+
+        fn record(name: &str) -> Vec<u8> {
+            DesignationSpecification::from_text("id: u8, name: string")
+                .unwrap()
+                .pack(&HashMap::from([
+                    ("id", DataValue::Byte(1)),
+                    ("name", DataValue::Str(name.to_string())),
+                ]))
+                .unwrap()
+        }
+
+        fn too_long(found: usize) -> ElucidatorError {
+            ElucidatorError::StringTooLong {
+                member: "name".to_string(),
+                max: 4,
+                found,
+            }
+        }
+
+        #[test]
+        fn parse_ok() {
+            let spec = DesignationSpecification::from_text("name: string(64)").unwrap();
+            assert_eq!(spec.member("name").unwrap().max_len(), Some(64));
+            assert_eq!(spec.to_string(), "name: string(64)");
+            let spec = DesignationSpecification::from_text(r#"name: string ( 64 ) "doc""#);
+            assert_eq!(spec.unwrap().to_string(), r#"name: string(64) "doc""#);
+        }
+
+        #[test]
+        fn non_string_err() {
+            for text in ["n: u8(4)", "c: char(1)", "xs: f32(2)"] {
+                assert!(DesignationSpecification::from_text(text).is_err(), "{text}");
+            }
+        }
+
+        #[test]
+        fn within_cap_ok() {
+            let spec = capped();
+            for name in ["", "abcd"] {
+                let decoded = spec.interpret_enum(&record(name)).unwrap();
+                assert_eq!(decoded["name"], DataValue::Str(name.to_string()));
+            }
+            let values = HashMap::from([
+                ("id", DataValue::Byte(1)),
+                ("name", DataValue::Str("abcd".to_string())),
+            ]);
+            assert_eq!(spec.pack(&values), Ok(record("abcd")));
+        }
+
+        #[test]
+        fn over_cap_decode_err() {
+            let spec = capped();
+            let buffer = record("abcde");
+            assert_eq!(spec.interpret_enum(&buffer), Err(too_long(5)));
+            assert!(matches!(spec.interpret(&buffer), Err(e) if e == too_long(5)));
+            let mut scratch = DecodeScratch::new();
+            assert_eq!(
+                spec.interpret_enum_reuse(&buffer, &mut scratch).err(),
+                Some(too_long(5))
+            );
+            let options = InterpretOptions {
+                string_encoding: StringEncoding::NulTerminated,
+                ..Default::default()
+            };
+            assert_eq!(
+                spec.interpret_enum_with_options(b"\x01abcdef\0", &options),
+                Err(too_long(6))
+            );
+        }
+
+        #[test]
+        fn over_cap_pack_err() {
+            let values = HashMap::from([
+                ("id", DataValue::Byte(1)),
+                ("name", DataValue::Str("abcde".to_string())),
+            ]);
+            assert_eq!(capped().pack(&values), Err(too_long(5)));
+        }
+    }
+
+    mod record_writer {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn spec() -> DesignationSpecification {
+            DesignationSpecification::from_text("id: u32, xs: f64[], tag: u8[2], name: string")
+                .unwrap()
+        }
+
+        #[test]
+        fn sequence_matches_pack() {
+            let spec = spec();
+            let mut writer = RecordWriter::new(&spec);
+            assert_eq!(writer.next_member(), Some("id"));
+            // Narrower values widen to the member's dtype, as with pack
+            writer.write(7u8).unwrap();
+            writer.write(vec![1.5, -2.0]).unwrap();
+            writer.write(vec![3u8, 4]).unwrap();
+            writer.write("probe".to_string()).unwrap();
+            assert_eq!(writer.next_member(), None);
+
+            let values = HashMap::from([
+                ("id", DataValue::UnsignedInteger32(7)),
+                ("xs", DataValue::Float64Array(vec![1.5, -2.0])),
+                ("tag", DataValue::ByteArray(vec![3, 4])),
+                ("name", DataValue::Str("probe".to_string())),
+            ]);
+            assert_eq!(writer.finish(), spec.pack(&values));
+        }
+
+        #[test]
+        fn wrong_type_err() {
+            let spec = spec();
+            let mut writer = RecordWriter::new(&spec);
+            assert!(matches!(
+                writer.write("seven".to_string()),
+                Err(ElucidatorError::Conversion { .. })
+            ));
+            // A singleton where an array is expected is also rejected
+            writer.write(7u32).unwrap();
+            assert!(matches!(
+                writer.write(1.5f64),
+                Err(ElucidatorError::Conversion { .. })
+            ));
+            writer.write(vec![1.5]).unwrap();
+            assert!(matches!(
+                writer.write(vec![1u8, 2, 3]),
+                Err(ElucidatorError::Conversion { .. })
+            ));
+            // Rejected writes leave nothing behind
+            writer.write(vec![1u8, 2]).unwrap();
+            writer.write(String::new()).unwrap();
+            let buffer = writer.finish().unwrap();
+            assert_eq!(
+                spec.interpret_enum(&buffer).unwrap()["tag"].to_string(),
+                "[1, 2]"
+            );
+        }
+
+        #[test]
+        fn incomplete_err() {
+            let spec = spec();
+            let mut writer = RecordWriter::new(&spec);
+            writer.write(7u32).unwrap();
+            writer.write(vec![1.5]).unwrap();
+            assert_eq!(
+                writer.finish(),
+                Err(ElucidatorError::MissingValue {
+                    member: "tag".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn extra_value_err() {
+            let spec = DesignationSpecification::from_text("id: u32").unwrap();
+            let mut writer = RecordWriter::new(&spec);
+            writer.write(7u32).unwrap();
+            assert_eq!(
+                writer.write(8u32),
+                Err(ElucidatorError::RecordComplete { members: 1 })
+            );
+            assert_eq!(writer.finish(), Ok(7u32.to_le_bytes().to_vec()));
+        }
+    }
+
+    mod content_hash {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn equal_values_same_hash() {
+            let spec =
+                DesignationSpecification::from_text("id: u32, xs: f64[], name: string").unwrap();
+            let values = HashMap::from([
+                ("id", DataValue::UnsignedInteger32(7)),
+                ("xs", DataValue::Float64Array(vec![1.5, f64::NAN])),
+                ("name", DataValue::Str("probe".to_string())),
+            ]);
+            let buffer = spec.pack(&values).unwrap();
+            let mut padded = buffer.clone();
+            padded.extend([0; 5]);
+            assert_ne!(buffer, padded);
+            assert_eq!(spec.content_hash(&padded), spec.content_hash(&buffer));
+
+            let mut other = values.clone();
+            other.insert("name", DataValue::Str("probes".to_string()));
+            let other = spec.pack(&other).unwrap();
+            assert_ne!(spec.content_hash(&other), spec.content_hash(&buffer));
+        }
+
+        #[test]
+        fn undecodable_err() {
+            let spec = DesignationSpecification::from_text("id: u32").unwrap();
+            assert!(spec.content_hash(&[1, 2]).is_err());
+        }
+    }
+
+    mod dtype_of {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn known_member_ok() {
+            let spec = DesignationSpecification::from_text("a: u8, b: f32[3], c: string").unwrap();
+            assert_eq!(spec.dtype_of("a"), Some((Dtype::Byte, Sizing::Singleton)));
+            assert_eq!(spec.dtype_of("b"), Some((Dtype::Float32, Sizing::Fixed(3))));
+            assert_eq!(spec.dtype_of("c"), Some((Dtype::Str, Sizing::Singleton)));
+        }
+
+        #[test]
+        fn unknown_member_none() {
+            let spec = DesignationSpecification::from_text("a: u8").unwrap();
+            assert_eq!(spec.dtype_of("z"), None);
+        }
+    }
+
+    mod equivalent_unordered {
+        use super::*;
+
+        #[test]
+        fn reordered_true() {
+            let a = DesignationSpecification::from_text("a: u8, b: f32[3], c: string").unwrap();
+            let b = DesignationSpecification::from_text("c: string, a: u8, b: f32[3]").unwrap();
+            assert!(a.equivalent_unordered(&b));
+            assert!(b.equivalent_unordered(&a));
+            assert_ne!(a, b);
+        }
+
+        #[test]
+        fn different_sizing_false() {
+            let a = DesignationSpecification::from_text("a: u8, b: f32[3]").unwrap();
+            let b = DesignationSpecification::from_text("b: f32[], a: u8").unwrap();
+            assert!(!a.equivalent_unordered(&b));
+        }
+
+        #[test]
+        fn extra_member_false() {
+            let a = DesignationSpecification::from_text("a: u8").unwrap();
+            let b = DesignationSpecification::from_text("a: u8, b: u8").unwrap();
+            assert!(!a.equivalent_unordered(&b));
+            assert!(!b.equivalent_unordered(&a));
+        }
+    }
+
+    mod validate_buffer_length {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn exact_length_ok() {
+            let spec = DesignationSpecification::from_text("a: u32, b: f64[2]").unwrap();
+            assert_eq!(spec.validate_buffer_length(&[0; 20]), Ok(()));
+        }
+
+        #[test]
+        fn short_buffer_err() {
+            let spec = DesignationSpecification::from_text("a: u32, b: f64[2]").unwrap();
+            assert_eq!(
+                spec.validate_buffer_length(&[0; 12]),
+                Err(ElucidatorError::BufferSizing {
+                    expected: 20,
+                    found: 12
+                })
+            );
+        }
+
+        #[test]
+        fn dynamic_spec_err() {
+            let spec = DesignationSpecification::from_text("a: u32, b: f64[], c: string").unwrap();
+            assert_eq!(
+                spec.validate_buffer_length(&[0; 20]),
+                Err(ElucidatorError::NotFixedSize {
+                    dynamic_members: vec!["b".to_string(), "c".to_string()]
+                })
+            );
         }
     }
 
-    fn random_sizing() -> Sizing {
-        let num = random::<u8>() % 3;
-        match num {
-            0 => Sizing::Singleton,
-            1 => Sizing::Fixed((random::<u8>() % 100 + 1) as u64),
-            2 => Sizing::Dynamic,
-            _ => {
-                unreachable!();
+    mod pad_short_buffer {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn padded() -> InterpretOptions {
+            InterpretOptions {
+                pad_short_buffer: true,
+                ..Default::default()
             }
         }
-    }
 
-    fn random_dtype() -> Dtype {
-        let num = random::<u8>() % 11; // There are 11 variants in the Dtype enum
-        match num {
-            0 => Dtype::Byte,
-            1 => Dtype::UnsignedInteger16,
-            2 => Dtype::UnsignedInteger32,
-            3 => Dtype::UnsignedInteger64,
-            4 => Dtype::SignedInteger8,
-            5 => Dtype::SignedInteger16,
-            6 => Dtype::SignedInteger32,
-            7 => Dtype::SignedInteger64,
-            8 => Dtype::Float32,
-            9 => Dtype::Float64,
-            10 => Dtype::Str,
-            _ => unreachable!(),
+        #[test]
+        fn missing_trailing_u32_reads_zero() {
+            let spec = DesignationSpecification::from_text("a: u16, b: u32").unwrap();
+            let buffer = 7u16.to_le_bytes();
+            let values = spec
+                .interpret_enum_with_options(&buffer, &padded())
+                .unwrap();
+            assert_eq!(values["a"], DataValue::UnsignedInteger16(7));
+            assert_eq!(values["b"], DataValue::UnsignedInteger32(0));
+            assert!(spec
+                .interpret_enum_with_options(&buffer, &InterpretOptions::default())
+                .is_err());
         }
-    }
 
-    fn random_dtype_sizing() -> (Sizing, Dtype) {
-        let dtype = random_dtype();
-        let sizing = if let Dtype::Str = dtype {
-            Sizing::Singleton
-        } else {
-            random_sizing()
-        };
-        (sizing, dtype)
-    }
+        #[test]
+        fn empty_buffer_err() {
+            let spec = DesignationSpecification::from_text("a: u16, b: u32").unwrap();
+            assert_eq!(
+                spec.interpret_enum_with_options(&[], &padded()),
+                Err(ElucidatorError::EmptyBuffer {
+                    expected_at_least: 6
+                })
+            );
+        }
 
-    fn random_identifier() -> String {
-        let mut rng = rand::thread_rng();
-        let length = (random::<u8>() % 5) + 1;
-        (0..length)
-            .map(|_| (rng.gen_range(b'a'..=b'z') as char))
-            .collect()
+        #[test]
+        fn dynamic_spec_not_padded() {
+            let spec = DesignationSpecification::from_text("a: u16, xs: u8[]").unwrap();
+            let buffer = 7u16.to_le_bytes();
+            assert!(spec
+                .interpret_enum_with_options(&buffer, &padded())
+                .is_err());
+        }
     }
 
-    fn random_member_specification() -> MemberSpecification {
-        let (sizing, dtype) = random_dtype_sizing();
-        let identifier = random_identifier();
-        MemberSpecification {
-            identifier,
-            sizing,
-            dtype,
+    mod detect_endianness {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn spec() -> DesignationSpecification {
+            DesignationSpecification::from_text("id: u32, x: f64, count: u16, xs: i32[]").unwrap()
+        }
+
+        #[test]
+        fn little_endian_buffer() {
+            let mut buffer = Vec::new();
+            buffer.extend(42u32.to_le_bytes());
+            buffer.extend(3.5f64.to_le_bytes());
+            buffer.extend(7u16.to_le_bytes());
+            buffer.extend(2u64.to_le_bytes());
+            buffer.extend((-5i32).to_le_bytes());
+            buffer.extend(100i32.to_le_bytes());
+            assert_eq!(spec().detect_endianness(&buffer), EndiannessGuess::Little);
+        }
+
+        #[test]
+        fn big_endian_buffer() {
+            let mut buffer = Vec::new();
+            buffer.extend(42u32.to_be_bytes());
+            buffer.extend(3.5f64.to_be_bytes());
+            buffer.extend(7u16.to_be_bytes());
+            buffer.extend(2u64.to_be_bytes());
+            buffer.extend((-5i32).to_be_bytes());
+            buffer.extend(100i32.to_be_bytes());
+            assert_eq!(spec().detect_endianness(&buffer), EndiannessGuess::Big);
+        }
+
+        #[test]
+        fn bytes_only_ambiguous() {
+            let spec = DesignationSpecification::from_text("a: u8, b: u8").unwrap();
+            assert_eq!(spec.detect_endianness(&[1, 2]), EndiannessGuess::Ambiguous);
+        }
+
+        #[test]
+        fn undecodable_ambiguous() {
+            assert_eq!(
+                spec().detect_endianness(&[1, 2, 3]),
+                EndiannessGuess::Ambiguous
+            );
         }
     }
 
-    fn random_designation_specification() -> DesignationSpecification {
-        let num_members = random::<u8>() % 20 + 1;
-        let members = loop {
-            let candidates = (0..num_members)
-                .map(|_| random_member_specification())
-                .collect::<Vec<_>>();
-            let unique_names = candidates
-                .iter()
-                .map(|x| x.identifier.clone())
-                .collect::<HashSet<_>>();
-            if candidates.len() == unique_names.len() {
-                break candidates;
+    mod max_dynamic_elements {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn bogus_length_prefix_err() {
+            let spec = DesignationSpecification::from_text("a: u8, xs: f64[]").unwrap();
+            let mut buffer = vec![1];
+            buffer.extend(u64::MAX.to_le_bytes());
+            buffer.extend(1.0f64.to_le_bytes());
+            assert_eq!(
+                spec.interpret_enum(&buffer),
+                Err(ElucidatorError::ArrayTooLarge {
+                    member: "xs".to_string(),
+                    requested: u64::MAX as usize,
+                    limit: 1,
+                })
+            );
+        }
+
+        #[test]
+        fn bogus_length_prefix_err_on_every_path() {
+            let spec = DesignationSpecification::from_text("a: u8, xs: f64[], y: f32").unwrap();
+            let mut buffer = vec![1];
+            buffer.extend(u64::MAX.to_le_bytes());
+            buffer.extend(1.0f64.to_le_bytes());
+            let expected = ElucidatorError::ArrayTooLarge {
+                member: "xs".to_string(),
+                requested: u64::MAX as usize,
+                limit: 1,
+            };
+            assert_eq!(spec.interpret(&buffer).err(), Some(expected.clone()));
+            assert_eq!(
+                spec.interpret_subset(&buffer, &["xs"]),
+                Err(expected.clone())
+            );
+            assert_eq!(
+                spec.interpret_subset(&buffer, &["y"]),
+                Err(expected.clone())
+            );
+            assert_eq!(spec.first_non_finite_member(&buffer), Err(expected.clone()));
+            assert_eq!(spec.record_len(&buffer), Err(expected.clone()));
+            let (_, errors) = spec.interpret_enum_recovering(&buffer);
+            assert_eq!(errors[0].error, expected);
+        }
+
+        #[test]
+        fn missing_length_prefix_err() {
+            let spec = DesignationSpecification::from_text("a: u8, xs: f64[]").unwrap();
+            let mut buffer = vec![1];
+            for x in [1.0f64, 2.5, -4.0] {
+                buffer.extend(x.to_le_bytes());
             }
-        };
-        DesignationSpecification { members }
+            let expected = ElucidatorError::LikelyMissingLengthPrefix {
+                member: "xs".to_string(),
+                decoded_count: 1.0f64.to_bits(),
+            };
+            assert_eq!(spec.interpret_enum(&buffer), Err(expected.clone()));
+            let mut scratch = DecodeScratch::new();
+            assert_eq!(
+                spec.interpret_enum_reuse(&buffer, &mut scratch).err(),
+                Some(expected.clone())
+            );
+            assert!(expected.to_string().contains("length prefix"));
+            assert_eq!(spec.interpret(&buffer).err(), Some(expected.clone()));
+            assert_eq!(
+                spec.interpret_subset(&buffer, &["xs"]),
+                Err(expected.clone())
+            );
+
+            let spec = DesignationSpecification::from_text("xs: i32[] @ q16.16").unwrap();
+            let mut buffer = Vec::new();
+            for x in [1i32, 2, 3] {
+                buffer.extend(x.to_le_bytes());
+            }
+            assert!(matches!(
+                spec.interpret_enum_reuse(&buffer, &mut DecodeScratch::new()),
+                Err(ElucidatorError::LikelyMissingLengthPrefix { .. })
+            ));
+        }
+
+        #[test]
+        fn byte_array_corrupt_prefix_too_large() {
+            let spec = DesignationSpecification::from_text("xs: u8[]").unwrap();
+            let mut buffer = vec![0x41; 8];
+            buffer.extend([1, 2, 3]);
+            assert_eq!(
+                spec.interpret_enum(&buffer),
+                Err(ElucidatorError::ArrayTooLarge {
+                    member: "xs".to_string(),
+                    requested: u64::from_le_bytes([0x41; 8]) as usize,
+                    limit: 3,
+                })
+            );
+        }
+
+        #[test]
+        fn explicit_limit_err() {
+            let spec = DesignationSpecification::from_text("xs: u8[]").unwrap();
+            let values = HashMap::from([("xs", DataValue::ByteArray(vec![0; 10]))]);
+            let buffer = spec.pack(&values).unwrap();
+            let options = InterpretOptions {
+                max_dynamic_elements: Some(4),
+                ..Default::default()
+            };
+            assert_eq!(
+                spec.interpret_enum_with_options(&buffer, &options),
+                Err(ElucidatorError::ArrayTooLarge {
+                    member: "xs".to_string(),
+                    requested: 10,
+                    limit: 4,
+                })
+            );
+            let options = InterpretOptions {
+                max_dynamic_elements: Some(10),
+                ..Default::default()
+            };
+            assert_eq!(
+                spec.interpret_enum_with_options(&buffer, &options),
+                Ok(values)
+            );
+        }
     }
 
-    fn generate_random_designation_specification_data(
-        designation_spec: &DesignationSpecification,
-    ) -> HashMap<&str, DataValue> {
-        let mut data_map = HashMap::new();
+    mod interpret_subset {
+        use super::*;
+        use pretty_assertions::assert_eq;
 
-        for member in &designation_spec.members {
-            let data_value = random_data_value(&member.dtype, &member.sizing);
-            data_map.insert(member.identifier.as_str(), data_value);
+        #[test]
+        fn skips_unrequested_ok() {
+            let spec = DesignationSpecification::from_text(
+                "a: string, b: f32[], c: u16[2], d: u64, e: i8",
+            )
+            .unwrap();
+            let values = HashMap::from([
+                ("a", DataValue::Str("skip me".to_string())),
+                ("b", DataValue::Float32Array(vec![1.0, 2.0, 3.0])),
+                ("c", DataValue::UnsignedInteger16Array(vec![4, 5])),
+                ("d", DataValue::UnsignedInteger64(6)),
+                ("e", DataValue::SignedInteger8(-7)),
+            ]);
+            let buffer = spec.pack(&values).unwrap();
+            let subset = spec
+                .interpret_subset(&buffer, &["d", "b", "missing"])
+                .unwrap();
+            assert_eq!(
+                subset,
+                HashMap::from([
+                    ("b", DataValue::Float32Array(vec![1.0, 2.0, 3.0])),
+                    ("d", DataValue::UnsignedInteger64(6)),
+                ])
+            );
         }
 
-        data_map
+        #[test]
+        fn short_buffer_err() {
+            let spec = DesignationSpecification::from_text("a: u32, b: u8").unwrap();
+            assert!(spec.interpret_subset(&[0, 0, 0], &["b"]).is_err());
+        }
     }
 
-    fn into_blob(dv: &DataValue, sizing: &Sizing) -> Vec<u8> {
-        let mut buffer = Vec::new();
+    mod layout {
+        use super::*;
+        use pretty_assertions::assert_eq;
 
-        if let Sizing::Dynamic = sizing {
-            let num_elements = match dv {
-                DataValue::ByteArray(v) => v.len() as u64,
-                DataValue::UnsignedInteger16Array(v) => v.len() as u64,
-                DataValue::UnsignedInteger32Array(v) => v.len() as u64,
-                DataValue::UnsignedInteger64Array(v) => v.len() as u64,
-                DataValue::SignedInteger8Array(v) => v.len() as u64,
-                DataValue::SignedInteger16Array(v) => v.len() as u64,
-                DataValue::SignedInteger32Array(v) => v.len() as u64,
-                DataValue::SignedInteger64Array(v) => v.len() as u64,
-                DataValue::Float32Array(v) => v.len() as u64,
-                DataValue::Float64Array(v) => v.len() as u64,
-                _ => {
-                    unreachable!("Only arrays should have dynamic sizing");
-                }
-            };
-            buffer.extend_from_slice(&num_elements.to_le_bytes());
+        fn entry(name: &str, offset: Option<usize>, dtype: Dtype, n: Option<usize>) -> LayoutEntry {
+            LayoutEntry {
+                name: name.to_string(),
+                offset,
+                dtype,
+                element_count: n,
+            }
         }
 
-        buffer.extend_from_slice(&dv.as_buffer());
-        buffer
+        #[test]
+        fn all_fixed_ok() {
+            let spec = DesignationSpecification::from_text("a: u8, b: f64[3], c: i16").unwrap();
+            assert_eq!(
+                spec.layout(),
+                vec![
+                    entry("a", Some(0), Dtype::Byte, Some(1)),
+                    entry("b", Some(1), Dtype::Float64, Some(3)),
+                    entry("c", Some(25), Dtype::SignedInteger16, Some(1)),
+                ]
+            );
+        }
+
+        #[test]
+        fn mixed_ok() {
+            let spec =
+                DesignationSpecification::from_text("a: u32, b: f32[], c: u8, d: string").unwrap();
+            assert_eq!(
+                spec.layout(),
+                vec![
+                    entry("a", Some(0), Dtype::UnsignedInteger32, Some(1)),
+                    entry("b", Some(4), Dtype::Float32, None),
+                    entry("c", None, Dtype::Byte, Some(1)),
+                    entry("d", None, Dtype::Str, Some(1)),
+                ]
+            );
+        }
+
+        #[test]
+        fn string_breaks_offsets() {
+            let spec = DesignationSpecification::from_text("s: string, x: u8").unwrap();
+            assert_eq!(
+                spec.layout(),
+                vec![
+                    entry("s", Some(0), Dtype::Str, Some(1)),
+                    entry("x", None, Dtype::Byte, Some(1)),
+                ]
+            );
+        }
     }
 
-    fn generate_designation_and_perform_round_trip() {
-        let designation = random_designation_specification();
-        let n_data = random::<u8>() % 50;
-        let data_vec: Vec<HashMap<&str, DataValue>> = (0..n_data)
-            .map(|_| generate_random_designation_specification_data(&designation))
-            .collect();
-        for datum in &data_vec {
-            let blob_vec: Vec<Vec<u8>> = designation
-                .members
-                .iter()
-                .map(|member| {
-                    let dv = datum.get(member.identifier.as_str()).unwrap();
-                    let sizing = &member.sizing;
-                    into_blob(dv, sizing)
+    mod pack {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn u8_spec() -> DesignationSpecification {
+            DesignationSpecification::from_text("small: u8").unwrap()
+        }
+
+        #[test]
+        fn overflow_default_err() {
+            let values = HashMap::from([("small", DataValue::UnsignedInteger64(300))]);
+            assert_eq!(
+                u8_spec().pack(&values),
+                ElucidatorError::new_narrowing("u64", "u8")
+            );
+        }
+
+        #[test]
+        fn overflow_saturate_ok() {
+            let values = HashMap::from([("small", DataValue::UnsignedInteger64(300))]);
+            let buffer = u8_spec()
+                .pack_with_policy(&values, PackOverflowPolicy::Saturate)
+                .unwrap();
+            assert_eq!(buffer, vec![255]);
+            let values = HashMap::from([("small", DataValue::SignedInteger32(-4))]);
+            let buffer = u8_spec()
+                .pack_with_policy(&values, PackOverflowPolicy::Saturate)
+                .unwrap();
+            assert_eq!(buffer, vec![0]);
+        }
+
+        #[test]
+        fn overflow_wrap_ok() {
+            let values = HashMap::from([("small", DataValue::UnsignedInteger64(300))]);
+            let buffer = u8_spec()
+                .pack_with_policy(&values, PackOverflowPolicy::Wrap)
+                .unwrap();
+            assert_eq!(buffer, vec![44]);
+        }
+
+        #[test]
+        fn round_trip_ok() {
+            let spec =
+                DesignationSpecification::from_text("a: i16, b: f32[2], c: u64[], d: string")
+                    .unwrap();
+            let values = HashMap::from([
+                ("a", DataValue::SignedInteger16(-3)),
+                ("b", DataValue::Float32Array(vec![0.5, 1.5])),
+                ("c", DataValue::UnsignedInteger64Array(vec![1, 2, 3])),
+                ("d", DataValue::Str("hi".to_string())),
+            ]);
+            let buffer = spec.pack(&values).unwrap();
+            assert_eq!(spec.interpret_enum(&buffer).unwrap(), values);
+        }
+
+        #[test]
+        fn missing_value_err() {
+            let values = HashMap::new();
+            assert_eq!(
+                u8_spec().pack(&values),
+                Err(ElucidatorError::MissingValue {
+                    member: "small".to_string()
                 })
-                .collect();
-            let buffer: Vec<u8> = blob_vec.iter().flat_map(|x| x.iter()).copied().collect();
-            let map = designation.interpret_enum(&buffer);
-            let dr: Result<HashMap<&str, DataValue>> = Ok(datum.clone());
-            pretty_assertions::assert_eq!(
-                map,
-                dr,
-                "{designation:#?}\nBuffer size {}",
-                buffer.len()
+            );
+        }
+
+        #[test]
+        fn wrong_fixed_length_err() {
+            let spec = DesignationSpecification::from_text("b: f32[2]").unwrap();
+            let values = HashMap::from([("b", DataValue::Float32Array(vec![0.5]))]);
+            assert_eq!(
+                spec.pack(&values),
+                ElucidatorError::new_conversion("f32 array", "f32[2]")
+            );
+        }
+
+        #[test]
+        fn float_narrowing_err() {
+            let spec = DesignationSpecification::from_text("x: f32").unwrap();
+            let values = HashMap::from([("x", DataValue::Float64(0.1))]);
+            assert_eq!(
+                spec.pack_with_policy(&values, PackOverflowPolicy::Saturate),
+                ElucidatorError::new_narrowing("f64", "f32")
             );
         }
     }
 
     #[test]
-    fn compare_dv_hm() {
-        let left = HashMap::from([
-            ("foo", DataValue::Byte(9)),
-            ("bar", DataValue::Float32Array(vec![-5.0, -10.0, 3.14])),
-        ]);
-        let right = HashMap::from([
-            ("foo", DataValue::Byte(9)),
-            ("bar", DataValue::Float32Array(vec![-5.0, -10.0, 3.14])),
-        ]);
-        pretty_assertions::assert_eq!(left, right,);
+    fn stray_commas_ok() {
+        let expected = DesignationSpecification::from_text("foo: u32, bar: u8").unwrap();
+        assert_eq!(
+            DesignationSpecification::from_text("foo: u32, bar: u8,"),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            DesignationSpecification::from_text(", foo: u32, bar: u8"),
+            Ok(expected)
+        );
+        assert!(DesignationSpecification::from_text("foo:u32,,bar:u8").is_err());
+        assert!(DesignationSpecification::from_text(",").is_err());
     }
 
     #[test]
-    fn simple_interpret_enum() {
-        let hm = HashMap::from([
-            ("foo", DataValue::Byte(9)),
-            ("bar", DataValue::Float32Array(vec![-5.0, -10.0, 3.14])),
-        ]);
-        let buff_foo = hm.get("foo").unwrap().as_buffer();
-        let buff_bar = hm.get("bar").unwrap().as_buffer();
-        let buffer: Vec<u8> = buff_foo.iter().chain(buff_bar.iter()).copied().collect();
-        let designation = DesignationSpecification::from_text("foo: u8, bar: f32[3]").unwrap();
-        let result = designation.interpret_enum(&buffer);
-        pretty_assertions::assert_eq!(result, Ok(hm),);
+    fn parse_matches_from_text() {
+        let text = "foo: u32, bar: f64[3], baz: string";
+        let parsed = text.parse::<DesignationSpecification>();
+        assert_eq!(parsed, DesignationSpecification::from_text(text));
+        assert!(parsed.is_ok());
+        assert_eq!(
+            "foo u32".parse::<DesignationSpecification>(),
+            DesignationSpecification::from_text("foo u32")
+        );
     }
 
     #[test]
-    fn complex_interpret_enum() {
-        let foo_vec: Vec<i16> = vec![-1, 2, 1025];
-        let bar_vec: Vec<f64> = vec![3.1415, 2.71];
-        let baz_vec: Vec<i8> = vec![-3, 4, -5, 6];
-        let hm = HashMap::from([
-            ("foo", DataValue::SignedInteger16Array(foo_vec.clone())),
-            ("bar", DataValue::Float64Array(bar_vec)),
-            ("baz", DataValue::SignedInteger8Array(baz_vec.clone())),
-        ]);
-        let foo_size_buf = (foo_vec.len() as u64).to_le_bytes();
-        let baz_size_buf = (baz_vec.len() as u64).to_le_bytes();
-        let buffer: Vec<u8> = foo_size_buf
+    fn from_text_with_policy_dotted_ok() {
+        let policy = IdentifierPolicy {
+            extra_chars: vec!['.'],
+        };
+        let spec = DesignationSpecification::from_text_with_policy("gps.lat: f64", &policy);
+        let expected = DesignationSpecification {
+            members: vec![MemberSpecification::from_parts(
+                "gps.lat",
+                &Sizing::Singleton,
+                &Dtype::Float64,
+            )],
+        };
+        pretty_assertions::assert_eq!(spec, Ok(expected));
+        assert!(DesignationSpecification::from_text("gps.lat: f64").is_err());
+    }
+
+    fn invalid_utf8_buffer() -> Vec<u8> {
+        let contents = [b'c', b'a', b'f', 0xE9];
+        (contents.len() as u64)
+            .to_le_bytes()
             .iter()
-            .chain(hm.get("foo").unwrap().as_buffer().iter())
-            .chain(hm.get("bar").unwrap().as_buffer().iter())
-            .chain(baz_size_buf.iter())
-            .chain(hm.get("baz").unwrap().as_buffer().iter())
+            .chain(contents.iter())
             .copied()
-            .collect();
-        let designation =
-            DesignationSpecification::from_text("foo: i16[], bar: f64[2], baz: i8[]").unwrap();
-        let result = designation.interpret_enum(&buffer);
-        pretty_assertions::assert_eq!(result, Ok(hm),);
+            .collect()
+    }
+
+    #[test]
+    fn interpret_enum_strict_invalid_utf8_err() {
+        let designation = DesignationSpecification::from_text("name: string").unwrap();
+        let result =
+            designation.interpret_enum_with_policy(&invalid_utf8_buffer(), StringPolicy::Strict);
+        assert!(matches!(result, Err(ElucidatorError::FromUtf8 { .. })));
+    }
+
+    #[test]
+    fn interpret_enum_lossy_invalid_utf8_ok() {
+        let designation = DesignationSpecification::from_text("name: string").unwrap();
+        let result =
+            designation.interpret_enum_with_policy(&invalid_utf8_buffer(), StringPolicy::Lossy);
+        let expected = HashMap::from([("name", DataValue::Str("caf\u{FFFD}".to_string()))]);
+        pretty_assertions::assert_eq!(result, Ok(expected));
     }
 
     #[test]
@@ -1,4 +1,4 @@
-use crate::token::TokenClone;
+use crate::{member::Dtype, token::TokenClone};
 use std::{collections::HashSet, fmt, string::FromUtf8Error};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -18,6 +18,54 @@ pub enum ElucidatorError {
         column_end: usize,
         reason: String,
     },
+    /// An empty buffer was given for a specification that needs at least some bytes
+    EmptyBuffer { expected_at_least: usize },
+    /// A dynamic array's length prefix asks for more elements than allowed
+    ArrayTooLarge {
+        member: String,
+        requested: usize,
+        limit: usize,
+    },
+    /// An array was requested of a dtype that cannot be an array element
+    UnsupportedArrayElement { dtype: Dtype },
+    /// A fixed-size operation was attempted on a specification with dynamic members
+    NotFixedSize { dynamic_members: Vec<String> },
+    /// A specification with no members was given where at least one is required
+    EmptySpecification,
+    /// A value required by a specification member was not provided
+    MissingValue { member: String },
+    /// Renaming decoded members would give two of them the same name
+    RenameCollision { name: String },
+    /// A NUL-terminated string ran to the end of the buffer without a terminator
+    MissingNulTerminator,
+    /// A string to be written NUL-terminated contains a NUL of its own
+    InteriorNul { member: String },
+    /// A decoded char is a surrogate or beyond the last Unicode scalar value
+    InvalidChar { value: u32 },
+    /// A line of a designation document is not of the form `name = spec`
+    MalformedDocument { line: usize },
+    /// A designation document names the same designation twice
+    DuplicateDesignation { name: String },
+    /// The specification given for a designation in a document is invalid
+    InvalidDesignation {
+        name: String,
+        error: Box<ElucidatorError>,
+    },
+    /// Reading the bytes to decode from a file failed
+    Io { reason: String },
+    /// A JSON Schema uses a construct that has no equivalent specification
+    UnsupportedSchema { reason: String },
+    /// A string is longer than the `string(N)` cap of its member
+    StringTooLong {
+        member: String,
+        max: usize,
+        found: usize,
+    },
+    /// A dynamic member's length prefix asks for far more than the buffer holds, most likely
+    /// because the prefix was never written and element data was read in its place
+    LikelyMissingLengthPrefix { member: String, decoded_count: u64 },
+    /// A value was written to a record that already holds every member
+    RecordComplete { members: usize },
     /// Multiple, simultaneous failures
     MultipleErrors(Box<Vec<ElucidatorError>>),
 }
@@ -35,6 +83,90 @@ impl ElucidatorError {
             to: to.to_string(),
         })
     }
+    /// Type being converted from by a `Conversion` or `Narrowing` error, such as `u16`.
+    /// ```
+    /// use elucidator::Representable;
+    ///
+    /// let error = 5u16.as_u8().unwrap_err();
+    /// assert_eq!(error.from_type(), Some("u16"));
+    /// assert_eq!(error.to_type(), Some("u8"));
+    /// ```
+    pub fn from_type(&self) -> Option<&str> {
+        match self {
+            Self::Conversion { from, .. } | Self::Narrowing { from, .. } => Some(from),
+            _ => None,
+        }
+    }
+    /// Type being converted to by a `Conversion` or `Narrowing` error, such as `u8`.
+    pub fn to_type(&self) -> Option<&str> {
+        match self {
+            Self::Conversion { to, .. } | Self::Narrowing { to, .. } => Some(to),
+            _ => None,
+        }
+    }
+    /// Stable numeric code identifying the kind of error, for tooling and foreign callers that
+    /// should not parse messages. Codes are never reused or renumbered.
+    ///
+    /// | code | variant                     |
+    /// |------|-----------------------------|
+    /// | 1    | `Conversion`                |
+    /// | 2    | `Narrowing`                 |
+    /// | 3    | `BufferSizing`              |
+    /// | 4    | `FromUtf8`                  |
+    /// | 5    | `Specification`             |
+    /// | 6    | `EmptyBuffer`               |
+    /// | 7    | `ArrayTooLarge`             |
+    /// | 8    | `UnsupportedArrayElement`   |
+    /// | 9    | `NotFixedSize`              |
+    /// | 10   | `EmptySpecification`        |
+    /// | 11   | `MissingValue`              |
+    /// | 12   | `RenameCollision`           |
+    /// | 13   | `MissingNulTerminator`      |
+    /// | 14   | `InteriorNul`               |
+    /// | 15   | `InvalidChar`               |
+    /// | 16   | `MalformedDocument`         |
+    /// | 17   | `DuplicateDesignation`      |
+    /// | 18   | `InvalidDesignation`        |
+    /// | 19   | `MultipleErrors`            |
+    /// | 20   | `Io`                        |
+    /// | 21   | `UnsupportedSchema`         |
+    /// | 22   | `StringTooLong`             |
+    /// | 23   | `LikelyMissingLengthPrefix` |
+    /// | 24   | `RecordComplete`            |
+    /// ```
+    /// use elucidator::designation::DesignationSpecification;
+    ///
+    /// let error = DesignationSpecification::from_text("foo: u9").unwrap_err();
+    /// assert_eq!(error.code(), 5);
+    /// ```
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::Conversion { .. } => 1,
+            Self::Narrowing { .. } => 2,
+            Self::BufferSizing { .. } => 3,
+            Self::FromUtf8 { .. } => 4,
+            Self::Specification { .. } => 5,
+            Self::EmptyBuffer { .. } => 6,
+            Self::ArrayTooLarge { .. } => 7,
+            Self::UnsupportedArrayElement { .. } => 8,
+            Self::NotFixedSize { .. } => 9,
+            Self::EmptySpecification => 10,
+            Self::MissingValue { .. } => 11,
+            Self::RenameCollision { .. } => 12,
+            Self::MissingNulTerminator => 13,
+            Self::InteriorNul { .. } => 14,
+            Self::InvalidChar { .. } => 15,
+            Self::MalformedDocument { .. } => 16,
+            Self::DuplicateDesignation { .. } => 17,
+            Self::InvalidDesignation { .. } => 18,
+            Self::MultipleErrors(_) => 19,
+            Self::Io { .. } => 20,
+            Self::UnsupportedSchema { .. } => 21,
+            Self::StringTooLong { .. } => 22,
+            Self::LikelyMissingLengthPrefix { .. } => 23,
+            Self::RecordComplete { .. } => 24,
+        }
+    }
     fn expand(&self) -> Vec<ElucidatorError> {
         match &self {
             Self::MultipleErrors(errs) => errs.iter().flat_map(|e| e.expand()).collect(),
@@ -79,6 +211,75 @@ impl fmt::Display for ElucidatorError {
             } => {
                 format!("Error {reason} between positions {column_start} and {column_end}:\n{context}\n")
             }
+            Self::EmptyBuffer { expected_at_least } => {
+                format!("Buffer is empty, expected at least {expected_at_least} bytes")
+            }
+            Self::ArrayTooLarge {
+                member,
+                requested,
+                limit,
+            } => {
+                format!(
+                    "Member {member} requests {requested} elements, exceeding the limit of {limit}"
+                )
+            }
+            Self::UnsupportedArrayElement { dtype } => {
+                format!("Arrays of {} are not supported", dtype.keyword())
+            }
+            Self::NotFixedSize { dynamic_members } => {
+                format!(
+                    "Specification is not fixed-size because of dynamic members {}; use interpret_enum to validate it",
+                    dynamic_members.join(", ")
+                )
+            }
+            Self::EmptySpecification => "Specification has no members".to_string(),
+            Self::MissingValue { member } => {
+                format!("No value provided for member {member}")
+            }
+            Self::RenameCollision { name } => {
+                format!("Renaming produces more than one member named {name}")
+            }
+            Self::MissingNulTerminator => {
+                "String reaches the end of the buffer without a NUL terminator".to_string()
+            }
+            Self::InteriorNul { member } => {
+                format!("Member {member} contains a NUL and cannot be written NUL-terminated")
+            }
+            Self::InvalidChar { value } => {
+                format!("{value:#x} is not a valid Unicode scalar value")
+            }
+            Self::MalformedDocument { line } => {
+                format!("Line {line} of the document is not of the form name = spec")
+            }
+            Self::DuplicateDesignation { name } => {
+                format!("Designation {name} is defined more than once")
+            }
+            Self::InvalidDesignation { name, error } => {
+                format!("Designation {name} has an invalid specification: {error}")
+            }
+            Self::Io { reason } => {
+                format!("Could not read bytes to decode: {reason}")
+            }
+            Self::UnsupportedSchema { reason } => {
+                format!("Unsupported JSON Schema: {reason}")
+            }
+            Self::StringTooLong { member, max, found } => {
+                format!(
+                    "Member {member} holds a string of {found} bytes, exceeding its cap of {max}"
+                )
+            }
+            Self::LikelyMissingLengthPrefix {
+                member,
+                decoded_count,
+            } => {
+                format!(
+                    "Member {member} has a length prefix of {decoded_count} elements, far more than \
+                     the buffer holds; the buffer is probably missing the u64 length prefix"
+                )
+            }
+            Self::RecordComplete { members } => {
+                format!("Record already holds all {members} members; no more values can be written")
+            }
             Self::MultipleErrors(errs) => errs
                 .iter()
                 .map(|x| format!("{x}"))
@@ -89,6 +290,15 @@ impl fmt::Display for ElucidatorError {
     }
 }
 
+impl std::error::Error for ElucidatorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::FromUtf8 { source } => Some(source),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) enum InternalError {
     /// Errors related to parsing strings, see [`ParsingFailure`] for reasons parsing might fail
@@ -152,6 +362,11 @@ impl fmt::Display for InternalError {
 pub(crate) enum ParsingFailure {
     MissingIdSpecDelimiter,
     UnexpectedEndOfExpression,
+    UnterminatedDoc,
+    TrailingTextAfterDoc,
+    InvalidScaling,
+    InvalidEnum,
+    InvalidMaxLength,
 }
 
 impl fmt::Display for ParsingFailure {
@@ -161,6 +376,17 @@ impl fmt::Display for ParsingFailure {
                 "Missing delimeter : between identifier and type specification".to_string()
             }
             Self::UnexpectedEndOfExpression => "Unexpected end of expression".to_string(),
+            Self::UnterminatedDoc => "Missing closing \" for member documentation".to_string(),
+            Self::TrailingTextAfterDoc => "Unexpected text after member documentation".to_string(),
+            Self::InvalidScaling => {
+                "Fixed-point scaling must be written as qM.N, e.g. q16.16".to_string()
+            }
+            Self::InvalidEnum => {
+                "Enum mappings must be written as enum <type> { name=value, ... }".to_string()
+            }
+            Self::InvalidMaxLength => {
+                "String caps must be written as string(N), e.g. string(64)".to_string()
+            }
         };
         write!(f, "{m}")
     }
@@ -174,6 +400,10 @@ pub(crate) enum SpecificationFailure {
     ZeroLengthIdentifier,
     IllegalArraySizing,
     IllegalCharacters(Vec<char>),
+    ScalingRequiresInteger,
+    EnumRequiresInteger,
+    DuplicateDiscriminant { value: i128 },
+    MaxLengthRequiresString,
 }
 
 impl fmt::Display for SpecificationFailure {
@@ -200,6 +430,18 @@ impl fmt::Display for SpecificationFailure {
                     .join(", ");
                 format!("Illegal characters encountered: {offending_list}")
             }
+            Self::ScalingRequiresInteger => {
+                "Fixed-point scaling can only be applied to integer types".to_string()
+            }
+            Self::EnumRequiresInteger => {
+                "Enum mappings can only be applied to unscaled singleton integer types".to_string()
+            }
+            Self::DuplicateDiscriminant { value } => {
+                format!("Enum discriminant {value} is given to more than one variant")
+            }
+            Self::MaxLengthRequiresString => {
+                "A maximum length can only be applied to string members".to_string()
+            }
             Self::IllegalArraySizing => {
                 "The size of the array is not valid; valid sizes must be unsigned integers or empty"
                     .to_string()
@@ -208,3 +450,76 @@ impl fmt::Display for SpecificationFailure {
         write!(f, "{m}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Representable;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn codes_stable() {
+        assert_eq!(
+            ElucidatorError::new_conversion::<()>("a", "b")
+                .unwrap_err()
+                .code(),
+            1
+        );
+        assert_eq!(
+            ElucidatorError::new_narrowing::<()>("a", "b")
+                .unwrap_err()
+                .code(),
+            2
+        );
+        let sizing = ElucidatorError::BufferSizing {
+            expected: 4,
+            found: 2,
+        };
+        assert_eq!(sizing.code(), 3);
+        assert_eq!(ElucidatorError::EmptySpecification.code(), 10);
+        assert_eq!(ElucidatorError::InvalidChar { value: 0xd800 }.code(), 15);
+    }
+
+    #[test]
+    fn conversion_types_ok() {
+        let error = 5u16.as_u8().unwrap_err();
+        assert_eq!(error.from_type(), Some("u16"));
+        assert_eq!(error.to_type(), Some("u8"));
+        assert_eq!(
+            error.to_string(),
+            "Conversion from u16 to u8 would cause narrowing"
+        );
+        let error = "abc".to_string().as_f64().unwrap_err();
+        assert_eq!(
+            (error.from_type(), error.to_type()),
+            (Some("string"), Some("f64"))
+        );
+        assert_eq!(error.to_string(), "Cannot convert string to f64");
+        assert_eq!(ElucidatorError::EmptySpecification.from_type(), None);
+        assert_eq!(ElucidatorError::EmptySpecification.to_type(), None);
+    }
+
+    #[test]
+    fn codes_distinct() {
+        let errors = [
+            ElucidatorError::new_conversion::<()>("a", "b").unwrap_err(),
+            ElucidatorError::new_narrowing::<()>("a", "b").unwrap_err(),
+            ElucidatorError::EmptyBuffer {
+                expected_at_least: 1,
+            },
+            ElucidatorError::MissingValue {
+                member: "a".to_string(),
+            },
+            ElucidatorError::MissingNulTerminator,
+            ElucidatorError::MalformedDocument { line: 1 },
+            ElucidatorError::StringTooLong {
+                member: "a".to_string(),
+                max: 1,
+                found: 2,
+            },
+            ElucidatorError::MultipleErrors(Box::default()),
+        ];
+        let codes: HashSet<u32> = errors.iter().map(ElucidatorError::code).collect();
+        assert_eq!(codes.len(), errors.len());
+    }
+}
@@ -0,0 +1,325 @@
+//! Conversion between specifications and JSON Schema objects, enabled by the `serde` feature.
+//!
+//! Each member becomes a property of an object schema. Integers carry the `minimum` and
+//! `maximum` of their dtype, floats are `number`s with `format` `float` or `double`, strings
+//! are `string`s whose `maxLength` is the cap of a `string(N)`, and arrays are `array`s of
+//! their element schema whose `minItems` and `maxItems` are equal when the array is fixed-size.
+use crate::{
+    designation::DesignationSpecification,
+    error::ElucidatorError,
+    member::{Dtype, MemberSpecification, Sizing},
+};
+use serde_json::{json, Map, Value};
+
+type Result<T, E = ElucidatorError> = std::result::Result<T, E>;
+
+/// Format marking an integer property as a duration in nanoseconds
+const DURATION_FORMAT: &str = "duration-nanoseconds";
+
+/// Integer dtypes from narrowest to widest, unsigned before signed of the same width
+const INTEGER_DTYPES: [Dtype; 8] = [
+    Dtype::Byte,
+    Dtype::SignedInteger8,
+    Dtype::UnsignedInteger16,
+    Dtype::SignedInteger16,
+    Dtype::UnsignedInteger32,
+    Dtype::SignedInteger32,
+    Dtype::UnsignedInteger64,
+    Dtype::SignedInteger64,
+];
+
+fn unsupported<T>(reason: String) -> Result<T> {
+    Err(ElucidatorError::UnsupportedSchema { reason })
+}
+
+fn integer_bounds(dtype: &Dtype) -> Option<(i128, i128)> {
+    match dtype {
+        Dtype::Byte => Some((u8::MIN as i128, u8::MAX as i128)),
+        Dtype::UnsignedInteger16 => Some((u16::MIN as i128, u16::MAX as i128)),
+        Dtype::UnsignedInteger32 => Some((u32::MIN as i128, u32::MAX as i128)),
+        Dtype::UnsignedInteger64 => Some((u64::MIN as i128, u64::MAX as i128)),
+        Dtype::SignedInteger8 => Some((i8::MIN as i128, i8::MAX as i128)),
+        Dtype::SignedInteger16 => Some((i16::MIN as i128, i16::MAX as i128)),
+        Dtype::SignedInteger32 => Some((i32::MIN as i128, i32::MAX as i128)),
+        Dtype::SignedInteger64 | Dtype::Duration => Some((i64::MIN as i128, i64::MAX as i128)),
+        _ => None,
+    }
+}
+
+fn bound_value(bound: i128) -> Value {
+    match u64::try_from(bound) {
+        Ok(unsigned) => json!(unsigned),
+        Err(_) => json!(bound as i64),
+    }
+}
+
+fn element_schema(dtype: &Dtype) -> Value {
+    match dtype {
+        Dtype::Float32 => json!({"type": "number", "format": "float"}),
+        Dtype::Float64 => json!({"type": "number", "format": "double"}),
+        Dtype::Str => json!({"type": "string"}),
+        Dtype::Char => json!({"type": "string", "minLength": 1, "maxLength": 1}),
+        _ => {
+            let (min, max) = integer_bounds(dtype).expect("remaining dtypes are integers");
+            let mut schema = json!({
+                "type": "integer",
+                "minimum": bound_value(min),
+                "maximum": bound_value(max),
+            });
+            if *dtype == Dtype::Duration {
+                schema["format"] = json!(DURATION_FORMAT);
+            }
+            schema
+        }
+    }
+}
+
+fn member_schema(member: &MemberSpecification) -> Value {
+    let element = element_schema(member.dtype());
+    let mut schema = match member.sizing() {
+        Sizing::Singleton => element,
+        Sizing::Fixed(n) => {
+            json!({"type": "array", "items": element, "minItems": n, "maxItems": n})
+        }
+        Sizing::Dynamic => json!({"type": "array", "items": element}),
+    };
+    if let Some(max_len) = member.max_len() {
+        schema["maxLength"] = json!(max_len);
+    }
+    if let Some(doc) = member.doc() {
+        schema["description"] = json!(doc);
+    }
+    schema
+}
+
+/// An integer bound of `schema`, which must be a whole number if present
+fn integer_bound(schema: &Map<String, Value>, key: &str) -> Result<Option<i128>> {
+    match schema.get(key) {
+        None => Ok(None),
+        Some(value) => match (value.as_i64(), value.as_u64()) {
+            (Some(i), _) => Ok(Some(i as i128)),
+            (_, Some(u)) => Ok(Some(u as i128)),
+            _ => unsupported(format!("{key} {value} is not a whole number")),
+        },
+    }
+}
+
+/// Narrowest integer dtype holding every value between the schema's bounds. A missing
+/// `minimum` allows negative values; a missing `maximum` allows any value of 64 bits.
+fn integer_dtype(schema: &Map<String, Value>) -> Result<Dtype> {
+    if schema.get("format").and_then(Value::as_str) == Some(DURATION_FORMAT) {
+        return Ok(Dtype::Duration);
+    }
+    let min = integer_bound(schema, "minimum")?.unwrap_or(i64::MIN as i128);
+    let max = integer_bound(schema, "maximum")?.unwrap_or(if min >= 0 {
+        u64::MAX as i128
+    } else {
+        i64::MAX as i128
+    });
+    INTEGER_DTYPES
+        .into_iter()
+        .find(|dtype| {
+            let (lo, hi) = integer_bounds(dtype).unwrap();
+            lo <= min && max <= hi
+        })
+        .map_or_else(
+            || unsupported(format!("no integer dtype holds {min} through {max}")),
+            Ok,
+        )
+}
+
+fn element_dtype(name: &str, schema: &Map<String, Value>) -> Result<Dtype> {
+    for keyword in ["$ref", "oneOf", "anyOf", "allOf", "not", "enum", "const"] {
+        if schema.contains_key(keyword) {
+            return unsupported(format!("property {name} uses {keyword}"));
+        }
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("integer") => integer_dtype(schema),
+        Some("number") => match schema.get("format").and_then(Value::as_str) {
+            Some("float") => Ok(Dtype::Float32),
+            _ => Ok(Dtype::Float64),
+        },
+        Some("string") => {
+            let one_char = |key| schema.get(key).and_then(Value::as_u64) == Some(1);
+            if one_char("minLength") && one_char("maxLength") {
+                Ok(Dtype::Char)
+            } else {
+                Ok(Dtype::Str)
+            }
+        }
+        Some(other) => unsupported(format!("property {name} has type {other}")),
+        None => unsupported(format!("property {name} has no single type")),
+    }
+}
+
+fn member_from_schema(name: &str, schema: &Value) -> Result<MemberSpecification> {
+    let Some(schema) = schema.as_object() else {
+        return unsupported(format!("property {name} is not a schema object"));
+    };
+    let (sizing, dtype) = if schema.get("type").and_then(Value::as_str) == Some("array") {
+        let Some(items) = schema.get("items").and_then(Value::as_object) else {
+            return unsupported(format!("array property {name} has no items schema"));
+        };
+        let dtype = element_dtype(name, items)?;
+        if matches!(dtype, Dtype::Str | Dtype::Char) {
+            return unsupported(format!("array property {name} has string items"));
+        }
+        let min_items = schema.get("minItems").and_then(Value::as_u64);
+        let max_items = schema.get("maxItems").and_then(Value::as_u64);
+        match (min_items, max_items) {
+            (Some(min), Some(max)) if min == max => (Sizing::Fixed(max), dtype),
+            _ => (Sizing::Dynamic, dtype),
+        }
+    } else {
+        (Sizing::Singleton, element_dtype(name, schema)?)
+    };
+    let member = MemberSpecification::from_parts(name, &sizing, &dtype);
+    let member = match schema.get("maxLength").and_then(Value::as_u64) {
+        Some(max_len) if dtype == Dtype::Str => member.with_max_len(max_len as usize),
+        _ => member,
+    };
+    Ok(match schema.get("description").and_then(Value::as_str) {
+        Some(doc) => member.with_doc(doc),
+        None => member,
+    })
+}
+
+impl DesignationSpecification {
+    /// Describe this specification as a JSON Schema object with one property per member.
+    /// Scaling and enum mappings are not represented; properties describe the stored values.
+    /// ```
+    /// use elucidator::designation::DesignationSpecification;
+    ///
+    /// let spec = DesignationSpecification::from_text("id: u16, xs: f32[3]").unwrap();
+    /// let schema = spec.to_json_schema();
+    /// assert_eq!(schema["properties"]["id"]["maximum"], 65535);
+    /// assert_eq!(schema["properties"]["xs"]["maxItems"], 3);
+    /// ```
+    pub fn to_json_schema(&self) -> Value {
+        let properties: Map<String, Value> = self
+            .members()
+            .iter()
+            .map(|member| (member.identifier().to_string(), member_schema(member)))
+            .collect();
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": self.member_names().collect::<Vec<&str>>(),
+            "additionalProperties": false,
+        })
+    }
+
+    /// Build a specification from the properties of a JSON Schema object.
+    /// Integers get the narrowest dtype holding their `minimum` through `maximum`, numbers are
+    /// `f64` unless their `format` is `float`, and arrays are fixed-size only when `minItems`
+    /// equals `maxItems`. Members follow the order of `required`, then any remaining
+    /// properties in name order. References, combinators, and nested objects are errors.
+    pub fn from_json_schema(schema: &Value) -> Result<Self> {
+        let Some(object) = schema.as_object() else {
+            return unsupported("schema is not an object".to_string());
+        };
+        if object.get("type").is_some_and(|t| t != "object") {
+            return unsupported("top-level type is not object".to_string());
+        }
+        let Some(properties) = object.get("properties").and_then(Value::as_object) else {
+            return unsupported("schema has no properties".to_string());
+        };
+        let mut names: Vec<&str> = object
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| required.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        names.retain(|name| properties.contains_key(*name));
+        for name in properties.keys() {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        let members = names
+            .iter()
+            .map(|name| Ok(member_from_schema(name, &properties[*name])?.to_string()))
+            .collect::<Result<Vec<String>>>()?;
+        DesignationSpecification::from_text(&members.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn round_trip_equivalent() {
+        let spec = DesignationSpecification::from_text(
+            "id: u32, t: i64, dt: duration, c: char, name: string, temp: f32, \
+             xs: f64[4], counts: u16[], level: i8 \"from sensor\", label: string(8)",
+        )
+        .unwrap();
+        let recovered = DesignationSpecification::from_json_schema(&spec.to_json_schema());
+        let recovered = recovered.unwrap();
+        assert!(spec.equivalent_unordered(&recovered));
+        assert_eq!(
+            recovered.member("level").unwrap().doc(),
+            Some("from sensor")
+        );
+        assert_eq!(recovered.member("label").unwrap().max_len(), Some(8));
+        assert_eq!(recovered.member("name").unwrap().max_len(), None);
+        assert_eq!(recovered.member_names().next(), Some("id"));
+    }
+
+    #[test]
+    fn narrowest_integer_ok() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "small": {"type": "integer", "minimum": 0, "maximum": 200},
+                "negative": {"type": "integer", "minimum": -5, "maximum": 5},
+                "wide": {"type": "integer", "minimum": 0, "maximum": 70000},
+                "unbounded": {"type": "integer"},
+                "ratio": {"type": "number"},
+                "tags": {"type": "array", "items": {"type": "integer", "minimum": 0, "maximum": 9}},
+            },
+        });
+        let spec = DesignationSpecification::from_json_schema(&schema).unwrap();
+        let dtype = |name| spec.dtype_of(name).unwrap();
+        assert_eq!(dtype("small"), (Dtype::Byte, Sizing::Singleton));
+        assert_eq!(
+            dtype("negative"),
+            (Dtype::SignedInteger8, Sizing::Singleton)
+        );
+        assert_eq!(dtype("wide"), (Dtype::UnsignedInteger32, Sizing::Singleton));
+        assert_eq!(
+            dtype("unbounded"),
+            (Dtype::SignedInteger64, Sizing::Singleton)
+        );
+        assert_eq!(dtype("ratio"), (Dtype::Float64, Sizing::Singleton));
+        assert_eq!(dtype("tags"), (Dtype::Byte, Sizing::Dynamic));
+    }
+
+    #[test]
+    fn unsupported_constructs_err() {
+        let with_property = |property: Value| json!({"properties": {"p": property}});
+        for schema in [
+            json!([1, 2]),
+            json!({"type": "array"}),
+            json!({"type": "object"}),
+            with_property(json!({"type": "object"})),
+            with_property(json!({"type": ["integer", "null"]})),
+            with_property(json!({"$ref": "#/definitions/p"})),
+            with_property(json!({"anyOf": [{"type": "integer"}]})),
+            with_property(json!({"type": "integer", "minimum": 0.5})),
+            with_property(json!({"type": "integer", "minimum": -1, "maximum": u64::MAX})),
+            with_property(json!({"type": "array", "items": {"type": "string"}})),
+            with_property(json!({"type": "array"})),
+        ] {
+            assert!(
+                matches!(
+                    DesignationSpecification::from_json_schema(&schema),
+                    Err(ElucidatorError::UnsupportedSchema { .. })
+                ),
+                "{schema}"
+            );
+        }
+    }
+}
@@ -1,14 +1,24 @@
 //! Main elucidator library.
 use crate::error::*;
+pub use designation::{diff_buffers, interpret_text};
 pub use representable::Representable;
 
 pub mod designation;
 pub mod error;
+#[cfg(feature = "serde")]
+mod json_schema;
 pub mod member;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 mod parsing;
 pub mod representable;
 mod test_utils;
+pub mod testing;
 mod token;
 mod util;
 mod validating;
 pub mod value;
+#[cfg(feature = "zerocopy")]
+pub mod zerocopy;
@@ -1,6 +1,11 @@
 mod member_specification;
 pub use member_specification::MemberSpecification;
 mod dtype;
+pub(crate) use dtype::char_from_le;
 pub use dtype::Dtype;
 mod sizing;
 pub use sizing::Sizing;
+mod scaling;
+pub use scaling::Scaling;
+mod enum_mapping;
+pub use enum_mapping::EnumMapping;
@@ -2,7 +2,7 @@ use crate::error::*;
 use crate::Representable;
 
 /// Possible Data Types allowed in The Elucidation Metadata Standard, most composable as arrays.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 #[non_exhaustive]
 pub enum Dtype {
     Byte,
@@ -16,6 +16,10 @@ pub enum Dtype {
     Float32,
     Float64,
     Str,
+    /// Signed duration in nanoseconds, stored exactly like `i64`
+    Duration,
+    /// A single Unicode scalar value, stored as a little-endian `u32`
+    Char,
 }
 
 fn buff_size_or_err<T>(buffer: &[u8]) -> Result<usize, ElucidatorError> {
@@ -29,7 +33,51 @@ fn buff_size_or_err<T>(buffer: &[u8]) -> Result<usize, ElucidatorError> {
     Ok(expected_buff_size)
 }
 
+/// Read a char from exactly four little-endian bytes, rejecting surrogates and values past
+/// `char::MAX`.
+pub(crate) fn char_from_le(buffer: &[u8]) -> Result<char, ElucidatorError> {
+    let value =
+        u32::from_le_bytes(
+            buffer
+                .try_into()
+                .map_err(|_| ElucidatorError::BufferSizing {
+                    expected: 4,
+                    found: buffer.len(),
+                })?,
+        );
+    char::from_u32(value).ok_or(ElucidatorError::InvalidChar { value })
+}
+
 impl Dtype {
+    /// Keyword naming this dtype in a specification, the inverse of parsing a dtype
+    /// ```
+    /// use elucidator::member::Dtype;
+    ///
+    /// assert_eq!(Dtype::Float32.keyword(), "f32");
+    /// ```
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Self::Byte => "u8",
+            Self::UnsignedInteger16 => "u16",
+            Self::UnsignedInteger32 => "u32",
+            Self::UnsignedInteger64 => "u64",
+            Self::SignedInteger8 => "i8",
+            Self::SignedInteger16 => "i16",
+            Self::SignedInteger32 => "i32",
+            Self::SignedInteger64 => "i64",
+            Self::Float32 => "f32",
+            Self::Float64 => "f64",
+            Self::Str => "string",
+            Self::Duration => "duration",
+            Self::Char => "char",
+        }
+    }
+
+    /// Name of an array of this dtype, as used in conversion errors
+    pub(crate) fn array_keyword(&self) -> String {
+        format!("{} array", self.keyword())
+    }
+
     pub fn get_size(&self) -> Option<usize> {
         match &self {
             Self::Byte => Some(std::mem::size_of::<u8>()),
@@ -43,6 +91,8 @@ impl Dtype {
             Self::Float32 => Some(std::mem::size_of::<f32>()),
             Self::Float64 => Some(std::mem::size_of::<f64>()),
             Self::Str => None,
+            Self::Duration => Some(std::mem::size_of::<i64>()),
+            Self::Char => Some(std::mem::size_of::<u32>()),
         }
     }
 
@@ -132,7 +182,7 @@ impl Dtype {
                         .unwrap(),
                 )))
             }
-            Self::SignedInteger64 => {
+            Self::SignedInteger64 | Self::Duration => {
                 let buffer_len = buff_size_or_err::<i64>(buffer)?;
                 Ok(Box::new(i64::from_le_bytes(
                     buffer
@@ -168,6 +218,10 @@ impl Dtype {
                         .unwrap(),
                 )))
             }
+            Self::Char => {
+                buff_size_or_err::<u32>(buffer)?;
+                Ok(Box::new(char_from_le(buffer)?))
+            }
             Self::Str => {
                 let buffer_len = buffer.len();
                 if buffer_len < 8 {
@@ -0,0 +1,118 @@
+/// Names for the values of a singleton integer member, written `enum u8 { idle=0, active=1 }`.
+/// ```
+/// use elucidator::member::EnumMapping;
+///
+/// let mapping = EnumMapping::new(&[("idle", 0), ("active", 1)]);
+/// assert_eq!(mapping.name_of(1), Some("active"));
+/// assert_eq!(mapping.name_of(7), None);
+/// assert_eq!(mapping.to_string(), "{ idle=0, active=1 }");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EnumMapping {
+    variants: Vec<(String, i128)>,
+}
+
+impl EnumMapping {
+    pub fn new(variants: &[(&str, i128)]) -> Self {
+        EnumMapping {
+            variants: variants
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect(),
+        }
+    }
+
+    /// Variant names and their discriminants, in the order they were written
+    pub fn variants(&self) -> &[(String, i128)] {
+        &self.variants
+    }
+
+    /// Name of the first variant with discriminant `value`
+    pub fn name_of(&self, value: i128) -> Option<&str> {
+        self.variants
+            .iter()
+            .find(|(_, v)| *v == value)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// A discriminant given to more than one variant, if any
+    pub(crate) fn duplicate_discriminant(&self) -> Option<i128> {
+        self.variants
+            .iter()
+            .enumerate()
+            .find(|(i, (_, value))| self.variants[..*i].iter().any(|(_, v)| v == value))
+            .map(|(_, (_, value))| *value)
+    }
+
+    /// Parse the text between the braces, such as `idle=0, active=1`
+    pub(crate) fn from_text(text: &str) -> Option<Self> {
+        let mut entries: Vec<&str> = text.split(',').collect();
+        // Allow a trailing comma after the last variant
+        if entries.len() > 1 && entries.last().is_some_and(|e| e.trim().is_empty()) {
+            entries.pop();
+        }
+        let mut variants = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let (name, value) = entry.split_once('=')?;
+            let name = name.trim();
+            let starts_alphabetic = name.chars().next().is_some_and(char::is_alphabetic);
+            if !starts_alphabetic || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return None;
+            }
+            variants.push((name.to_string(), value.trim().parse().ok()?));
+        }
+        Some(EnumMapping { variants })
+    }
+}
+
+impl std::fmt::Display for EnumMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let variants = self
+            .variants
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(f, "{{ {variants} }}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_text_ok() {
+        assert_eq!(
+            EnumMapping::from_text(" idle=0, active = 1, error=-2, "),
+            Some(EnumMapping::new(&[
+                ("idle", 0),
+                ("active", 1),
+                ("error", -2)
+            ]))
+        );
+    }
+
+    #[test]
+    fn from_text_err() {
+        for text in [
+            "",
+            "idle",
+            "idle=",
+            "=0",
+            "0idle=0",
+            "idle=zero",
+            "a=0,,b=1",
+        ] {
+            assert_eq!(EnumMapping::from_text(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn duplicate_discriminant_found() {
+        let mapping = EnumMapping::new(&[("a", 0), ("b", 1), ("c", 1)]);
+        assert_eq!(mapping.duplicate_discriminant(), Some(1));
+        let mapping = EnumMapping::new(&[("a", 0), ("b", 1)]);
+        assert_eq!(mapping.duplicate_discriminant(), None);
+    }
+}
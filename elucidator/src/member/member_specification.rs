@@ -1,13 +1,38 @@
-use crate::member::{dtype::Dtype, sizing::Sizing};
+use crate::designation::convert_error;
+use crate::error::ElucidatorError;
+use crate::member::{dtype::Dtype, enum_mapping::EnumMapping, scaling::Scaling, sizing::Sizing};
+use crate::validating::validate_member_parts;
+use crate::value::DataValue;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct MemberSpecification {
     pub(crate) identifier: String,
     pub(crate) sizing: Sizing,
     pub(crate) dtype: Dtype,
+    pub(crate) doc: Option<String>,
+    pub(crate) scaling: Option<Scaling>,
+    pub(crate) enum_mapping: Option<EnumMapping>,
+    pub(crate) max_len: Option<usize>,
 }
 
 impl MemberSpecification {
+    /// Build a member from its parts, checking them as `DesignationSpecification::from_text`
+    /// would: the identifier must be legal, and strings and chars must be singletons.
+    /// ```
+    /// use elucidator::member::{Dtype, MemberSpecification, Sizing};
+    ///
+    /// assert!(MemberSpecification::new("temps", &Sizing::Fixed(3), &Dtype::Float32).is_ok());
+    /// assert!(MemberSpecification::new("3temps", &Sizing::Singleton, &Dtype::Float32).is_err());
+    /// assert!(MemberSpecification::new("names", &Sizing::Dynamic, &Dtype::Str).is_err());
+    /// ```
+    pub fn new(identifier: &str, sizing: &Sizing, dtype: &Dtype) -> Result<Self, ElucidatorError> {
+        validate_member_parts(identifier, sizing, dtype).map_err(|e| {
+            let text = format!("{identifier}: {}{}", dtype.keyword(), sizing.suffix());
+            convert_error(&e, &text)
+        })
+    }
+    /// Build a member from its parts without validating the identifier. Prefer `new` outside
+    /// of tests and other code that already knows its parts are legal.
     pub fn from_parts(identifier: &str, sizing: &Sizing, dtype: &Dtype) -> Self {
         if *dtype == Dtype::Str && *sizing != Sizing::Singleton {
             panic!("Dtype is string, but sizing is non-singleton for passed values {identifier:#?}, {sizing:#?}, {dtype:#?}. TODO: make this panic an error.");
@@ -16,33 +41,142 @@ impl MemberSpecification {
             identifier: identifier.to_string(),
             sizing: sizing.clone(),
             dtype: dtype.clone(),
+            doc: None,
+            scaling: None,
+            enum_mapping: None,
+            max_len: None,
         }
     }
+    /// Attach documentation to this member. Documentation may not contain `"`.
+    pub fn with_doc(mut self, doc: &str) -> Self {
+        self.doc = Some(doc.to_string());
+        self
+    }
+    /// Read this integer member as fixed-point with the given scaling.
+    pub fn with_scaling(mut self, scaling: Scaling) -> Self {
+        self.scaling = Some(scaling);
+        self
+    }
+    /// Name the values of this singleton integer member.
+    pub fn with_enum_mapping(mut self, mapping: EnumMapping) -> Self {
+        self.enum_mapping = Some(mapping);
+        self
+    }
+    /// Cap this string member at `max_len` bytes, written `string(N)`.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+    pub fn sizing(&self) -> &Sizing {
+        &self.sizing
+    }
+    pub fn dtype(&self) -> &Dtype {
+        &self.dtype
+    }
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+    pub fn scaling(&self) -> Option<&Scaling> {
+        self.scaling.as_ref()
+    }
+    pub fn enum_mapping(&self) -> Option<&EnumMapping> {
+        self.enum_mapping.as_ref()
+    }
+    pub fn max_len(&self) -> Option<usize> {
+        self.max_len
+    }
+    /// Apply this member's fixed-point scaling, if any, to a freshly decoded raw value.
+    pub(crate) fn apply_scaling(&self, value: DataValue) -> DataValue {
+        match &self.scaling {
+            Some(scaling) => value.scaled(scaling),
+            None => value,
+        }
+    }
+    /// Type as written in a specification, e.g. `u8[4]` or `string(64)`
+    pub(crate) fn type_string(&self) -> String {
+        let max_len_string = match self.max_len {
+            Some(n) => format!("({n})"),
+            None => String::new(),
+        };
+        let sizing_string = self.sizing.suffix();
+        format!("{}{max_len_string}{sizing_string}", self.dtype.keyword())
+    }
 }
 
 impl std::fmt::Display for MemberSpecification {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let sizing_string = match self.sizing {
-            Sizing::Singleton => String::new(),
-            Sizing::Dynamic => "[]".to_string(),
-            Sizing::Fixed(n) => {
-                format!("[{n}]")
-            }
+        let doc_string = match &self.doc {
+            Some(doc) => format!(" \"{doc}\""),
+            None => String::new(),
         };
-        let dtype_string = match self.dtype {
-            Dtype::Byte => "u8".to_string(),
-            Dtype::UnsignedInteger16 => "u16".to_string(),
-            Dtype::UnsignedInteger32 => "u32".to_string(),
-            Dtype::UnsignedInteger64 => "u64".to_string(),
-            Dtype::SignedInteger8 => "i8".to_string(),
-            Dtype::SignedInteger16 => "i16".to_string(),
-            Dtype::SignedInteger32 => "i32".to_string(),
-            Dtype::SignedInteger64 => "i64".to_string(),
-            Dtype::Float32 => "f32".to_string(),
-            Dtype::Float64 => "f64".to_string(),
-            Dtype::Str => "string".to_string(),
+        let scaling_string = match &self.scaling {
+            Some(scaling) => format!(" @ {scaling}"),
+            None => String::new(),
+        };
+        let m = match &self.enum_mapping {
+            Some(mapping) => format!(
+                "{}: enum {} {mapping}{scaling_string}{doc_string}",
+                self.identifier,
+                self.type_string()
+            ),
+            None => format!(
+                "{}: {}{scaling_string}{doc_string}",
+                self.identifier,
+                self.type_string()
+            ),
         };
-        let m = format!("{}: {dtype_string}{sizing_string}", self.identifier);
         write!(f, "{m}")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn new_ok() {
+        let member = MemberSpecification::new("temps", &Sizing::Fixed(3), &Dtype::Float32);
+        assert_eq!(
+            member,
+            Ok(MemberSpecification::from_parts(
+                "temps",
+                &Sizing::Fixed(3),
+                &Dtype::Float32
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_identifier_err() {
+        for identifier in ["", "3temps", "temp c", "temp:c"] {
+            let member = MemberSpecification::new(identifier, &Sizing::Singleton, &Dtype::Byte);
+            assert!(
+                matches!(member, Err(ElucidatorError::Specification { .. })),
+                "{identifier:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn string_array_err() {
+        for dtype in [Dtype::Str, Dtype::Char] {
+            for sizing in [Sizing::Fixed(2), Sizing::Dynamic] {
+                let Err(ElucidatorError::Specification {
+                    column_start,
+                    column_end,
+                    ..
+                }) = MemberSpecification::new("names", &sizing, &dtype)
+                else {
+                    panic!("{dtype:?} {sizing:?} should be rejected");
+                };
+                let text = format!("names: {}{}", dtype.keyword(), sizing.suffix());
+                assert_eq!(column_start, text.find('[').unwrap());
+                assert_eq!(column_end, text.len());
+            }
+        }
+    }
+}
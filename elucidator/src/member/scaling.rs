@@ -0,0 +1,76 @@
+/// Fixed-point interpretation of an integer member, written `@ qM.N` after its type.
+/// Raw integers are scaled by 2^-N on read, so `x: i32 @ q16.16` stores Q16.16 values.
+/// ```
+/// use elucidator::member::Scaling;
+///
+/// let q = Scaling { int_bits: 16, frac_bits: 16 };
+/// assert_eq!(q.factor(), 1.0 / 65536.0);
+/// assert_eq!(q.to_string(), "q16.16");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Scaling {
+    pub int_bits: u8,
+    pub frac_bits: u8,
+}
+
+impl Scaling {
+    /// Largest number of fractional bits accepted, the width of the widest integer dtype
+    pub(crate) const MAX_FRAC_BITS: u8 = 64;
+
+    /// Multiplier converting a raw integer to its fixed-point value
+    pub fn factor(&self) -> f64 {
+        (-(self.frac_bits as f64)).exp2()
+    }
+
+    /// Parse the text following `@`, such as `q16.16`
+    pub(crate) fn from_text(text: &str) -> Option<Self> {
+        let (int_bits, frac_bits) = text.strip_prefix(['q', 'Q'])?.split_once('.')?;
+        let all_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        if !all_digits(int_bits) || !all_digits(frac_bits) {
+            return None;
+        }
+        let scaling = Scaling {
+            int_bits: int_bits.parse().ok()?,
+            frac_bits: frac_bits.parse().ok()?,
+        };
+        (scaling.frac_bits <= Self::MAX_FRAC_BITS).then_some(scaling)
+    }
+}
+
+impl std::fmt::Display for Scaling {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "q{}.{}", self.int_bits, self.frac_bits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_text_ok() {
+        assert_eq!(
+            Scaling::from_text("q16.16"),
+            Some(Scaling {
+                int_bits: 16,
+                frac_bits: 16
+            })
+        );
+        assert_eq!(
+            Scaling::from_text("Q1.15"),
+            Some(Scaling {
+                int_bits: 1,
+                frac_bits: 15
+            })
+        );
+    }
+
+    #[test]
+    fn from_text_malformed_none() {
+        for text in [
+            "", "q", "16.16", "q16", "q16.", "q.16", "q+1.2", "q1.65", "q300.1",
+        ] {
+            assert_eq!(Scaling::from_text(text), None, "{text}");
+        }
+    }
+}
@@ -18,3 +18,14 @@ pub enum Sizing {
     Fixed(u64),
     Dynamic,
 }
+
+impl Sizing {
+    /// Sizing as written after a dtype: `[4]`, `[]`, or nothing for a singleton
+    pub(crate) fn suffix(&self) -> String {
+        match self {
+            Sizing::Singleton => String::new(),
+            Sizing::Dynamic => "[]".to_string(),
+            Sizing::Fixed(n) => format!("[{n}]"),
+        }
+    }
+}
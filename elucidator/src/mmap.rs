@@ -0,0 +1,60 @@
+//! Decoding records straight out of memory-mapped files, enabled by the `mmap` feature.
+use crate::{designation::DesignationSpecification, error::ElucidatorError, value::DataValue};
+use memmap2::MmapOptions;
+use std::{collections::HashMap, fs::File, path::Path};
+
+type Result<T, E = ElucidatorError> = std::result::Result<T, E>;
+
+fn io_error(error: std::io::Error) -> ElucidatorError {
+    ElucidatorError::Io {
+        reason: error.to_string(),
+    }
+}
+
+/// Decode the record of `spec` starting `offset` bytes into the file at `path`.
+/// The file is mapped rather than read, so only the pages holding the record are loaded; the
+/// decode itself borrows the mapping exactly as [`DesignationSpecification::interpret_enum_from`]
+/// borrows any other slice.
+pub fn interpret_mmap<'a>(
+    path: &Path,
+    offset: u64,
+    spec: &'a DesignationSpecification,
+) -> Result<HashMap<&'a str, DataValue>> {
+    let file = File::open(path).map_err(io_error)?;
+    // SAFETY: the mapping is only read while it is alive. If another process truncates the
+    // file meanwhile, reads may fault; this is the usual caveat of mapping files.
+    let map = unsafe { MmapOptions::new().offset(offset).map(&file) }.map_err(io_error)?;
+    let (record, _) = spec.interpret_enum_from(&map)?;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    #[test]
+    fn interpret_mmap_ok() {
+        let spec = DesignationSpecification::from_text("id: u32, temp: f64, name: string").unwrap();
+        let values = HashMap::from([
+            ("id", DataValue::UnsignedInteger32(7)),
+            ("temp", DataValue::Float64(21.5)),
+            ("name", DataValue::Str("probe".to_string())),
+        ]);
+        let record = spec.pack(&values).unwrap();
+
+        let path = std::env::temp_dir().join(format!("elucidator-mmap-{}", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        // Put the record after a header so that the offset is not page aligned
+        file.write_all(&[0xff; 3]).unwrap();
+        file.write_all(&record).unwrap();
+        drop(file);
+
+        let decoded = interpret_mmap(&path, 3, &spec);
+        let missing = interpret_mmap(&path.with_extension("missing"), 0, &spec);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(decoded, Ok(values));
+        assert!(matches!(missing, Err(ElucidatorError::Io { .. })));
+    }
+}
@@ -0,0 +1,89 @@
+//! Decoding many records across threads, enabled by the `parallel` feature.
+use crate::{designation::DesignationSpecification, error::ElucidatorError, value::DataValue};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+type Result<T, E = ElucidatorError> = std::result::Result<T, E>;
+
+/// Decode each buffer with the specification paired with it, spreading the work over the rayon
+/// thread pool. Results come back in the same order as `specs_and_buffers`, and a buffer that
+/// fails to decode does not stop the others. Member names are owned so that the records can
+/// outlive the specifications they were decoded with.
+/// ```
+/// use elucidator::{designation::DesignationSpecification, parallel::interpret_many};
+///
+/// let spec = DesignationSpecification::from_text("a: u16").unwrap();
+/// let records = interpret_many(&[(&spec, &[1, 0][..]), (&spec, &[2][..])]);
+/// assert_eq!(records[0].as_ref().unwrap()["a"].to_string(), "1");
+/// assert!(records[1].is_err());
+/// ```
+pub fn interpret_many(
+    specs_and_buffers: &[(&DesignationSpecification, &[u8])],
+) -> Vec<Result<HashMap<String, DataValue>>> {
+    specs_and_buffers
+        .par_iter()
+        .map(|(spec, buffer)| {
+            Ok(spec
+                .interpret_enum(buffer)?
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn matches_serial_decode() {
+        let specs = [
+            DesignationSpecification::from_text("id: u32, xs: f64[], name: string").unwrap(),
+            DesignationSpecification::from_text("flag: u8, ys: i16[2]").unwrap(),
+        ];
+        let buffers: Vec<Vec<u8>> = (0..1000)
+            .map(|i| match i % 2 {
+                0 => specs[0]
+                    .pack(&HashMap::from([
+                        ("id", DataValue::UnsignedInteger32(i)),
+                        (
+                            "xs",
+                            DataValue::Float64Array(vec![i as f64; i as usize % 5]),
+                        ),
+                        ("name", DataValue::Str(format!("record {i}"))),
+                    ]))
+                    .unwrap(),
+                _ => specs[1]
+                    .pack(&HashMap::from([
+                        ("flag", DataValue::Byte((i % 3) as u8)),
+                        ("ys", DataValue::SignedInteger16Array(vec![i as i16, -1])),
+                    ]))
+                    .unwrap(),
+            })
+            .collect();
+        let mut pairs: Vec<(&DesignationSpecification, &[u8])> = buffers
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| (&specs[i % 2], buffer.as_slice()))
+            .collect();
+        // A truncated buffer fails on its own without disturbing its neighbours
+        pairs.push((&specs[0], &buffers[0][..3]));
+
+        let serial: Vec<Result<HashMap<String, DataValue>>> = pairs
+            .iter()
+            .map(|(spec, buffer)| {
+                spec.interpret_enum(buffer).map(|record| {
+                    record
+                        .into_iter()
+                        .map(|(name, value)| (name.to_string(), value))
+                        .collect()
+                })
+            })
+            .collect();
+        let parallel = interpret_many(&pairs);
+        assert!(parallel.last().unwrap().is_err());
+        assert_eq!(parallel, serial);
+    }
+}
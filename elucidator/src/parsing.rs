@@ -1,4 +1,8 @@
-use crate::{error::*, token::*};
+use crate::{
+    error::*,
+    member::{EnumMapping, Scaling},
+    token::*,
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) struct WordParserOutput<'a> {
@@ -34,6 +38,15 @@ pub(crate) struct TypeSpecParserOutput<'a> {
 pub(crate) struct MemberSpecParserOutput<'a> {
     pub identifier: Option<IdentifierToken<'a>>,
     pub typespec: Option<TypeSpecParserOutput<'a>>,
+    /// Contents of the optional quoted documentation following the type specification
+    pub doc: Option<&'a str>,
+    /// Optional fixed-point annotation following `@`, with the token it was parsed from
+    pub scaling: Option<(Scaling, TokenClone)>,
+    /// Optional variant names written as `enum <type> { name=value, ... }`, with the token they
+    /// were parsed from
+    pub enum_mapping: Option<(EnumMapping, TokenClone)>,
+    /// Optional maximum length written as `string(N)`, with the token it was parsed from
+    pub max_len: Option<(usize, TokenClone)>,
     pub errors: Vec<InternalError>,
 }
 #[derive(Debug, PartialEq, Clone)]
@@ -62,21 +75,21 @@ impl<'a> MemberSpecParserOutput<'a> {
     }
 }
 
-pub fn get_identifier(data: &str, start_col: usize) -> IdentifierParserOutput {
+pub fn get_identifier(data: &str, start_col: usize) -> IdentifierParserOutput<'_> {
     let word_output = get_word(data, start_col);
     let identifier = word_output.word.map(|word| IdentifierToken { data: word });
     let errors = word_output.errors;
     IdentifierParserOutput { identifier, errors }
 }
 
-pub fn get_dtype(data: &str, start_col: usize) -> DtypeParserOutput {
+pub fn get_dtype(data: &str, start_col: usize) -> DtypeParserOutput<'_> {
     let word_output = get_word(data, start_col);
     let dtype = word_output.word.map(|word| DtypeToken { data: word });
     let errors = word_output.errors;
     DtypeParserOutput { dtype, errors }
 }
 
-pub fn get_sizing(data: &str, start_col: usize) -> SizingParserOutput {
+pub fn get_sizing(data: &str, start_col: usize) -> SizingParserOutput<'_> {
     if data.chars().all(|x| x.is_whitespace()) {
         let data_len = data.chars().count();
         let last_slice = if data_len == 0 {
@@ -107,7 +120,7 @@ pub fn get_sizing(data: &str, start_col: usize) -> SizingParserOutput {
     }
 }
 
-pub fn get_word(data: &str, start_col: usize) -> WordParserOutput {
+pub fn get_word(data: &str, start_col: usize) -> WordParserOutput<'_> {
     let mut errors = Vec::new();
     let id_start = data.char_indices().find(|(_, x)| !x.is_whitespace());
     if id_start.is_none() {
@@ -134,7 +147,7 @@ pub fn get_word(data: &str, start_col: usize) -> WordParserOutput {
     WordParserOutput { word, errors }
 }
 
-pub fn get_typespec(data: &str, start_col: usize) -> TypeSpecParserOutput {
+pub fn get_typespec(data: &str, start_col: usize) -> TypeSpecParserOutput<'_> {
     let sizing;
     let is_singleton;
     let end_of_dtype;
@@ -191,9 +204,144 @@ pub fn get_typespec(data: &str, start_col: usize) -> TypeSpecParserOutput {
     }
 }
 
+/// Split a quoted documentation string off the end of a type specification, returning the type
+/// specification text and the documentation contents without quotes.
+fn split_doc<'a>(
+    data: &'a str,
+    start_col: usize,
+    errors: &mut Vec<InternalError>,
+) -> (&'a str, Option<&'a str>) {
+    let Some((typespec, quoted)) = data.split_once('"') else {
+        return (data, None);
+    };
+    let quote_col = start_col + typespec.chars().count();
+    match quoted.split_once('"') {
+        Some((doc, rest)) => {
+            if !rest.chars().all(char::is_whitespace) {
+                let rest_col = quote_col + doc.chars().count() + 2;
+                let leading = rest.chars().take_while(|c| c.is_whitespace()).count();
+                errors.push(InternalError::Parsing {
+                    offender: TokenClone::new(rest.trim(), rest_col + leading),
+                    reason: ParsingFailure::TrailingTextAfterDoc,
+                });
+            }
+            (typespec, Some(doc))
+        }
+        None => {
+            errors.push(InternalError::Parsing {
+                offender: TokenClone::new(&data[typespec.len()..], quote_col),
+                reason: ParsingFailure::UnterminatedDoc,
+            });
+            (typespec, None)
+        }
+    }
+}
+
+/// Split a fixed-point annotation such as `@ q16.16` off the end of a type specification,
+/// returning the type specification text and the parsed scaling.
+fn split_scaling<'a>(
+    data: &'a str,
+    start_col: usize,
+    errors: &mut Vec<InternalError>,
+) -> (&'a str, Option<(Scaling, TokenClone)>) {
+    let Some((typespec, annotation)) = data.split_once('@') else {
+        return (data, None);
+    };
+    // The offending token includes the `@` so that it is never empty
+    let token = TokenClone::new(
+        &format!("@{}", annotation.trim_end()),
+        start_col + typespec.chars().count(),
+    );
+    match Scaling::from_text(annotation.trim()) {
+        Some(scaling) => (typespec, Some((scaling, token))),
+        None => {
+            errors.push(InternalError::Parsing {
+                offender: token,
+                reason: ParsingFailure::InvalidScaling,
+            });
+            (typespec, None)
+        }
+    }
+}
+
+/// Split an enum mapping such as `enum u8 { idle=0, active=1 }` into the type specification
+/// between `enum` and `{` and the parsed mapping. The column at which the returned type
+/// specification starts is returned alongside it.
+fn split_enum<'a>(
+    data: &'a str,
+    start_col: usize,
+    errors: &mut Vec<InternalError>,
+) -> (&'a str, usize, Option<(EnumMapping, TokenClone)>) {
+    let leading = data.len() - data.trim_start().len();
+    let Some(rest) = data[leading..]
+        .strip_prefix("enum")
+        .filter(|rest| rest.starts_with(char::is_whitespace))
+    else {
+        return (data, start_col, None);
+    };
+    let typespec_col = start_col + data[..leading].chars().count() + "enum".len();
+    let token = TokenClone::new(data.trim(), start_col + data[..leading].chars().count());
+    let Some((typespec, braced)) = rest.split_once('{') else {
+        errors.push(InternalError::Parsing {
+            offender: token,
+            reason: ParsingFailure::InvalidEnum,
+        });
+        return (rest, typespec_col, None);
+    };
+    match braced
+        .trim_end()
+        .strip_suffix('}')
+        .and_then(EnumMapping::from_text)
+    {
+        Some(mapping) => (typespec, typespec_col, Some((mapping, token))),
+        None => {
+            errors.push(InternalError::Parsing {
+                offender: token,
+                reason: ParsingFailure::InvalidEnum,
+            });
+            (typespec, typespec_col, None)
+        }
+    }
+}
+
+/// Split a maximum length such as the `(64)` of `string(64)` off the end of a type
+/// specification, returning the type specification text and the parsed length.
+fn split_max_len<'a>(
+    data: &'a str,
+    start_col: usize,
+    errors: &mut Vec<InternalError>,
+) -> (&'a str, Option<(usize, TokenClone)>) {
+    let Some((typespec, parenthesized)) = data.split_once('(') else {
+        return (data, None);
+    };
+    let parenthesized = parenthesized.trim_end();
+    // The offending token includes the `(` so that it is never empty
+    let token = TokenClone::new(
+        &format!("({parenthesized}"),
+        start_col + typespec.chars().count(),
+    );
+    match parenthesized
+        .strip_suffix(')')
+        .and_then(|n| n.trim().parse().ok())
+    {
+        Some(max_len) => (typespec, Some((max_len, token))),
+        None => {
+            errors.push(InternalError::Parsing {
+                offender: token,
+                reason: ParsingFailure::InvalidMaxLength,
+            });
+            (typespec, None)
+        }
+    }
+}
+
 pub fn get_memberspec(data: &str, start_col: usize) -> MemberSpecParserOutput<'_> {
     let mut identifier = None;
     let mut typespec = None;
+    let mut doc = None;
+    let mut scaling = None;
+    let mut enum_mapping = None;
+    let mut max_len = None;
     let mut errors = Vec::new();
 
     if let Some((left_of_colon, right_of_colon)) = data.split_once(':') {
@@ -204,8 +352,23 @@ pub fn get_memberspec(data: &str, start_col: usize) -> MemberSpecParserOutput<'_
         for error in &ipo.errors {
             errors.push(error.clone());
         }
+        // Documentation parsing
+        let (right_of_colon, doc_text) =
+            split_doc(right_of_colon, start_col + colon_pos + 1, &mut errors);
+        doc = doc_text;
+        // Scaling parsing
+        let (right_of_colon, scaling_annotation) =
+            split_scaling(right_of_colon, start_col + colon_pos + 1, &mut errors);
+        scaling = scaling_annotation;
+        // Enum mapping parsing
+        let (right_of_colon, typespec_col, mapping) =
+            split_enum(right_of_colon, start_col + colon_pos + 1, &mut errors);
+        enum_mapping = mapping;
+        // Maximum length parsing
+        let (right_of_colon, cap) = split_max_len(right_of_colon, typespec_col, &mut errors);
+        max_len = cap;
         // TypeSpec parsing
-        let tso = get_typespec(right_of_colon, start_col + colon_pos + 1);
+        let tso = get_typespec(right_of_colon, typespec_col);
         for error in &tso.errors {
             errors.push(error.clone());
         }
@@ -224,28 +387,61 @@ pub fn get_memberspec(data: &str, start_col: usize) -> MemberSpecParserOutput<'_
     MemberSpecParserOutput {
         identifier,
         typespec,
+        doc,
+        scaling,
+        enum_mapping,
+        max_len,
         errors,
     }
 }
 
+/// Split a specification on commas which are not inside quoted documentation or enum braces,
+/// pairing each segment with the byte position at which it starts.
+fn split_members(data: &str) -> Vec<(&str, usize)> {
+    let mut segments = Vec::new();
+    let mut in_quotes = false;
+    let mut in_braces = false;
+    let mut start = 0;
+    for (i, c) in data.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '{' if !in_quotes => in_braces = true,
+            '}' if !in_quotes => in_braces = false,
+            ',' if !in_quotes && !in_braces => {
+                segments.push((&data[start..i], start));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push((&data[start..], start));
+    segments
+}
+
 pub fn get_metadataspec(data: &str) -> MetadataSpecParserOutput<'_> {
     let member_outputs: Vec<MemberSpecParserOutput>;
 
-    let mut start_positions = data
-        .char_indices()
-        .filter(|(_, c)| *c == ',')
-        .map(|(i, _)| i + 1)
-        .collect::<Vec<usize>>();
-    start_positions.insert(0, 0);
+    let mut segments = split_members(data);
 
     if data.chars().all(char::is_whitespace) {
         member_outputs = Vec::new();
-    } else if !data.chars().any(|c| c == ',') {
+    } else if segments.len() == 1 {
         member_outputs = vec![get_memberspec(data, 0)]
     } else {
-        member_outputs = data
-            .split(',')
-            .zip(start_positions)
+        // A blank segment before the first comma or after the last one is a stray delimiter,
+        // not a missing member, so drop it. Blank segments between commas still error, as does
+        // a spec made only of commas, which has no member for the delimiters to belong to.
+        let is_blank = |(segment, _): &(&str, usize)| segment.chars().all(char::is_whitespace);
+        if !segments.iter().all(is_blank) {
+            if segments.last().is_some_and(is_blank) {
+                segments.pop();
+            }
+            if segments.first().is_some_and(is_blank) {
+                segments.remove(0);
+            }
+        }
+        member_outputs = segments
+            .into_iter()
             .map(|(member_spec, pos)| get_memberspec(member_spec, pos))
             .collect();
     }
@@ -875,6 +1071,79 @@ mod test {
             run_ok_simple("myarr", "f32", Some("5"));
         }
 
+        #[test]
+        fn doc_ok() {
+            let text = r#"foo: u32[4] "a, b [c]""#;
+            let member_spec = get_memberspec(text, 0);
+            pretty_assertions::assert_eq!(member_spec.doc, Some("a, b [c]"));
+            pretty_assertions::assert_eq!(member_spec.errors, Vec::new());
+            let typespec = member_spec.typespec.unwrap();
+            pretty_assertions::assert_eq!(typespec.dtype.unwrap().data.data, "u32");
+            pretty_assertions::assert_eq!(typespec.sizing.unwrap().data.data, "4");
+        }
+
+        #[test]
+        fn max_len_ok() {
+            let text = r#"name: string(64) "label""#;
+            let member_spec = get_memberspec(text, 0);
+            pretty_assertions::assert_eq!(member_spec.errors, Vec::new());
+            pretty_assertions::assert_eq!(
+                member_spec.max_len,
+                Some((64, TokenClone::new("(64)", 12)))
+            );
+            pretty_assertions::assert_eq!(member_spec.doc, Some("label"));
+            let typespec = member_spec.typespec.unwrap();
+            pretty_assertions::assert_eq!(typespec.dtype.unwrap().data.data, "string");
+            assert!(typespec.is_singleton);
+        }
+
+        #[test]
+        fn invalid_max_len_fails() {
+            for (text, offender) in [
+                ("name: string(x)", "(x)"),
+                ("name: string(-1)", "(-1)"),
+                ("name: string(64", "(64"),
+                ("name: string(64)[2]", "(64)[2]"),
+            ] {
+                let member_spec = get_memberspec(text, 0);
+                pretty_assertions::assert_eq!(member_spec.max_len, None, "{text}");
+                pretty_assertions::assert_eq!(
+                    member_spec.errors,
+                    vec![InternalError::Parsing {
+                        offender: TokenClone::new(offender, 12),
+                        reason: ParsingFailure::InvalidMaxLength
+                    }],
+                    "{text}"
+                );
+            }
+        }
+
+        #[test]
+        fn unterminated_doc_fails() {
+            let text = r#"foo: u32 "abc"#;
+            let member_spec = get_memberspec(text, 0);
+            pretty_assertions::assert_eq!(
+                member_spec.errors,
+                vec![InternalError::Parsing {
+                    offender: TokenClone::new(r#""abc"#, 9),
+                    reason: ParsingFailure::UnterminatedDoc
+                }]
+            );
+        }
+
+        #[test]
+        fn trailing_text_after_doc_fails() {
+            let text = r#"foo: u32 "abc" def "#;
+            let member_spec = get_memberspec(text, 0);
+            pretty_assertions::assert_eq!(
+                member_spec.errors,
+                vec![InternalError::Parsing {
+                    offender: TokenClone::new("def", 15),
+                    reason: ParsingFailure::TrailingTextAfterDoc
+                }]
+            );
+        }
+
         #[test]
         fn missing_delimiter_fails() {
             let text = "  foo u8 ";
@@ -884,6 +1153,10 @@ mod test {
                 MemberSpecParserOutput {
                     identifier: None,
                     typespec: None,
+                    doc: None,
+                    scaling: None,
+                    enum_mapping: None,
+                    max_len: None,
                     errors: vec![InternalError::Parsing {
                         offender: TokenClone::new("foo u8", 2),
                         reason: ParsingFailure::MissingIdSpecDelimiter
@@ -1023,6 +1296,69 @@ mod test {
             );
         }
 
+        #[test]
+        fn trailing_comma_ok() {
+            let m1 = "foo: u32";
+            let spec = &format!("{m1}, ");
+            let metadata_spec = get_metadataspec(spec);
+            pretty_assertions::assert_eq!(
+                metadata_spec,
+                MetadataSpecParserOutput {
+                    member_outputs: vec![get_memberspec(m1, 0)],
+                    errors: Vec::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn leading_comma_ok() {
+            let m1 = " foo: u32";
+            let m2 = "bar: u8";
+            let spec = &format!(",{m1},{m2}");
+            let metadata_spec = get_metadataspec(spec);
+            pretty_assertions::assert_eq!(
+                metadata_spec,
+                MetadataSpecParserOutput {
+                    member_outputs: vec![get_memberspec(m1, 1), get_memberspec(m2, m1.len() + 2),],
+                    errors: Vec::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn comma_in_doc_ok() {
+            let m1 = r#"foo: u32 "x, y""#;
+            let m2 = "bar: u8";
+            let spec = &format!("{m1},{m2}");
+            let metadata_spec = get_metadataspec(spec);
+            pretty_assertions::assert_eq!(
+                metadata_spec,
+                MetadataSpecParserOutput {
+                    member_outputs: vec![get_memberspec(m1, 0), get_memberspec(m2, m1.len() + 1)],
+                    errors: Vec::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn double_internal_comma_err() {
+            let spec = "foo:u32,,bar:u8";
+            let metadata_spec = get_metadataspec(spec);
+            pretty_assertions::assert_eq!(metadata_spec.member_outputs.len(), 3);
+            pretty_assertions::assert_eq!(metadata_spec.member_outputs[1], get_memberspec("", 8));
+            assert!(!metadata_spec.errors.is_empty());
+        }
+
+        #[test]
+        fn only_comma_err() {
+            let metadata_spec = get_metadataspec(" , ");
+            pretty_assertions::assert_eq!(
+                metadata_spec.member_outputs,
+                vec![get_memberspec(" ", 0), get_memberspec(" ", 2)]
+            );
+            assert!(!metadata_spec.errors.is_empty());
+        }
+
         // TODO: handle case where all memberspecs are invalid
     }
 }
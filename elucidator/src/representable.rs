@@ -1,6 +1,11 @@
 use crate::error::*;
 use crate::member::Dtype;
+use crate::value::LeBufferRead;
 use elucidator_macros::{representable_primitive_impl, representable_vec_impl};
+use std::io::Cursor;
+use std::num::{
+    NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8,
+};
 
 type Result<T, E = ElucidatorError> = std::result::Result<T, E>;
 
@@ -109,7 +114,6 @@ type Result<T, E = ElucidatorError> = std::result::Result<T, E>;
 /// # let datum: Box<dyn Representable> = Box::new(0 as u8);
 /// let datum_as_buffer = datum.as_buffer();
 /// ```
-
 pub trait Representable {
     /// Determine whether this type contains numeric values
     fn is_numeric(&self) -> bool;
@@ -124,7 +128,13 @@ pub trait Representable {
     /// Determine whether this type is floating-point
     fn is_floating(&self) -> bool;
     /// Produce an equivalent buffer of bytes
-    fn as_buffer(&self) -> Vec<u8>;
+    fn as_buffer(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.as_buffer_into(&mut out);
+        out
+    }
+    /// Append the bytes `as_buffer` would produce to `out`, without allocating a new buffer
+    fn as_buffer_into(&self, out: &mut Vec<u8>);
     /// Attempt to convert this type into a u8
     fn as_u8(&self) -> Result<u8, ElucidatorError>;
     /// Attempt to convert this type into a u16
@@ -180,6 +190,281 @@ representable_vec_impl!(std::primitive::i64);
 representable_vec_impl!(std::primitive::f32);
 representable_vec_impl!(std::primitive::f64);
 
+/// Non-zero integers are written exactly as their underlying integer.
+macro_rules! impl_representable_nonzero {
+    ($($t:ty),*) => {
+        $(
+            impl Representable for $t {
+                fn is_numeric(&self) -> bool {
+                    self.get().is_numeric()
+                }
+                fn is_array(&self) -> bool {
+                    self.get().is_array()
+                }
+                fn is_signed(&self) -> bool {
+                    self.get().is_signed()
+                }
+                fn is_integer(&self) -> bool {
+                    self.get().is_integer()
+                }
+                fn is_floating(&self) -> bool {
+                    self.get().is_floating()
+                }
+                fn get_dtype(&self) -> Dtype {
+                    self.get().get_dtype()
+                }
+                fn as_buffer_into(&self, out: &mut Vec<u8>) {
+                    self.get().as_buffer_into(out)
+                }
+                fn as_u8(&self) -> Result<u8, ElucidatorError> {
+                    self.get().as_u8()
+                }
+                fn as_u16(&self) -> Result<u16, ElucidatorError> {
+                    self.get().as_u16()
+                }
+                fn as_u32(&self) -> Result<u32, ElucidatorError> {
+                    self.get().as_u32()
+                }
+                fn as_u64(&self) -> Result<u64, ElucidatorError> {
+                    self.get().as_u64()
+                }
+                fn as_i8(&self) -> Result<i8, ElucidatorError> {
+                    self.get().as_i8()
+                }
+                fn as_i16(&self) -> Result<i16, ElucidatorError> {
+                    self.get().as_i16()
+                }
+                fn as_i32(&self) -> Result<i32, ElucidatorError> {
+                    self.get().as_i32()
+                }
+                fn as_i64(&self) -> Result<i64, ElucidatorError> {
+                    self.get().as_i64()
+                }
+                fn as_f32(&self) -> Result<f32, ElucidatorError> {
+                    self.get().as_f32()
+                }
+                fn as_f64(&self) -> Result<f64, ElucidatorError> {
+                    self.get().as_f64()
+                }
+                fn as_string(&self) -> Result<String, ElucidatorError> {
+                    self.get().as_string()
+                }
+                fn as_vec_u8(&self) -> Result<Vec<u8>, ElucidatorError> {
+                    self.get().as_vec_u8()
+                }
+                fn as_vec_u16(&self) -> Result<Vec<u16>, ElucidatorError> {
+                    self.get().as_vec_u16()
+                }
+                fn as_vec_u32(&self) -> Result<Vec<u32>, ElucidatorError> {
+                    self.get().as_vec_u32()
+                }
+                fn as_vec_u64(&self) -> Result<Vec<u64>, ElucidatorError> {
+                    self.get().as_vec_u64()
+                }
+                fn as_vec_i8(&self) -> Result<Vec<i8>, ElucidatorError> {
+                    self.get().as_vec_i8()
+                }
+                fn as_vec_i16(&self) -> Result<Vec<i16>, ElucidatorError> {
+                    self.get().as_vec_i16()
+                }
+                fn as_vec_i32(&self) -> Result<Vec<i32>, ElucidatorError> {
+                    self.get().as_vec_i32()
+                }
+                fn as_vec_i64(&self) -> Result<Vec<i64>, ElucidatorError> {
+                    self.get().as_vec_i64()
+                }
+                fn as_vec_f32(&self) -> Result<Vec<f32>, ElucidatorError> {
+                    self.get().as_vec_f32()
+                }
+                fn as_vec_f64(&self) -> Result<Vec<f64>, ElucidatorError> {
+                    self.get().as_vec_f64()
+                }
+            }
+        )*
+    };
+}
+
+impl_representable_nonzero! {
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64
+}
+
+/// Durations are written as `i64` nanoseconds, saturating at `i64::MAX` (about 292 years).
+impl Representable for std::time::Duration {
+    fn is_numeric(&self) -> bool {
+        true
+    }
+    fn is_array(&self) -> bool {
+        false
+    }
+    fn get_dtype(&self) -> Dtype {
+        Dtype::Duration
+    }
+    fn is_signed(&self) -> bool {
+        true
+    }
+    fn is_integer(&self) -> bool {
+        true
+    }
+    fn is_floating(&self) -> bool {
+        false
+    }
+    fn as_buffer_into(&self, out: &mut Vec<u8>) {
+        self.as_i64().unwrap().as_buffer_into(out)
+    }
+    fn as_u8(&self) -> Result<u8, ElucidatorError> {
+        self.as_i64()?.as_u8()
+    }
+    fn as_u16(&self) -> Result<u16, ElucidatorError> {
+        self.as_i64()?.as_u16()
+    }
+    fn as_u32(&self) -> Result<u32, ElucidatorError> {
+        self.as_i64()?.as_u32()
+    }
+    fn as_u64(&self) -> Result<u64, ElucidatorError> {
+        self.as_i64()?.as_u64()
+    }
+    fn as_i8(&self) -> Result<i8, ElucidatorError> {
+        self.as_i64()?.as_i8()
+    }
+    fn as_i16(&self) -> Result<i16, ElucidatorError> {
+        self.as_i64()?.as_i16()
+    }
+    fn as_i32(&self) -> Result<i32, ElucidatorError> {
+        self.as_i64()?.as_i32()
+    }
+    fn as_i64(&self) -> Result<i64, ElucidatorError> {
+        Ok(i64::try_from(self.as_nanos()).unwrap_or(i64::MAX))
+    }
+    fn as_f32(&self) -> Result<f32, ElucidatorError> {
+        self.as_i64()?.as_f32()
+    }
+    fn as_f64(&self) -> Result<f64, ElucidatorError> {
+        self.as_i64()?.as_f64()
+    }
+    fn as_string(&self) -> Result<String, ElucidatorError> {
+        ElucidatorError::new_conversion(Dtype::Duration.keyword(), Dtype::Str.keyword())
+    }
+    fn as_vec_u8(&self) -> Result<Vec<u8>, ElucidatorError> {
+        self.as_i64()?.as_vec_u8()
+    }
+    fn as_vec_u16(&self) -> Result<Vec<u16>, ElucidatorError> {
+        self.as_i64()?.as_vec_u16()
+    }
+    fn as_vec_u32(&self) -> Result<Vec<u32>, ElucidatorError> {
+        self.as_i64()?.as_vec_u32()
+    }
+    fn as_vec_u64(&self) -> Result<Vec<u64>, ElucidatorError> {
+        self.as_i64()?.as_vec_u64()
+    }
+    fn as_vec_i8(&self) -> Result<Vec<i8>, ElucidatorError> {
+        self.as_i64()?.as_vec_i8()
+    }
+    fn as_vec_i16(&self) -> Result<Vec<i16>, ElucidatorError> {
+        self.as_i64()?.as_vec_i16()
+    }
+    fn as_vec_i32(&self) -> Result<Vec<i32>, ElucidatorError> {
+        self.as_i64()?.as_vec_i32()
+    }
+    fn as_vec_i64(&self) -> Result<Vec<i64>, ElucidatorError> {
+        self.as_i64()?.as_vec_i64()
+    }
+    fn as_vec_f32(&self) -> Result<Vec<f32>, ElucidatorError> {
+        self.as_i64()?.as_vec_f32()
+    }
+    fn as_vec_f64(&self) -> Result<Vec<f64>, ElucidatorError> {
+        self.as_i64()?.as_vec_f64()
+    }
+}
+
+/// Chars are written as their Unicode scalar value in a little-endian `u32`, and convert to
+/// the same numbers a `u32` does.
+impl Representable for char {
+    fn is_numeric(&self) -> bool {
+        false
+    }
+    fn is_array(&self) -> bool {
+        false
+    }
+    fn get_dtype(&self) -> Dtype {
+        Dtype::Char
+    }
+    fn is_signed(&self) -> bool {
+        false
+    }
+    fn is_integer(&self) -> bool {
+        false
+    }
+    fn is_floating(&self) -> bool {
+        false
+    }
+    fn as_buffer_into(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&(*self as u32).to_le_bytes());
+    }
+    fn as_u8(&self) -> Result<u8, ElucidatorError> {
+        (*self as u32).as_u8()
+    }
+    fn as_u16(&self) -> Result<u16, ElucidatorError> {
+        (*self as u32).as_u16()
+    }
+    fn as_u32(&self) -> Result<u32, ElucidatorError> {
+        (*self as u32).as_u32()
+    }
+    fn as_u64(&self) -> Result<u64, ElucidatorError> {
+        (*self as u32).as_u64()
+    }
+    fn as_i8(&self) -> Result<i8, ElucidatorError> {
+        (*self as u32).as_i8()
+    }
+    fn as_i16(&self) -> Result<i16, ElucidatorError> {
+        (*self as u32).as_i16()
+    }
+    fn as_i32(&self) -> Result<i32, ElucidatorError> {
+        (*self as u32).as_i32()
+    }
+    fn as_i64(&self) -> Result<i64, ElucidatorError> {
+        (*self as u32).as_i64()
+    }
+    fn as_f32(&self) -> Result<f32, ElucidatorError> {
+        (*self as u32).as_f32()
+    }
+    fn as_f64(&self) -> Result<f64, ElucidatorError> {
+        (*self as u32).as_f64()
+    }
+    fn as_string(&self) -> Result<String, ElucidatorError> {
+        Ok(self.to_string())
+    }
+    fn as_vec_u8(&self) -> Result<Vec<u8>, ElucidatorError> {
+        (*self as u32).as_vec_u8()
+    }
+    fn as_vec_u16(&self) -> Result<Vec<u16>, ElucidatorError> {
+        (*self as u32).as_vec_u16()
+    }
+    fn as_vec_u32(&self) -> Result<Vec<u32>, ElucidatorError> {
+        (*self as u32).as_vec_u32()
+    }
+    fn as_vec_u64(&self) -> Result<Vec<u64>, ElucidatorError> {
+        (*self as u32).as_vec_u64()
+    }
+    fn as_vec_i8(&self) -> Result<Vec<i8>, ElucidatorError> {
+        (*self as u32).as_vec_i8()
+    }
+    fn as_vec_i16(&self) -> Result<Vec<i16>, ElucidatorError> {
+        (*self as u32).as_vec_i16()
+    }
+    fn as_vec_i32(&self) -> Result<Vec<i32>, ElucidatorError> {
+        (*self as u32).as_vec_i32()
+    }
+    fn as_vec_i64(&self) -> Result<Vec<i64>, ElucidatorError> {
+        (*self as u32).as_vec_i64()
+    }
+    fn as_vec_f32(&self) -> Result<Vec<f32>, ElucidatorError> {
+        (*self as u32).as_vec_f32()
+    }
+    fn as_vec_f64(&self) -> Result<Vec<f64>, ElucidatorError> {
+        (*self as u32).as_vec_f64()
+    }
+}
+
 impl Representable for String {
     fn is_numeric(&self) -> bool {
         false
@@ -199,86 +484,440 @@ impl Representable for String {
     fn is_floating(&self) -> bool {
         false
     }
-    fn as_buffer(&self) -> Vec<u8> {
+    fn as_buffer_into(&self, out: &mut Vec<u8>) {
         // TODO: Determine if we need to enforce ASCII
-        let mut contents_buffer: Vec<u8> = self.as_bytes().to_vec();
-        let buffer_len = contents_buffer.len() as u64;
-        let mut buffer_indicating_size: Vec<u8> = buffer_len.to_le_bytes().to_vec();
-        let mut final_buffer =
-            Vec::with_capacity(buffer_indicating_size.len() + contents_buffer.len());
-        final_buffer.append(&mut buffer_indicating_size);
-        final_buffer.append(&mut contents_buffer);
-        final_buffer
+        out.reserve(8 + self.len());
+        out.extend_from_slice(&(self.len() as u64).to_le_bytes());
+        out.extend_from_slice(self.as_bytes());
     }
     fn as_u8(&self) -> Result<u8, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "u8")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::Byte.keyword())
     }
     fn as_u16(&self) -> Result<u16, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "u16")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::UnsignedInteger16.keyword())
     }
     fn as_u32(&self) -> Result<u32, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "u32")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::UnsignedInteger32.keyword())
     }
     fn as_u64(&self) -> Result<u64, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "u64")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::UnsignedInteger64.keyword())
     }
     fn as_i8(&self) -> Result<i8, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "i8")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::SignedInteger8.keyword())
     }
     fn as_i16(&self) -> Result<i16, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "i16")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::SignedInteger16.keyword())
     }
     fn as_i32(&self) -> Result<i32, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "i32")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::SignedInteger32.keyword())
     }
     fn as_i64(&self) -> Result<i64, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "i64")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::SignedInteger64.keyword())
     }
     fn as_f32(&self) -> Result<f32, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "f32")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::Float32.keyword())
     }
     fn as_f64(&self) -> Result<f64, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "f64")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), Dtype::Float64.keyword())
     }
     fn as_string(&self) -> Result<String, ElucidatorError> {
         Ok(self.clone())
     }
     fn as_vec_u8(&self) -> Result<Vec<u8>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "u8 array")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), &Dtype::Byte.array_keyword())
     }
     fn as_vec_u16(&self) -> Result<Vec<u16>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "u16 array")
+        ElucidatorError::new_conversion(
+            Dtype::Str.keyword(),
+            &Dtype::UnsignedInteger16.array_keyword(),
+        )
     }
     fn as_vec_u32(&self) -> Result<Vec<u32>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "u32 array")
+        ElucidatorError::new_conversion(
+            Dtype::Str.keyword(),
+            &Dtype::UnsignedInteger32.array_keyword(),
+        )
     }
     fn as_vec_u64(&self) -> Result<Vec<u64>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "u64 array")
+        ElucidatorError::new_conversion(
+            Dtype::Str.keyword(),
+            &Dtype::UnsignedInteger64.array_keyword(),
+        )
     }
     fn as_vec_i8(&self) -> Result<Vec<i8>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "i8 array")
+        ElucidatorError::new_conversion(
+            Dtype::Str.keyword(),
+            &Dtype::SignedInteger8.array_keyword(),
+        )
     }
     fn as_vec_i16(&self) -> Result<Vec<i16>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "i16 array")
+        ElucidatorError::new_conversion(
+            Dtype::Str.keyword(),
+            &Dtype::SignedInteger16.array_keyword(),
+        )
     }
     fn as_vec_i32(&self) -> Result<Vec<i32>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "i32 array")
+        ElucidatorError::new_conversion(
+            Dtype::Str.keyword(),
+            &Dtype::SignedInteger32.array_keyword(),
+        )
     }
     fn as_vec_i64(&self) -> Result<Vec<i64>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "i64 array")
+        ElucidatorError::new_conversion(
+            Dtype::Str.keyword(),
+            &Dtype::SignedInteger64.array_keyword(),
+        )
     }
     fn as_vec_f32(&self) -> Result<Vec<f32>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "f32 array")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), &Dtype::Float32.array_keyword())
     }
     fn as_vec_f64(&self) -> Result<Vec<f64>, ElucidatorError> {
-        ElucidatorError::new_conversion("string", "f64 array")
+        ElucidatorError::new_conversion(Dtype::Str.keyword(), &Dtype::Float64.array_keyword())
+    }
+}
+
+/// Determine whether a value of dtype `from` can always be converted to dtype `to` without loss,
+/// following the conversion table documented on [`Representable`].
+pub fn can_convert(from: Dtype, to: Dtype) -> bool {
+    use Dtype::*;
+    match to {
+        Str => matches!(from, Str | Char),
+        Char => matches!(from, Char),
+        Byte => matches!(from, Byte),
+        UnsignedInteger16 => matches!(from, Byte | UnsignedInteger16),
+        UnsignedInteger32 => matches!(from, Byte | UnsignedInteger16 | UnsignedInteger32 | Char),
+        UnsignedInteger64 => matches!(
+            from,
+            Byte | UnsignedInteger16 | UnsignedInteger32 | UnsignedInteger64 | Char
+        ),
+        SignedInteger8 => matches!(from, SignedInteger8),
+        SignedInteger16 => matches!(from, Byte | SignedInteger8 | SignedInteger16),
+        SignedInteger32 => matches!(
+            from,
+            Byte | UnsignedInteger16 | SignedInteger8 | SignedInteger16 | SignedInteger32
+        ),
+        // Durations are stored exactly like i64, so they convert both ways
+        SignedInteger64 | Duration => matches!(
+            from,
+            Byte | UnsignedInteger16
+                | UnsignedInteger32
+                | SignedInteger8
+                | SignedInteger16
+                | SignedInteger32
+                | SignedInteger64
+                | Duration
+                | Char
+        ),
+        Float32 => matches!(
+            from,
+            Byte | UnsignedInteger16 | SignedInteger8 | SignedInteger16 | Float32
+        ),
+        Float64 => matches!(
+            from,
+            Byte | UnsignedInteger16
+                | UnsignedInteger32
+                | SignedInteger8
+                | SignedInteger16
+                | SignedInteger32
+                | Float32
+                | Float64
+                | Char
+        ),
+    }
+}
+
+/// Compare two representable values, requiring the same dtype and array-ness before comparing
+/// contents. Floats compare with `==`, so NaN is never equal to itself.
+pub fn repr_eq(a: &dyn Representable, b: &dyn Representable) -> bool {
+    if a.get_dtype() != b.get_dtype() || a.is_array() != b.is_array() {
+        return false;
     }
+    macro_rules! same {
+        ($conversion:ident) => {
+            matches!((a.$conversion(), b.$conversion()), (Ok(x), Ok(y)) if x == y)
+        };
+    }
+    match (a.get_dtype(), a.is_array()) {
+        (Dtype::Byte, false) => same!(as_u8),
+        (Dtype::UnsignedInteger16, false) => same!(as_u16),
+        (Dtype::UnsignedInteger32, false) => same!(as_u32),
+        (Dtype::UnsignedInteger64, false) => same!(as_u64),
+        (Dtype::SignedInteger8, false) => same!(as_i8),
+        (Dtype::SignedInteger16, false) => same!(as_i16),
+        (Dtype::SignedInteger32, false) => same!(as_i32),
+        (Dtype::SignedInteger64 | Dtype::Duration, false) => same!(as_i64),
+        (Dtype::Float32, false) => same!(as_f32),
+        (Dtype::Float64, false) => same!(as_f64),
+        (Dtype::Str, false) => same!(as_string),
+        (Dtype::Char, false) => same!(as_u32),
+        (Dtype::Byte, true) => same!(as_vec_u8),
+        (Dtype::UnsignedInteger16, true) => same!(as_vec_u16),
+        (Dtype::UnsignedInteger32, true) => same!(as_vec_u32),
+        (Dtype::UnsignedInteger64, true) => same!(as_vec_u64),
+        (Dtype::SignedInteger8, true) => same!(as_vec_i8),
+        (Dtype::SignedInteger16, true) => same!(as_vec_i16),
+        (Dtype::SignedInteger32, true) => same!(as_vec_i32),
+        (Dtype::SignedInteger64 | Dtype::Duration, true) => same!(as_vec_i64),
+        (Dtype::Float32, true) => same!(as_vec_f32),
+        (Dtype::Float64, true) => same!(as_vec_f64),
+        (Dtype::Str | Dtype::Char, true) => false,
+    }
+}
+
+/// Build one record buffer from a tuple of [`Representable`] values, concatenating each
+/// element's `as_buffer` in order. Implemented for tuples of up to 8 elements.
+/// ```
+/// use elucidator::representable::BufferConcat;
+///
+/// let buffer = (7u64, 2u64).to_combined_buffer();
+/// # assert_eq!(buffer.len(), 16);
+/// ```
+pub trait BufferConcat {
+    fn to_combined_buffer(&self) -> Vec<u8>;
+}
+
+macro_rules! impl_buffer_concat {
+    ($($name:ident),+) => {
+        impl<$($name: Representable),+> BufferConcat for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn to_combined_buffer(&self) -> Vec<u8> {
+                let ($($name,)+) = self;
+                let mut buffer = Vec::new();
+                $($name.as_buffer_into(&mut buffer);)+
+                buffer
+            }
+        }
+    };
+}
+
+impl_buffer_concat!(A);
+impl_buffer_concat!(A, B);
+impl_buffer_concat!(A, B, C);
+impl_buffer_concat!(A, B, C, D);
+impl_buffer_concat!(A, B, C, D, E);
+impl_buffer_concat!(A, B, C, D, E, F);
+impl_buffer_concat!(A, B, C, D, E, F, G);
+impl_buffer_concat!(A, B, C, D, E, F, G, H);
+
+/// Numeric primitives that can be read from little-endian bytes with [`read_values`].
+pub trait LeReadable: Sized {
+    /// Read `n` values from the start of `buf`
+    fn read_n_le(buf: &[u8], n: usize) -> Result<Vec<Self>>;
+    /// Number of bytes occupied by `n` values
+    fn bytes_for(n: usize) -> usize;
+}
+
+macro_rules! impl_le_readable {
+    ($($tt:ty), *) => {
+        $(
+            impl LeReadable for $tt {
+                fn read_n_le(buf: &[u8], n: usize) -> Result<Vec<Self>> {
+                    <$tt as LeBufferRead>::get_n_le(buf, n)
+                }
+                fn bytes_for(n: usize) -> usize {
+                    <$tt as LeBufferRead>::bytes_needed(n)
+                }
+            }
+        )*
+    };
+}
+
+impl_le_readable! {u8, u16, u32, u64, i8, i16, i32, i64, f32, f64}
+
+/// Read `n` little-endian values from `cursor`, advancing it past them.
+/// On failure the cursor is left where it was.
+/// ```
+/// use elucidator::representable::read_values;
+/// use std::io::Cursor;
+///
+/// let bytes = [1, 0, 2, 0];
+/// let mut cursor = Cursor::new(&bytes[..]);
+/// let values = read_values::<u16>(&mut cursor, 2).unwrap();
+///
+/// # assert_eq!(values, vec![1, 2]);
+/// # assert_eq!(cursor.position(), 4);
+/// ```
+pub fn read_values<T: LeReadable>(cursor: &mut Cursor<&[u8]>, n: usize) -> Result<Vec<T>> {
+    let buf = *cursor.get_ref();
+    let start = (cursor.position() as usize).min(buf.len());
+    let values = T::read_n_le(&buf[start..], n)?;
+    cursor.set_position((start + T::bytes_for(n)) as u64);
+    Ok(values)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    mod read_values {
+        use super::*;
+
+        #[test]
+        fn u16_then_f64_ok() {
+            let bytes: Vec<u8> = [1u16, 515, 65535]
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .chain([0.5f64, -2.25].iter().flat_map(|v| v.to_le_bytes()))
+                .collect();
+            let mut cursor = Cursor::new(bytes.as_slice());
+
+            let shorts = read_values::<u16>(&mut cursor, 3);
+            assert_eq!(shorts, Ok(vec![1, 515, 65535]));
+            assert_eq!(cursor.position(), 6);
+
+            let doubles = read_values::<f64>(&mut cursor, 2);
+            assert_eq!(doubles, Ok(vec![0.5, -2.25]));
+            assert_eq!(cursor.position(), 22);
+        }
+
+        #[test]
+        fn too_short_err() {
+            let bytes = [0u8; 6];
+            let mut cursor = Cursor::new(&bytes[..]);
+            cursor.set_position(2);
+            let result = read_values::<f64>(&mut cursor, 1);
+            assert_eq!(
+                result,
+                Err(ElucidatorError::BufferSizing {
+                    expected: 8,
+                    found: 4
+                })
+            );
+            assert_eq!(cursor.position(), 2);
+        }
+    }
+
+    mod buffer_concat {
+        use super::*;
+        use crate::{designation::DesignationSpecification, value::DataValue};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn pair_matches_spec() {
+            let spec = DesignationSpecification::from_text("hits: u64, misses: u64").unwrap();
+            let buffer = (31u64, 9u64).to_combined_buffer();
+            let values = spec.interpret_enum(&buffer).unwrap();
+            assert_eq!(values["hits"], DataValue::UnsignedInteger64(31));
+            assert_eq!(values["misses"], DataValue::UnsignedInteger64(9));
+        }
+
+        #[test]
+        fn mixed_elements_ok() {
+            let buffer = (1u8, vec![2u16, 3], "ab".to_string()).to_combined_buffer();
+            assert_eq!(
+                buffer,
+                vec![1, 2, 0, 3, 0, 2, 0, 0, 0, 0, 0, 0, 0, b'a', b'b']
+            );
+        }
+    }
+
+    mod repr_eq {
+        use super::*;
+
+        #[test]
+        fn equal_scalars() {
+            assert!(repr_eq(&5u16, &5u16));
+            assert!(repr_eq(&"hi".to_string(), &"hi".to_string()));
+        }
+
+        #[test]
+        fn unequal_scalars() {
+            assert!(!repr_eq(&5u16, &6u16));
+            assert!(!repr_eq(&"hi".to_string(), &"ho".to_string()));
+        }
+
+        #[test]
+        fn equal_arrays() {
+            assert!(repr_eq(&vec![1.5f64, 2.5], &vec![1.5f64, 2.5]));
+            assert!(!repr_eq(&vec![1.5f64, 2.5], &vec![1.5f64]));
+        }
+
+        #[test]
+        fn dtype_mismatch_not_equal() {
+            assert!(!repr_eq(&5u16, &5u32));
+            assert!(!repr_eq(&5u8, &vec![5u8]));
+        }
+    }
+
+    mod can_convert {
+        use super::*;
+
+        const DTYPES: [Dtype; 13] = [
+            Dtype::Str,
+            Dtype::Byte,
+            Dtype::UnsignedInteger16,
+            Dtype::UnsignedInteger32,
+            Dtype::UnsignedInteger64,
+            Dtype::SignedInteger8,
+            Dtype::SignedInteger16,
+            Dtype::SignedInteger32,
+            Dtype::SignedInteger64,
+            Dtype::Float32,
+            Dtype::Float64,
+            Dtype::Duration,
+            Dtype::Char,
+        ];
+
+        fn default_of(dt: &Dtype) -> Box<dyn Representable> {
+            match dt {
+                Dtype::Str => Box::new(String::new()),
+                Dtype::Byte => Box::new(u8::default()),
+                Dtype::UnsignedInteger16 => Box::new(u16::default()),
+                Dtype::UnsignedInteger32 => Box::new(u32::default()),
+                Dtype::UnsignedInteger64 => Box::new(u64::default()),
+                Dtype::SignedInteger8 => Box::new(i8::default()),
+                Dtype::SignedInteger16 => Box::new(i16::default()),
+                Dtype::SignedInteger32 => Box::new(i32::default()),
+                Dtype::SignedInteger64 => Box::new(i64::default()),
+                Dtype::Float32 => Box::new(f32::default()),
+                Dtype::Float64 => Box::new(f64::default()),
+                Dtype::Duration => Box::new(std::time::Duration::default()),
+                Dtype::Char => Box::new(char::default()),
+            }
+        }
+
+        fn converts(value: &dyn Representable, to: &Dtype) -> bool {
+            match to {
+                Dtype::Str => value.as_string().is_ok(),
+                Dtype::Byte => value.as_u8().is_ok(),
+                Dtype::UnsignedInteger16 => value.as_u16().is_ok(),
+                Dtype::UnsignedInteger32 => value.as_u32().is_ok(),
+                Dtype::UnsignedInteger64 => value.as_u64().is_ok(),
+                Dtype::SignedInteger8 => value.as_i8().is_ok(),
+                Dtype::SignedInteger16 => value.as_i16().is_ok(),
+                Dtype::SignedInteger32 => value.as_i32().is_ok(),
+                Dtype::SignedInteger64 => value.as_i64().is_ok(),
+                Dtype::Float32 => value.as_f32().is_ok(),
+                Dtype::Float64 => value.as_f64().is_ok(),
+                Dtype::Duration => value.as_i64().is_ok(),
+                // Nothing converts into a char, so only chars themselves qualify
+                Dtype::Char => value.get_dtype() == Dtype::Char,
+            }
+        }
+
+        #[test]
+        fn matrix_cells_ok() {
+            assert!(can_convert(Dtype::Byte, Dtype::SignedInteger16));
+            assert!(!can_convert(Dtype::UnsignedInteger16, Dtype::Byte));
+            assert!(can_convert(Dtype::Float32, Dtype::Float64));
+            assert!(!can_convert(Dtype::Float64, Dtype::Float32));
+        }
+
+        #[test]
+        fn agrees_with_conversions() {
+            for from in DTYPES.iter() {
+                let value = default_of(from);
+                for to in DTYPES.iter() {
+                    assert_eq!(
+                        can_convert(from.clone(), to.clone()),
+                        converts(value.as_ref(), to),
+                        "{from:?} -> {to:?}"
+                    );
+                }
+            }
+        }
+    }
+
     mod as_buffer {
         use crate::test_utils;
 
@@ -298,6 +937,40 @@ mod tests {
             assert_eq!(value.as_buffer(), expected);
         }
 
+        #[test]
+        fn as_buffer_into_appends_ok() {
+            let values: Vec<Box<dyn Representable>> = vec![
+                Box::new(7u8),
+                Box::new(-3i64),
+                Box::new(2.5f32),
+                Box::new(vec![1u16, 0xABCD]),
+                Box::new("dog".to_string()),
+                Box::new(NonZeroU32::new(9).unwrap()),
+            ];
+            let mut out = vec![0xEE];
+            for value in &values {
+                value.as_buffer_into(&mut out);
+            }
+            let expected: Vec<u8> = std::iter::once(0xEE)
+                .chain(values.iter().flat_map(|v| v.as_buffer()))
+                .collect();
+            assert_eq!(out, expected);
+        }
+
+        #[test]
+        fn nonzero_u32_as_buffer_ok() {
+            let value = NonZeroU32::new(5).unwrap();
+            assert_eq!(value.as_buffer(), 5u32.as_buffer());
+            assert_eq!(value.get_dtype(), Dtype::UnsignedInteger32);
+        }
+
+        #[test]
+        fn nonzero_i16_as_buffer_ok() {
+            let value = NonZeroI16::new(-300).unwrap();
+            assert_eq!(value.as_buffer(), (-300i16).as_buffer());
+            assert_eq!(value.as_i64(), Ok(-300));
+        }
+
         #[test]
         fn u16_vec_as_buffer_ok() {
             let value: Vec<u16> = vec![0xFFFF, 0xAB];
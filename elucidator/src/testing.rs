@@ -0,0 +1,105 @@
+//! Helpers for testing code that decodes records, including lossy conversions where decoded
+//! floats are only expected to be close to the originals.
+use crate::value::DataValue;
+use std::collections::HashMap;
+
+fn floats_close(left: f64, right: f64, tol: f64) -> bool {
+    left == right || (left.is_nan() && right.is_nan()) || (left - right).abs() <= tol
+}
+
+fn values_close(left: &DataValue, right: &DataValue, tol: f64) -> bool {
+    fn arrays_close<T: Copy + Into<f64>>(left: &[T], right: &[T], tol: f64) -> bool {
+        left.len() == right.len()
+            && left
+                .iter()
+                .zip(right)
+                .all(|(l, r)| floats_close((*l).into(), (*r).into(), tol))
+    }
+    match (left, right) {
+        (DataValue::Float32(l), DataValue::Float32(r)) => floats_close(*l as f64, *r as f64, tol),
+        (DataValue::Float64(l), DataValue::Float64(r)) => floats_close(*l, *r, tol),
+        (DataValue::Float32Array(l), DataValue::Float32Array(r)) => arrays_close(l, r, tol),
+        (DataValue::Float64Array(l), DataValue::Float64Array(r)) => arrays_close(l, r, tol),
+        _ => left == right,
+    }
+}
+
+/// Assert that two decoded records have the same members with matching values.
+/// Float members, scalar or array, may differ by up to `tol` (and NaN matches NaN); every other
+/// value, including the variant itself, must be equal. Panics naming the first mismatching
+/// member in alphabetical order.
+/// ```
+/// use std::collections::HashMap;
+/// use elucidator::{testing::assert_records_close, value::DataValue};
+///
+/// let left = HashMap::from([("t", DataValue::Float64(1.0)), ("n", DataValue::Byte(3))]);
+/// let right = HashMap::from([("t", DataValue::Float64(1.0005)), ("n", DataValue::Byte(3))]);
+/// assert_records_close(&left, &right, 1e-3);
+/// ```
+#[track_caller]
+pub fn assert_records_close(
+    left: &HashMap<&str, DataValue>,
+    right: &HashMap<&str, DataValue>,
+    tol: f64,
+) {
+    let mut names: Vec<&str> = left.keys().chain(right.keys()).copied().collect();
+    names.sort_unstable();
+    names.dedup();
+    for name in names {
+        match (left.get(name), right.get(name)) {
+            (Some(l), Some(r)) if values_close(l, r, tol) => {}
+            (Some(l), Some(r)) => {
+                panic!("records differ at member {name}: left {l:?}, right {r:?}, tolerance {tol}")
+            }
+            (Some(_), None) => panic!("member {name} is only in the left record"),
+            (None, _) => panic!("member {name} is only in the right record"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(t: f64, ts: Vec<f32>) -> HashMap<&'static str, DataValue> {
+        HashMap::from([
+            ("id", DataValue::UnsignedInteger32(4)),
+            ("name", DataValue::Str("probe".to_string())),
+            ("t", DataValue::Float64(t)),
+            ("ts", DataValue::Float32Array(ts)),
+        ])
+    }
+
+    #[test]
+    fn within_tolerance_ok() {
+        let left = record(1.0, vec![0.5, f32::NAN]);
+        let right = record(1.0 + 1e-7, vec![0.5 - 1e-7, f32::NAN]);
+        assert_records_close(&left, &right, 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "records differ at member ts")]
+    fn beyond_tolerance_panics() {
+        let left = record(1.0, vec![0.5, 2.0]);
+        let right = record(1.0, vec![0.5, 2.1]);
+        assert_records_close(&left, &right, 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "records differ at member id")]
+    fn integers_exact() {
+        let left = record(1.0, vec![]);
+        let mut right = record(1.0, vec![]);
+        right.insert("id", DataValue::UnsignedInteger32(5));
+        assert_records_close(&left, &right, 10.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "member extra is only in the right record")]
+    fn missing_member_panics() {
+        let left = record(1.0, vec![]);
+        let mut right = record(1.0, vec![]);
+        right.insert("extra", DataValue::Byte(0));
+        assert_records_close(&left, &right, 0.0);
+    }
+}
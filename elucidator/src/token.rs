@@ -22,7 +22,7 @@ pub(crate) struct TokenData<'a> {
 }
 
 impl<'a> TokenData<'a> {
-    pub fn new(data: &'a str, column_start: usize, column_end: usize) -> TokenData {
+    pub fn new(data: &'a str, column_start: usize, column_end: usize) -> TokenData<'a> {
         let column_width = data.chars().count();
         assert!(column_start <= column_end, "columns swapped");
         assert!(
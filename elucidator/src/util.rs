@@ -16,8 +16,12 @@ impl<'a> Buffer<'a> {
     }
     /// Make a new vector of n elements new current position
     pub(crate) fn grab(&mut self, n: usize) -> Result<Vec<u8>> {
+        self.grab_slice(n).map(<[u8]>::to_vec)
+    }
+    /// Borrow the next n bytes, advancing past them without copying
+    pub(crate) fn grab_slice(&mut self, n: usize) -> Result<&'a [u8]> {
         let curr_pos = self.position;
-        if self.position + n > self.slice.len() {
+        if n > self.slice.len() - self.position {
             // Advance to end so that all future calls fail
             self.position = self.slice.len();
             Err(ElucidatorError::BufferSizing {
@@ -26,15 +30,66 @@ impl<'a> Buffer<'a> {
             })
         } else {
             self.position += n;
-            Ok(self.slice[curr_pos..(curr_pos + n)].to_vec())
+            Ok(&self.slice[curr_pos..(curr_pos + n)])
+        }
+    }
+    /// Borrow the bytes up to the next NUL, advancing past the NUL itself
+    pub(crate) fn grab_until_nul(&mut self) -> Result<&'a [u8]> {
+        let rest = &self.slice[self.position..];
+        match rest.iter().position(|&b| b == 0) {
+            Some(n) => {
+                self.position += n + 1;
+                Ok(&rest[..n])
+            }
+            None => {
+                self.position = self.slice.len();
+                Err(ElucidatorError::MissingNulTerminator)
+            }
         }
     }
+    /// Advance past n bytes without copying them
+    pub(crate) fn skip(&mut self, n: usize) -> Result<()> {
+        let remaining = self.slice.len() - self.position;
+        if n > remaining {
+            self.position = self.slice.len();
+            Err(ElucidatorError::BufferSizing {
+                expected: n,
+                found: remaining,
+            })
+        } else {
+            self.position += n;
+            Ok(())
+        }
+    }
+    /// Number of bytes not yet consumed
+    pub(crate) fn remaining(&self) -> usize {
+        self.slice.len() - self.position
+    }
+    /// Number of bytes consumed so far
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// 64-bit FNV-1a hash of `bytes`. Unlike the standard library's hasher, its output is fixed
+/// across Rust releases and platforms, so it may be stored.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn fnv1a_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
     #[test]
     fn simple_ok() {
         let array = [1, 2, 3, 4];
@@ -66,6 +121,27 @@ mod test {
         assert_eq!(expected, buffer.grab(4));
     }
 
+    #[test]
+    fn skip_ok() {
+        let array = [1, 2, 3, 4];
+        let mut buffer = Buffer::new(&array);
+        assert_eq!(Ok(()), buffer.skip(3));
+        assert_eq!(buffer.position(), 3);
+        assert_eq!(Ok(vec![4]), buffer.grab(1));
+    }
+
+    #[test]
+    fn skip_huge_err() {
+        let array = [1, 2];
+        let mut buffer = Buffer::new(&array);
+        buffer.skip(1).unwrap();
+        let expected = Err(ElucidatorError::BufferSizing {
+            expected: usize::MAX,
+            found: 1,
+        });
+        assert_eq!(expected, buffer.skip(usize::MAX));
+    }
+
     #[test]
     fn off_by_one_err() {
         let array = [1];
@@ -1,17 +1,26 @@
 use std::collections::HashMap;
 
+use crate::designation::IdentifierPolicy;
 use crate::error::*;
 use crate::member::{Dtype, MemberSpecification, Sizing};
 use crate::parsing::*;
-use crate::token::{DtypeToken, IdentifierToken, SizingToken, TokenClone};
+use crate::token::{DtypeToken, IdentifierToken, SizingToken, TokenClone, TokenData};
 
 type Result<T, E = InternalError> = std::result::Result<T, E>;
 
-fn valid_identifier_char(c: char) -> bool {
-    c.is_ascii_alphanumeric() || c == '_'
+fn valid_identifier_char(c: char, policy: &IdentifierPolicy) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || policy.extra_chars.contains(&c)
 }
 
+#[cfg(test)]
 pub(crate) fn validate_identifier(itoken: &IdentifierToken) -> Result<String> {
+    validate_identifier_with_policy(itoken, &IdentifierPolicy::default())
+}
+
+pub(crate) fn validate_identifier_with_policy(
+    itoken: &IdentifierToken,
+    policy: &IdentifierPolicy,
+) -> Result<String> {
     let mut errors: Vec<InternalError> = Vec::new();
     let identifier = itoken.data.data;
     match &identifier.chars().next() {
@@ -33,7 +42,7 @@ pub(crate) fn validate_identifier(itoken: &IdentifierToken) -> Result<String> {
 
     let mut illegal_chars: Vec<char> = identifier
         .chars()
-        .filter(|c| !valid_identifier_char(*c))
+        .filter(|c| !valid_identifier_char(*c, policy))
         .collect();
     illegal_chars.sort();
     illegal_chars.dedup();
@@ -50,6 +59,34 @@ pub(crate) fn validate_identifier(itoken: &IdentifierToken) -> Result<String> {
     }
 }
 
+/// Validate a member given as parts rather than text, as though it were written
+/// `identifier: dtype[sizing]` starting at column 0.
+pub(crate) fn validate_member_parts(
+    identifier: &str,
+    sizing: &Sizing,
+    dtype: &Dtype,
+) -> Result<MemberSpecification> {
+    let mut errors = Vec::new();
+    let itoken = IdentifierToken {
+        data: TokenData::new(identifier, 0, identifier.chars().count()),
+    };
+    if let Err(e) = validate_identifier_with_policy(&itoken, &IdentifierPolicy::default()) {
+        errors.push(e);
+    }
+    if matches!(dtype, Dtype::Str | Dtype::Char) && *sizing != Sizing::Singleton {
+        let column = identifier.chars().count() + ": ".len() + dtype.keyword().len();
+        errors.push(InternalError::IllegalSpecification {
+            offender: TokenClone::new(&sizing.suffix(), column),
+            reason: SpecificationFailure::IllegalArraySizing,
+        });
+    }
+    if errors.is_empty() {
+        Ok(MemberSpecification::from_parts(identifier, sizing, dtype))
+    } else {
+        Err(InternalError::merge(&errors))
+    }
+}
+
 pub(crate) fn validate_dtype(dtoken: &DtypeToken) -> Result<Dtype> {
     let s = dtoken.data.data;
     let dt = match s.trim() {
@@ -64,6 +101,8 @@ pub(crate) fn validate_dtype(dtoken: &DtypeToken) -> Result<Dtype> {
         "f32" => Dtype::Float32,
         "f64" => Dtype::Float64,
         "string" => Dtype::Str,
+        "duration" => Dtype::Duration,
+        "char" => Dtype::Char,
         _ => Err(InternalError::IllegalSpecification {
             offender: TokenClone::from_token_data(&dtoken.data),
             reason: SpecificationFailure::IllegalDataType,
@@ -87,14 +126,22 @@ pub(crate) fn validate_sizing(stoken: &SizingToken) -> Result<Sizing> {
     }
 }
 
-#[allow(clippy::unnecessary_unwrap)]
+#[cfg(test)]
 pub(crate) fn validate_memberspec(
     mpo: &MemberSpecParserOutput,
+) -> Result<MemberSpecification, InternalError> {
+    validate_memberspec_with_policy(mpo, &IdentifierPolicy::default())
+}
+
+#[allow(clippy::unnecessary_unwrap)]
+pub(crate) fn validate_memberspec_with_policy(
+    mpo: &MemberSpecParserOutput,
+    policy: &IdentifierPolicy,
 ) -> Result<MemberSpecification, InternalError> {
     let mut errors: Vec<InternalError> = mpo.errors.clone();
 
     let ident = if mpo.has_ident() {
-        match validate_identifier(&mpo.identifier.clone().unwrap()) {
+        match validate_identifier_with_policy(&mpo.identifier.clone().unwrap(), policy) {
             Ok(o) => Some(o),
             Err(e) => {
                 errors.push(e);
@@ -134,24 +181,86 @@ pub(crate) fn validate_memberspec(
     };
 
     if ident.is_some() && dtype.is_some() && sizing.is_some() {
+        // Documentation errors leave the rest of the member intact
+        if !mpo.errors.is_empty() {
+            return Err(InternalError::merge(&errors));
+        }
         if !errors.is_empty() {
             unreachable!(
                 "Parsed and validated MemberSpecification, but errors were also found: {:#?}",
                 errors
             );
         }
-        if dtype.clone().unwrap() == Dtype::Str && sizing.clone().unwrap() != Sizing::Singleton {
+        if matches!(dtype, Some(Dtype::Str | Dtype::Char)) && sizing != Some(Sizing::Singleton) {
+            // Point at the brackets, which the sizing token sits just inside of
+            let stoken = mpo.typespec.clone().unwrap().sizing.unwrap();
             errors.push(InternalError::IllegalSpecification {
-                offender: TokenClone::from_token_data(&mpo.identifier.clone().unwrap().data),
+                offender: TokenClone::new(
+                    &format!("[{}]", stoken.data.data),
+                    stoken.data.column_start - 1,
+                ),
                 reason: SpecificationFailure::IllegalArraySizing,
             });
             Err(InternalError::merge(&errors))
+        } else if let Some((_, token)) = mpo.scaling.as_ref().filter(|_| {
+            matches!(
+                dtype,
+                Some(Dtype::Float32 | Dtype::Float64 | Dtype::Str | Dtype::Char)
+            )
+        }) {
+            errors.push(InternalError::IllegalSpecification {
+                offender: token.clone(),
+                reason: SpecificationFailure::ScalingRequiresInteger,
+            });
+            Err(InternalError::merge(&errors))
+        } else if let Some((_, token)) = mpo.enum_mapping.as_ref().filter(|_| {
+            matches!(
+                dtype,
+                Some(Dtype::Float32 | Dtype::Float64 | Dtype::Str | Dtype::Char)
+            ) || sizing != Some(Sizing::Singleton)
+                || mpo.scaling.is_some()
+        }) {
+            errors.push(InternalError::IllegalSpecification {
+                offender: token.clone(),
+                reason: SpecificationFailure::EnumRequiresInteger,
+            });
+            Err(InternalError::merge(&errors))
+        } else if let Some((_, token)) = mpo.max_len.as_ref().filter(|_| dtype != Some(Dtype::Str))
+        {
+            errors.push(InternalError::IllegalSpecification {
+                offender: token.clone(),
+                reason: SpecificationFailure::MaxLengthRequiresString,
+            });
+            Err(InternalError::merge(&errors))
+        } else if let Some((value, token)) = mpo
+            .enum_mapping
+            .as_ref()
+            .and_then(|(mapping, token)| Some((mapping.duplicate_discriminant()?, token)))
+        {
+            errors.push(InternalError::IllegalSpecification {
+                offender: token.clone(),
+                reason: SpecificationFailure::DuplicateDiscriminant { value },
+            });
+            Err(InternalError::merge(&errors))
         } else {
-            Ok(MemberSpecification::from_parts(
-                &ident.unwrap(),
-                &sizing.unwrap(),
-                &dtype.unwrap(),
-            ))
+            let member =
+                MemberSpecification::from_parts(&ident.unwrap(), &sizing.unwrap(), &dtype.unwrap());
+            let member = match mpo.doc {
+                Some(doc) => member.with_doc(doc),
+                None => member,
+            };
+            let member = match mpo.scaling {
+                Some((scaling, _)) => member.with_scaling(scaling),
+                None => member,
+            };
+            let member = match mpo.max_len {
+                Some((max_len, _)) => member.with_max_len(max_len),
+                None => member,
+            };
+            Ok(match &mpo.enum_mapping {
+                Some((mapping, _)) => member.with_enum_mapping(mapping.clone()),
+                None => member,
+            })
         }
     } else {
         Err(InternalError::merge(&errors))
@@ -176,11 +285,12 @@ fn repeated_identifiers<'a>(member_names: &'a Vec<&'a str>) -> Vec<&'a str> {
 
 fn perform_metadata_partition(
     mpo: &MetadataSpecParserOutput,
+    policy: &IdentifierPolicy,
 ) -> (Vec<MemberSpecification>, Vec<Result<MemberSpecification>>) {
     let results = mpo
         .member_outputs
         .iter()
-        .map(|x| validate_memberspec(x))
+        .map(|x| validate_memberspec_with_policy(x, policy))
         .collect::<Vec<Result<MemberSpecification>>>();
 
     type BigResult = Result<MemberSpecification, InternalError>;
@@ -194,19 +304,13 @@ fn perform_metadata_partition(
 }
 
 fn err_from_repeat(mpo: &MetadataSpecParserOutput, repeat: &str) -> InternalError {
-    // Find matching token
+    // Find matching tokens; the error always points at the second occurrence
     let hits: Vec<TokenClone> = mpo
         .member_outputs
         .iter()
-        .filter_map(|x| {
-            if x.identifier.as_ref().unwrap().data.data == repeat {
-                Some(TokenClone::from_token_data(
-                    &x.identifier.as_ref().unwrap().data,
-                ))
-            } else {
-                None
-            }
-        })
+        .filter_map(|x| x.identifier.as_ref())
+        .filter(|ident| ident.data.data == repeat)
+        .map(|ident| TokenClone::from_token_data(&ident.data))
         .take(2)
         .collect();
     InternalError::IllegalSpecification {
@@ -217,8 +321,16 @@ fn err_from_repeat(mpo: &MetadataSpecParserOutput, repeat: &str) -> InternalErro
     }
 }
 
+#[cfg(test)]
 pub(crate) fn validate_metadataspec(
     mpo: &MetadataSpecParserOutput,
+) -> Result<Vec<MemberSpecification>, InternalError> {
+    validate_metadataspec_with_policy(mpo, &IdentifierPolicy::default())
+}
+
+pub(crate) fn validate_metadataspec_with_policy(
+    mpo: &MetadataSpecParserOutput,
+    policy: &IdentifierPolicy,
 ) -> Result<Vec<MemberSpecification>, InternalError> {
     let mut errors: Vec<InternalError> = mpo.errors.clone();
 
@@ -229,7 +341,7 @@ pub(crate) fn validate_metadataspec(
         .map(|x| x.identifier.as_ref().unwrap().data.data)
         .collect();
 
-    let (ok_members, errs) = perform_metadata_partition(mpo);
+    let (ok_members, errs) = perform_metadata_partition(mpo, policy);
     errs.iter()
         .for_each(|e| errors.push(e.as_ref().unwrap_err().clone()));
     repeated_identifiers(&members)
@@ -303,6 +415,32 @@ mod tests {
             );
         }
 
+        #[test]
+        fn dotted_ident_default_policy_err() {
+            let ident_text = "gps.lat";
+            let ipo = parsing::get_identifier(ident_text, 0);
+            let ident = validating::validate_identifier(&ipo.identifier.unwrap());
+            pretty_assertions::assert_eq!(
+                ident,
+                Err(InternalError::IllegalSpecification {
+                    offender: TokenClone::new(ident_text, 0),
+                    reason: SpecificationFailure::IllegalCharacters(vec!['.']),
+                })
+            );
+        }
+
+        #[test]
+        fn dotted_ident_extended_policy_ok() {
+            let ident_text = "gps.lat";
+            let policy = IdentifierPolicy {
+                extra_chars: vec!['.'],
+            };
+            let ipo = parsing::get_identifier(ident_text, 0);
+            let ident =
+                validating::validate_identifier_with_policy(&ipo.identifier.unwrap(), &policy);
+            assert_eq!(ident, Ok("gps.lat".to_string()));
+        }
+
         #[test]
         fn invalid_whitespace_in_ident() {
             let ident_text = " foo \r\n\u{85}bar()\t";
@@ -403,6 +541,31 @@ mod tests {
             pretty_assertions::assert_eq!(dtype, Ok(Dtype::Str));
         }
         #[test]
+        fn keyword_round_trip_ok() {
+            let dtypes = [
+                Dtype::Byte,
+                Dtype::UnsignedInteger16,
+                Dtype::UnsignedInteger32,
+                Dtype::UnsignedInteger64,
+                Dtype::SignedInteger8,
+                Dtype::SignedInteger16,
+                Dtype::SignedInteger32,
+                Dtype::SignedInteger64,
+                Dtype::Float32,
+                Dtype::Float64,
+                Dtype::Str,
+                Dtype::Duration,
+                Dtype::Char,
+            ];
+            for expected in dtypes {
+                let text = expected.keyword();
+                let dtype = validating::validate_dtype(&DtypeToken {
+                    data: TokenData::new(text, 0, text.len()),
+                });
+                pretty_assertions::assert_eq!(dtype, Ok(expected));
+            }
+        }
+        #[test]
         fn empty_string() {
             let text = "";
             let dtype = validating::validate_dtype(&DtypeToken {
@@ -549,7 +712,25 @@ mod tests {
             pretty_assertions::assert_eq!(
                 member,
                 Err(InternalError::IllegalSpecification {
-                    offender: TokenClone::new(ident, 0),
+                    offender: TokenClone::new("[]", 11),
+                    reason: SpecificationFailure::IllegalArraySizing,
+                },)
+            );
+        }
+
+        #[test]
+        fn string_fixed_array_span_err() {
+            let text = "foo: string[5]";
+            let mpo = parsing::get_memberspec(text, 0);
+            let member = validating::validate_memberspec(&mpo);
+            pretty_assertions::assert_eq!(
+                member,
+                Err(InternalError::IllegalSpecification {
+                    offender: TokenClone {
+                        data: "[5]".to_string(),
+                        column_start: 11,
+                        column_end: 14,
+                    },
                     reason: SpecificationFailure::IllegalArraySizing,
                 },)
             );
@@ -600,7 +781,7 @@ mod tests {
             let member = validating::validate_memberspec(&mpo);
             pretty_assertions::assert_eq!(
                 member,
-                Err(InternalError::merge(&vec![
+                Err(InternalError::merge(&[
                     InternalError::Parsing {
                         offender: TokenClone::new("", 6),
                         reason: ParsingFailure::UnexpectedEndOfExpression,
@@ -693,9 +874,9 @@ mod tests {
                     },
                     InternalError::IllegalSpecification {
                         offender: TokenClone {
-                            data: "baz".to_string(),
-                            column_start: 23,
-                            column_end: 26,
+                            data: "[5]".to_string(),
+                            column_start: 34,
+                            column_end: 37,
                         },
                         reason: SpecificationFailure::IllegalArraySizing,
                     }
@@ -727,6 +908,54 @@ mod tests {
             );
         }
 
+        #[test]
+        fn metadata_repeated_identifier_whitespace_err() {
+            let text = "foo: u8,  foo : i8";
+            let mpo = parsing::get_metadataspec(text);
+            let spec = validating::validate_metadataspec(&mpo);
+            pretty_assertions::assert_eq!(
+                spec,
+                Err(InternalError::IllegalSpecification {
+                    offender: TokenClone::new("foo", 10),
+                    reason: SpecificationFailure::RepeatedIdentifier {
+                        first: TokenClone::new("foo", 0),
+                    }
+                })
+            );
+        }
+
+        #[test]
+        fn metadata_repeated_identifier_third_occurrence_err() {
+            let text = " foo: u8, bar: u8, foo : i8, foo: u16";
+            let mpo = parsing::get_metadataspec(text);
+            let spec = validating::validate_metadataspec(&mpo);
+            pretty_assertions::assert_eq!(
+                spec,
+                Err(InternalError::IllegalSpecification {
+                    offender: TokenClone::new("foo", 19),
+                    reason: SpecificationFailure::RepeatedIdentifier {
+                        first: TokenClone::new("foo", 1),
+                    }
+                })
+            );
+        }
+
+        #[test]
+        fn metadata_repeated_identifier_missing_identifier_err() {
+            let text = "foo: u8, : u8, foo: i8";
+            let mpo = parsing::get_metadataspec(text);
+            let repeat = InternalError::IllegalSpecification {
+                offender: TokenClone::new("foo", 15),
+                reason: SpecificationFailure::RepeatedIdentifier {
+                    first: TokenClone::new("foo", 0),
+                },
+            };
+            match validating::validate_metadataspec(&mpo) {
+                Err(InternalError::MultipleFailures(errs)) => assert!(errs.contains(&repeat)),
+                other => panic!("Expected multiple failures, got {other:?}"),
+            }
+        }
+
         #[test]
         fn metadata_repeated_identifier_one_wrong_err() {
             let text = "foo: bar, foo: u32";
@@ -1,9 +1,16 @@
-use crate::{error::ElucidatorError, representable::Representable};
+use crate::{
+    designation::PackOverflowPolicy,
+    error::ElucidatorError,
+    member::{char_from_le, Dtype, EnumMapping, Scaling},
+    representable::{can_convert, Representable},
+};
 
 type Result<T, E = ElucidatorError> = std::result::Result<T, E>;
 
 /// Store data values that have been interpreted
+/// With the `serde` feature, values serialize as plain numbers, strings, and arrays.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
 pub enum DataValue {
     Byte(u8),
     UnsignedInteger16(u16),
@@ -16,6 +23,10 @@ pub enum DataValue {
     Float32(f32),
     Float64(f64),
     Str(String),
+    /// Signed duration in nanoseconds
+    Duration(i64),
+    /// A single Unicode scalar value
+    Char(char),
     ByteArray(Vec<u8>),
     UnsignedInteger16Array(Vec<u16>),
     UnsignedInteger32Array(Vec<u32>),
@@ -26,6 +37,7 @@ pub enum DataValue {
     SignedInteger64Array(Vec<i64>),
     Float32Array(Vec<f32>),
     Float64Array(Vec<f64>),
+    DurationArray(Vec<i64>),
 }
 
 impl DataValue {
@@ -41,6 +53,8 @@ impl DataValue {
             Self::SignedInteger64(v) => v.to_le_bytes().to_vec(),
             Self::Float32(v) => v.to_le_bytes().to_vec(),
             Self::Float64(v) => v.to_le_bytes().to_vec(),
+            Self::Duration(v) => v.to_le_bytes().to_vec(),
+            Self::Char(c) => c.as_buffer(),
             Self::Str(s) => s.as_buffer(),
             Self::ByteArray(v) => v.as_buffer(),
             Self::UnsignedInteger16Array(v) => v.as_buffer(),
@@ -52,8 +66,488 @@ impl DataValue {
             Self::SignedInteger64Array(v) => v.as_buffer(),
             Self::Float32Array(v) => v.as_buffer(),
             Self::Float64Array(v) => v.as_buffer(),
+            Self::DurationArray(v) => v.as_buffer(),
         }
     }
+    /// Widen a numeric scalar to `f64`. Returns `None` for strings and arrays.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Byte(v) => Some(*v as f64),
+            Self::UnsignedInteger16(v) => Some(*v as f64),
+            Self::UnsignedInteger32(v) => Some(*v as f64),
+            Self::UnsignedInteger64(v) => Some(*v as f64),
+            Self::SignedInteger8(v) => Some(*v as f64),
+            Self::SignedInteger16(v) => Some(*v as f64),
+            Self::SignedInteger32(v) => Some(*v as f64),
+            Self::SignedInteger64(v) => Some(*v as f64),
+            Self::Float32(v) => Some(*v as f64),
+            Self::Float64(v) => Some(*v),
+            Self::Duration(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+    /// Convert a non-negative `Duration` to a [`std::time::Duration`]. Returns `None` for
+    /// negative durations and for every other variant.
+    /// ```
+    /// use elucidator::value::DataValue;
+    /// use std::time::Duration;
+    ///
+    /// let value = DataValue::Duration(1_500_000_000);
+    /// assert_eq!(value.as_std_duration(), Some(Duration::from_millis(1500)));
+    /// assert_eq!(DataValue::Duration(-1).as_std_duration(), None);
+    /// ```
+    pub fn as_std_duration(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::Duration(v) => u64::try_from(*v).ok().map(std::time::Duration::from_nanos),
+            _ => None,
+        }
+    }
+    /// Name of the stored type, as conversion errors name it: the dtype keyword for scalars,
+    /// such as `u8`, and its array keyword for arrays, such as `u8 array`
+    pub(crate) fn type_name(&self) -> String {
+        match self.array_len() {
+            Some(_) => self.dtype().array_keyword(),
+            None => self.dtype().keyword().to_string(),
+        }
+    }
+}
+
+/// A single numeric element, widened so that any dtype can be range-checked
+#[derive(Clone, Copy)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+macro_rules! impl_numeric_views {
+    ($($scalar:ident, $array:ident, $tt:ty, $dtype:ident, $number:ident);* $(;)?) => {
+        impl DataValue {
+            /// Dtype of the stored scalar or of the array's elements
+            pub(crate) fn dtype(&self) -> Dtype {
+                match self {
+                    Self::Str(_) => Dtype::Str,
+                    Self::Char(_) => Dtype::Char,
+                    $(Self::$scalar(_) | Self::$array(_) => Dtype::$dtype,)*
+                }
+            }
+            /// Number of elements for arrays, `None` for scalars and strings
+            pub(crate) fn array_len(&self) -> Option<usize> {
+                match self {
+                    $(Self::$array(v) => Some(v.len()),)*
+                    _ => None,
+                }
+            }
+            fn numbers(&self) -> Option<Vec<Number>> {
+                match self {
+                    Self::Str(_) | Self::Char(_) => None,
+                    $(
+                        Self::$scalar(v) => Some(vec![Number::$number(*v as _)]),
+                        Self::$array(v) => Some(v.iter().map(|x| Number::$number(*x as _)).collect()),
+                    )*
+                }
+            }
+            fn from_numbers(dtype: &Dtype, numbers: &[Number], array: bool) -> Self {
+                match dtype {
+                    $(
+                        Dtype::$dtype => {
+                            let v: Vec<$tt> = numbers
+                                .iter()
+                                .map(|n| match *n {
+                                    Number::Int(i) => i as $tt,
+                                    Number::Float(f) => f as $tt,
+                                })
+                                .collect();
+                            if array {
+                                Self::$array(v)
+                            } else {
+                                Self::$scalar(v[0])
+                            }
+                        }
+                    )*
+                    Dtype::Str => unreachable!("Strings are not numeric"),
+                    // Chars are not numeric either; only `zeroed` builds one, as a NUL
+                    Dtype::Char => Self::Char('\0'),
+                }
+            }
+        }
+    };
+}
+
+impl_numeric_views! {
+    Byte, ByteArray, u8, Byte, Int;
+    UnsignedInteger16, UnsignedInteger16Array, u16, UnsignedInteger16, Int;
+    UnsignedInteger32, UnsignedInteger32Array, u32, UnsignedInteger32, Int;
+    UnsignedInteger64, UnsignedInteger64Array, u64, UnsignedInteger64, Int;
+    SignedInteger8, SignedInteger8Array, i8, SignedInteger8, Int;
+    SignedInteger16, SignedInteger16Array, i16, SignedInteger16, Int;
+    SignedInteger32, SignedInteger32Array, i32, SignedInteger32, Int;
+    SignedInteger64, SignedInteger64Array, i64, SignedInteger64, Int;
+    Float32, Float32Array, f32, Float32, Float;
+    Float64, Float64Array, f64, Float64, Float;
+    Duration, DurationArray, i64, Duration, Int;
+}
+
+fn integer_range(dtype: &Dtype) -> Option<(i128, i128)> {
+    match dtype {
+        Dtype::Byte => Some((u8::MIN as i128, u8::MAX as i128)),
+        Dtype::UnsignedInteger16 => Some((u16::MIN as i128, u16::MAX as i128)),
+        Dtype::UnsignedInteger32 => Some((u32::MIN as i128, u32::MAX as i128)),
+        Dtype::UnsignedInteger64 => Some((u64::MIN as i128, u64::MAX as i128)),
+        Dtype::SignedInteger8 => Some((i8::MIN as i128, i8::MAX as i128)),
+        Dtype::SignedInteger16 => Some((i16::MIN as i128, i16::MAX as i128)),
+        Dtype::SignedInteger32 => Some((i32::MIN as i128, i32::MAX as i128)),
+        Dtype::SignedInteger64 | Dtype::Duration => Some((i64::MIN as i128, i64::MAX as i128)),
+        _ => None,
+    }
+}
+
+fn coerce_number(
+    n: Number,
+    from: &Dtype,
+    to: &Dtype,
+    policy: PackOverflowPolicy,
+) -> Result<Number> {
+    match (n, integer_range(to)) {
+        (Number::Int(i), Some((min, max))) => {
+            if (min..=max).contains(&i) {
+                Ok(n)
+            } else {
+                match policy {
+                    PackOverflowPolicy::Error => {
+                        ElucidatorError::new_narrowing(from.keyword(), to.keyword())
+                    }
+                    PackOverflowPolicy::Saturate => Ok(Number::Int(i.clamp(min, max))),
+                    // Casting from i128 keeps the low-order bits
+                    PackOverflowPolicy::Wrap => Ok(n),
+                }
+            }
+        }
+        _ if can_convert(from.clone(), to.clone()) => Ok(n),
+        _ => ElucidatorError::new_narrowing(from.keyword(), to.keyword()),
+    }
+}
+
+impl DataValue {
+    /// Capture `value` as the variant of its own dtype and shape, without conversion
+    pub(crate) fn from_representable(value: &dyn Representable) -> Result<Self> {
+        let array = value.is_array();
+        Ok(match value.get_dtype() {
+            Dtype::Byte if array => Self::ByteArray(value.as_vec_u8()?),
+            Dtype::Byte => Self::Byte(value.as_u8()?),
+            Dtype::UnsignedInteger16 if array => Self::UnsignedInteger16Array(value.as_vec_u16()?),
+            Dtype::UnsignedInteger16 => Self::UnsignedInteger16(value.as_u16()?),
+            Dtype::UnsignedInteger32 if array => Self::UnsignedInteger32Array(value.as_vec_u32()?),
+            Dtype::UnsignedInteger32 => Self::UnsignedInteger32(value.as_u32()?),
+            Dtype::UnsignedInteger64 if array => Self::UnsignedInteger64Array(value.as_vec_u64()?),
+            Dtype::UnsignedInteger64 => Self::UnsignedInteger64(value.as_u64()?),
+            Dtype::SignedInteger8 if array => Self::SignedInteger8Array(value.as_vec_i8()?),
+            Dtype::SignedInteger8 => Self::SignedInteger8(value.as_i8()?),
+            Dtype::SignedInteger16 if array => Self::SignedInteger16Array(value.as_vec_i16()?),
+            Dtype::SignedInteger16 => Self::SignedInteger16(value.as_i16()?),
+            Dtype::SignedInteger32 if array => Self::SignedInteger32Array(value.as_vec_i32()?),
+            Dtype::SignedInteger32 => Self::SignedInteger32(value.as_i32()?),
+            Dtype::SignedInteger64 if array => Self::SignedInteger64Array(value.as_vec_i64()?),
+            Dtype::SignedInteger64 => Self::SignedInteger64(value.as_i64()?),
+            Dtype::Float32 if array => Self::Float32Array(value.as_vec_f32()?),
+            Dtype::Float32 => Self::Float32(value.as_f32()?),
+            Dtype::Float64 if array => Self::Float64Array(value.as_vec_f64()?),
+            Dtype::Float64 => Self::Float64(value.as_f64()?),
+            Dtype::Duration if array => Self::DurationArray(value.as_vec_i64()?),
+            Dtype::Duration => Self::Duration(value.as_i64()?),
+            Dtype::Str => Self::Str(value.as_string()?),
+            Dtype::Char => Self::Char(char_from_le(&value.as_buffer())?),
+        })
+    }
+
+    /// Convert to `dtype`, keeping scalars as scalars and arrays as arrays.
+    /// Integers are range-checked against `dtype` and handled according to `policy`; other
+    /// conversions must be lossless per [`can_convert`].
+    pub(crate) fn coerce_to(&self, dtype: &Dtype, policy: PackOverflowPolicy) -> Result<Self> {
+        let from = self.dtype();
+        if let Self::Char(c) = self {
+            // A char converts to text, or to whatever its scalar value as a u32 converts to
+            return match dtype {
+                Dtype::Char => Ok(self.clone()),
+                Dtype::Str => Ok(Self::Str(c.to_string())),
+                _ => Self::UnsignedInteger32(*c as u32).coerce_to(dtype, policy),
+            };
+        }
+        match (self.numbers(), dtype) {
+            (None, Dtype::Str) => Ok(self.clone()),
+            (None, _) | (Some(_), Dtype::Str | Dtype::Char) => {
+                ElucidatorError::new_conversion(&self.type_name(), dtype.keyword())
+            }
+            (Some(numbers), _) => {
+                let numbers = numbers
+                    .into_iter()
+                    .map(|n| coerce_number(n, &from, dtype, policy))
+                    .collect::<Result<Vec<Number>>>()?;
+                Ok(Self::from_numbers(
+                    dtype,
+                    &numbers,
+                    self.array_len().is_some(),
+                ))
+            }
+        }
+    }
+}
+
+/// Convert `value` to a number of `dtype`, failing unless it is exactly representable in an
+/// integer `dtype` or within the range of a float `dtype`.
+fn number_from_f64(value: f64, dtype: &Dtype) -> Result<Number> {
+    match (integer_range(dtype), dtype) {
+        (_, Dtype::Str | Dtype::Char) => {
+            ElucidatorError::new_conversion(Dtype::Float64.keyword(), dtype.keyword())
+        }
+        (Some((min, max)), _) => {
+            let in_range = value.is_finite()
+                && value.fract() == 0.0
+                // `max` rounds up to a power of two for 64-bit types, so compare against the
+                // exclusive bound instead
+                && (min as f64..max as f64 + 1.0).contains(&value);
+            if in_range {
+                Ok(Number::Int(value as i128))
+            } else {
+                ElucidatorError::new_narrowing(Dtype::Float64.keyword(), dtype.keyword())
+            }
+        }
+        (None, Dtype::Float32) if value.is_finite() && value.abs() > f32::MAX as f64 => {
+            ElucidatorError::new_narrowing(Dtype::Float64.keyword(), dtype.keyword())
+        }
+        (None, _) => Ok(Number::Float(value)),
+    }
+}
+
+impl DataValue {
+    /// Build a scalar of the numeric `dtype` from `value`, which must be a whole number within
+    /// range for integer dtypes, or within range for `f32`.
+    /// ```
+    /// use elucidator::{member::Dtype, value::DataValue};
+    ///
+    /// assert_eq!(DataValue::scalar(Dtype::Byte, 255.0), Ok(DataValue::Byte(255)));
+    /// assert!(DataValue::scalar(Dtype::Byte, 256.0).is_err());
+    /// ```
+    pub fn scalar(dtype: Dtype, value: f64) -> Result<Self> {
+        let number = number_from_f64(value, &dtype)?;
+        Ok(Self::from_numbers(&dtype, &[number], false))
+    }
+
+    /// Build an array of the numeric `dtype` from `values`, checking each as with `scalar`.
+    pub fn array(dtype: Dtype, values: &[f64]) -> Result<Self> {
+        let numbers = values
+            .iter()
+            .map(|v| number_from_f64(*v, &dtype))
+            .collect::<Result<Vec<Number>>>()?;
+        Ok(Self::from_numbers(&dtype, &numbers, true))
+    }
+}
+
+/// Options controlling how [`DataValue`]s are rendered as text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplayConfig {
+    /// Number of significant digits shown for floating point values
+    pub float_precision: usize,
+    /// Number of array elements shown before the rest are elided
+    pub array_truncate: usize,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        DisplayConfig {
+            float_precision: 6,
+            array_truncate: 10,
+        }
+    }
+}
+
+/// Format `value` with `precision` significant digits, switching to scientific notation for
+/// very large or small magnitudes and dropping trailing zeros.
+fn format_float(value: f64, precision: usize) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return value.to_string();
+    }
+    let precision = precision.max(1);
+    let exponent = value.abs().log10().floor() as i64;
+    if exponent < -4 || exponent >= precision as i64 {
+        let text = format!("{:.*e}", precision - 1, value);
+        let (mantissa, exponent) = text.split_once('e').unwrap();
+        format!("{}e{exponent}", trim_fraction(mantissa))
+    } else {
+        let decimals = (precision as i64 - 1 - exponent).max(0) as usize;
+        trim_fraction(&format!("{value:.decimals$}")).to_string()
+    }
+}
+
+fn trim_fraction(text: &str) -> &str {
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        text
+    }
+}
+
+fn format_array<T>(items: &[T], cfg: &DisplayConfig, f: impl Fn(&T) -> String) -> String {
+    let mut parts: Vec<String> = items.iter().take(cfg.array_truncate).map(f).collect();
+    if items.len() > cfg.array_truncate {
+        parts.push("...".to_string());
+    }
+    format!("[{}]", parts.join(", "))
+}
+
+impl DataValue {
+    /// Render this value as text according to `cfg`.
+    pub fn display_with(&self, cfg: &DisplayConfig) -> String {
+        let p = cfg.float_precision;
+        match self {
+            Self::Byte(v) => v.to_string(),
+            Self::UnsignedInteger16(v) => v.to_string(),
+            Self::UnsignedInteger32(v) => v.to_string(),
+            Self::UnsignedInteger64(v) => v.to_string(),
+            Self::SignedInteger8(v) => v.to_string(),
+            Self::SignedInteger16(v) => v.to_string(),
+            Self::SignedInteger32(v) => v.to_string(),
+            Self::SignedInteger64(v) => v.to_string(),
+            Self::Float32(v) => format_float(*v as f64, p),
+            Self::Float64(v) => format_float(*v, p),
+            Self::Str(v) => v.clone(),
+            Self::Duration(v) => v.to_string(),
+            Self::Char(v) => v.to_string(),
+            Self::ByteArray(v) => format_array(v, cfg, u8::to_string),
+            Self::UnsignedInteger16Array(v) => format_array(v, cfg, u16::to_string),
+            Self::UnsignedInteger32Array(v) => format_array(v, cfg, u32::to_string),
+            Self::UnsignedInteger64Array(v) => format_array(v, cfg, u64::to_string),
+            Self::SignedInteger8Array(v) => format_array(v, cfg, i8::to_string),
+            Self::SignedInteger16Array(v) => format_array(v, cfg, i16::to_string),
+            Self::SignedInteger32Array(v) => format_array(v, cfg, i32::to_string),
+            Self::SignedInteger64Array(v) => format_array(v, cfg, i64::to_string),
+            Self::Float32Array(v) => format_array(v, cfg, |x| format_float(*x as f64, p)),
+            Self::Float64Array(v) => format_array(v, cfg, |x| format_float(*x, p)),
+            Self::DurationArray(v) => format_array(v, cfg, i64::to_string),
+        }
+    }
+}
+
+impl std::fmt::Display for DataValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_with(&DisplayConfig::default()))
+    }
+}
+
+impl DataValue {
+    /// A zero scalar or an empty array of the numeric `dtype`.
+    pub(crate) fn zeroed(dtype: &Dtype, array: bool) -> Self {
+        let numbers: &[Number] = if array { &[] } else { &[Number::Int(0)] };
+        Self::from_numbers(dtype, numbers, array)
+    }
+
+    /// Replace an integer scalar with its variant name in `mapping` as a `Str`. Values the
+    /// mapping does not name are returned unchanged.
+    pub(crate) fn named(self, mapping: &EnumMapping) -> Self {
+        let name = match self.numbers().as_deref() {
+            Some([Number::Int(i)]) if self.array_len().is_none() => mapping.name_of(*i),
+            _ => None,
+        };
+        match name {
+            Some(name) => Self::Str(name.to_string()),
+            None => self,
+        }
+    }
+
+    /// Apply a fixed-point `scaling` to a raw numeric value, producing `f64` values.
+    pub(crate) fn scaled(&self, scaling: &Scaling) -> Self {
+        let factor = scaling.factor();
+        let Some(numbers) = self.numbers() else {
+            return self.clone();
+        };
+        let scaled: Vec<Number> = numbers
+            .into_iter()
+            .map(|n| match n {
+                Number::Int(i) => Number::Float(i as f64 * factor),
+                Number::Float(f) => Number::Float(f * factor),
+            })
+            .collect();
+        Self::from_numbers(&Dtype::Float64, &scaled, self.array_len().is_some())
+    }
+
+    /// Invert `scaled`, rounding to the nearest raw integer of `dtype`. Raw values out of range
+    /// for `dtype` are handled according to `policy`.
+    pub(crate) fn unscaled(
+        &self,
+        scaling: &Scaling,
+        dtype: &Dtype,
+        policy: PackOverflowPolicy,
+    ) -> Result<Self> {
+        let factor = scaling.factor();
+        let Some(numbers) = self.numbers() else {
+            return ElucidatorError::new_conversion(&self.type_name(), dtype.keyword());
+        };
+        let raw = numbers
+            .into_iter()
+            .map(|n| {
+                let f = match n {
+                    Number::Int(i) => i as f64 / factor,
+                    Number::Float(f) => f / factor,
+                };
+                if f.is_finite() {
+                    Ok(Number::Int(f.round() as i128))
+                } else {
+                    ElucidatorError::new_conversion("non-finite float", dtype.keyword())
+                }
+            })
+            .collect::<Result<Vec<Number>>>()?
+            .into_iter()
+            .map(|n| coerce_number(n, &Dtype::SignedInteger64, dtype, policy))
+            .collect::<Result<Vec<Number>>>()?;
+        Ok(Self::from_numbers(dtype, &raw, self.array_len().is_some()))
+    }
+}
+
+/// Extract a Rust value from a [`DataValue`] holding exactly that type.
+/// No widening is performed; a mismatched variant is a conversion error.
+pub trait FromDataValue: Sized {
+    fn from_data_value(value: &DataValue) -> Result<Self>;
+}
+
+macro_rules! impl_from_data_value {
+    ($($variant:ident => $tt:ty, $dtype:ident, $name:ident);* $(;)?) => {
+        $(
+            impl FromDataValue for $tt {
+                fn from_data_value(value: &DataValue) -> Result<Self> {
+                    match value {
+                        DataValue::$variant(v) => Ok(v.clone()),
+                        other => {
+                            ElucidatorError::new_conversion(&other.type_name(), &Dtype::$dtype.$name())
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_data_value! {
+    Byte => u8, Byte, keyword;
+    UnsignedInteger16 => u16, UnsignedInteger16, keyword;
+    UnsignedInteger32 => u32, UnsignedInteger32, keyword;
+    UnsignedInteger64 => u64, UnsignedInteger64, keyword;
+    SignedInteger8 => i8, SignedInteger8, keyword;
+    SignedInteger16 => i16, SignedInteger16, keyword;
+    SignedInteger32 => i32, SignedInteger32, keyword;
+    SignedInteger64 => i64, SignedInteger64, keyword;
+    Float32 => f32, Float32, keyword;
+    Float64 => f64, Float64, keyword;
+    Str => String, Str, keyword;
+    Char => char, Char, keyword;
+    ByteArray => Vec<u8>, Byte, array_keyword;
+    UnsignedInteger16Array => Vec<u16>, UnsignedInteger16, array_keyword;
+    UnsignedInteger32Array => Vec<u32>, UnsignedInteger32, array_keyword;
+    UnsignedInteger64Array => Vec<u64>, UnsignedInteger64, array_keyword;
+    SignedInteger8Array => Vec<i8>, SignedInteger8, array_keyword;
+    SignedInteger16Array => Vec<i16>, SignedInteger16, array_keyword;
+    SignedInteger32Array => Vec<i32>, SignedInteger32, array_keyword;
+    SignedInteger64Array => Vec<i64>, SignedInteger64, array_keyword;
+    Float32Array => Vec<f32>, Float32, array_keyword;
+    Float64Array => Vec<f64>, Float64, array_keyword;
 }
 
 pub(crate) trait LeBufferRead: Sized {
@@ -99,7 +593,8 @@ macro_rules! impl_le_bufread {
                     }
                 }
                 fn bytes_needed(n: usize) -> usize {
-                    std::mem::size_of::<$tt>() * n
+                    // Saturate so that an absurd count fails as too few bytes rather than panicking
+                    std::mem::size_of::<$tt>().checked_mul(n).unwrap_or(usize::MAX)
                 }
             }
         )*
@@ -185,4 +680,126 @@ mod test {
     fn test_vec_round_trips() {
         vec_round_trip!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
     }
+
+    mod display {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn float_precision_ok() {
+            let value = DataValue::Float64(std::f64::consts::PI);
+            let cfg = DisplayConfig {
+                float_precision: 3,
+                ..Default::default()
+            };
+            assert_eq!(value.display_with(&cfg), "3.14");
+            assert_eq!(value.to_string(), "3.14159");
+        }
+
+        #[test]
+        fn float_magnitudes_ok() {
+            let cfg = DisplayConfig::default();
+            assert_eq!(DataValue::Float64(1234.5).display_with(&cfg), "1234.5");
+            assert_eq!(DataValue::Float64(1.0).display_with(&cfg), "1");
+            assert_eq!(DataValue::Float64(-2.5e-7).display_with(&cfg), "-2.5e-7");
+            assert_eq!(DataValue::Float64(6.02e23).display_with(&cfg), "6.02e23");
+            assert_eq!(DataValue::Float32(0.1).display_with(&cfg), "0.1");
+        }
+
+        #[test]
+        fn array_truncate_ok() {
+            let value = DataValue::UnsignedInteger32Array((1..=5).collect());
+            let cfg = DisplayConfig {
+                array_truncate: 3,
+                ..Default::default()
+            };
+            assert_eq!(value.display_with(&cfg), "[1, 2, 3, ...]");
+            assert_eq!(value.to_string(), "[1, 2, 3, 4, 5]");
+        }
+
+        #[test]
+        fn float_array_uses_precision_ok() {
+            let value = DataValue::Float64Array(vec![1.23456789, 2.0]);
+            let cfg = DisplayConfig {
+                float_precision: 2,
+                array_truncate: 1,
+            };
+            assert_eq!(value.display_with(&cfg), "[1.2, ...]");
+        }
+    }
+
+    mod construct {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn byte_max_ok() {
+            assert_eq!(
+                DataValue::scalar(Dtype::Byte, 255.0),
+                Ok(DataValue::Byte(255))
+            );
+        }
+
+        #[test]
+        fn byte_overflow_err() {
+            assert_eq!(
+                DataValue::scalar(Dtype::Byte, 256.0),
+                ElucidatorError::new_narrowing("f64", "u8")
+            );
+        }
+
+        #[test]
+        fn u64_rounded_max_err() {
+            assert!(DataValue::scalar(Dtype::UnsignedInteger64, u64::MAX as f64).is_err());
+        }
+
+        #[test]
+        fn fractional_integer_err() {
+            assert!(DataValue::scalar(Dtype::SignedInteger32, 1.5).is_err());
+        }
+
+        #[test]
+        fn string_err() {
+            assert_eq!(
+                DataValue::scalar(Dtype::Str, 1.0),
+                ElucidatorError::new_conversion("f64", "string")
+            );
+        }
+
+        #[test]
+        fn float32_array_ok() {
+            assert_eq!(
+                DataValue::array(Dtype::Float32, &[0.5, -2.0, 1e3]),
+                Ok(DataValue::Float32Array(vec![0.5, -2.0, 1e3]))
+            );
+        }
+
+        #[test]
+        fn empty_array_ok() {
+            assert_eq!(
+                DataValue::array(Dtype::UnsignedInteger16, &[]),
+                Ok(DataValue::UnsignedInteger16Array(vec![]))
+            );
+        }
+    }
+
+    #[test]
+    fn type_names_match_representable() {
+        let value = DataValue::ByteArray(vec![1, 2]);
+        let expected = ElucidatorError::new_conversion("u8 array", "u16 array");
+        assert_eq!(Vec::<u16>::from_data_value(&value), expected);
+        assert_eq!(
+            value.coerce_to(&Dtype::Str, PackOverflowPolicy::Error),
+            ElucidatorError::new_conversion("u8 array", "string")
+        );
+        // Representable errors name arrays the same way
+        assert_eq!(
+            vec![300u16].as_vec_u8(),
+            ElucidatorError::new_narrowing("u16 array", "u8 array")
+        );
+        assert_eq!(
+            String::from_data_value(&DataValue::Float64(0.5)),
+            ElucidatorError::new_conversion("f64", "string")
+        );
+    }
 }
@@ -0,0 +1,267 @@
+//! Decoding numeric arrays as slices borrowed from the record buffer, enabled by the `zerocopy`
+//! feature.
+use crate::{
+    designation::{
+        get_array_from_buf, get_singleton_from_buf, read_dynamic_len, DesignationSpecification,
+        InterpretOptions,
+    },
+    error::ElucidatorError,
+    member::{Dtype, Sizing},
+    util::Buffer,
+    value::DataValue,
+};
+use std::collections::HashMap;
+
+type Result<T, E = ElucidatorError> = std::result::Result<T, E>;
+
+/// A member decoded by [`DesignationSpecification::interpret_zerocopy`].
+/// The array variants borrow their elements straight from the record buffer, so the value
+/// cannot outlive it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReprSlice<'a> {
+    /// A singleton or string member, decoded as `interpret_enum` would
+    Scalar(DataValue),
+    /// An array copied out of the buffer as `interpret_enum` would, because its elements are
+    /// misaligned in the buffer, the target is big-endian, or the member is scaled
+    Owned(DataValue),
+    ByteArray(&'a [u8]),
+    UnsignedInteger16Array(&'a [u16]),
+    UnsignedInteger32Array(&'a [u32]),
+    UnsignedInteger64Array(&'a [u64]),
+    SignedInteger8Array(&'a [i8]),
+    SignedInteger16Array(&'a [i16]),
+    SignedInteger32Array(&'a [i32]),
+    SignedInteger64Array(&'a [i64]),
+    Float32Array(&'a [f32]),
+    Float64Array(&'a [f64]),
+    /// Signed durations in nanoseconds
+    DurationArray(&'a [i64]),
+}
+
+impl ReprSlice<'_> {
+    /// Whether the value borrows from the record buffer
+    pub fn is_borrowed(&self) -> bool {
+        !matches!(self, Self::Scalar(_) | Self::Owned(_))
+    }
+
+    /// Copy the value into the `DataValue` that `interpret_enum` produces for the member
+    pub fn to_data_value(&self) -> DataValue {
+        match self {
+            Self::Scalar(v) | Self::Owned(v) => v.clone(),
+            Self::ByteArray(v) => DataValue::ByteArray(v.to_vec()),
+            Self::UnsignedInteger16Array(v) => DataValue::UnsignedInteger16Array(v.to_vec()),
+            Self::UnsignedInteger32Array(v) => DataValue::UnsignedInteger32Array(v.to_vec()),
+            Self::UnsignedInteger64Array(v) => DataValue::UnsignedInteger64Array(v.to_vec()),
+            Self::SignedInteger8Array(v) => DataValue::SignedInteger8Array(v.to_vec()),
+            Self::SignedInteger16Array(v) => DataValue::SignedInteger16Array(v.to_vec()),
+            Self::SignedInteger32Array(v) => DataValue::SignedInteger32Array(v.to_vec()),
+            Self::SignedInteger64Array(v) => DataValue::SignedInteger64Array(v.to_vec()),
+            Self::Float32Array(v) => DataValue::Float32Array(v.to_vec()),
+            Self::Float64Array(v) => DataValue::Float64Array(v.to_vec()),
+            Self::DurationArray(v) => DataValue::DurationArray(v.to_vec()),
+        }
+    }
+}
+
+/// View little-endian `bytes` as a slice of `T`, if the target is little-endian and the bytes
+/// are aligned for `T`
+fn borrow<T: bytemuck::Pod>(bytes: &[u8]) -> Option<&[T]> {
+    if cfg!(target_endian = "little") {
+        bytemuck::try_cast_slice(bytes).ok()
+    } else {
+        None
+    }
+}
+
+/// View the elements of an array member of dtype `dt` without copying them, if possible
+fn borrow_array<'a>(bytes: &'a [u8], dt: &Dtype) -> Option<ReprSlice<'a>> {
+    Some(match dt {
+        Dtype::Byte => ReprSlice::ByteArray(bytes),
+        Dtype::UnsignedInteger16 => ReprSlice::UnsignedInteger16Array(borrow(bytes)?),
+        Dtype::UnsignedInteger32 => ReprSlice::UnsignedInteger32Array(borrow(bytes)?),
+        Dtype::UnsignedInteger64 => ReprSlice::UnsignedInteger64Array(borrow(bytes)?),
+        Dtype::SignedInteger8 => ReprSlice::SignedInteger8Array(bytemuck::cast_slice(bytes)),
+        Dtype::SignedInteger16 => ReprSlice::SignedInteger16Array(borrow(bytes)?),
+        Dtype::SignedInteger32 => ReprSlice::SignedInteger32Array(borrow(bytes)?),
+        Dtype::SignedInteger64 => ReprSlice::SignedInteger64Array(borrow(bytes)?),
+        Dtype::Float32 => ReprSlice::Float32Array(borrow(bytes)?),
+        Dtype::Float64 => ReprSlice::Float64Array(borrow(bytes)?),
+        Dtype::Duration => ReprSlice::DurationArray(borrow(bytes)?),
+        _ => return None,
+    })
+}
+
+impl DesignationSpecification {
+    /// Interpret a buffer as with `interpret_enum`, except that numeric arrays are returned as
+    /// slices borrowed from `buffer` rather than copied into vectors. An array falls back to an
+    /// owned copy when its elements are not aligned for their type within `buffer`, when the
+    /// target is big-endian, or when the member has fixed-point scaling.
+    /// ```
+    /// use elucidator::{designation::DesignationSpecification, zerocopy::ReprSlice};
+    ///
+    /// let spec = DesignationSpecification::from_text("xs: u8[]").unwrap();
+    /// let buffer = [3, 0, 0, 0, 0, 0, 0, 0, 7, 8, 9];
+    /// let values = spec.interpret_zerocopy(&buffer).unwrap();
+    /// assert_eq!(values["xs"], ReprSlice::ByteArray(&buffer[8..]));
+    /// ```
+    pub fn interpret_zerocopy<'a>(&self, buffer: &'a [u8]) -> Result<HashMap<&str, ReprSlice<'a>>> {
+        self.check_not_empty(buffer)?;
+        let options = InterpretOptions::default();
+        let mut map = HashMap::new();
+        let mut buf = Buffer::new(buffer);
+        for member in self.members() {
+            let n = match member.sizing {
+                Sizing::Singleton => {
+                    let value = get_singleton_from_buf(&mut buf, member, &options)?;
+                    map.insert(
+                        member.identifier.as_str(),
+                        ReprSlice::Scalar(member.apply_scaling(value)),
+                    );
+                    continue;
+                }
+                Sizing::Fixed(n) => n as usize,
+                Sizing::Dynamic => read_dynamic_len(&mut buf, member, None)?,
+            };
+            let value = match member.dtype.get_size() {
+                Some(size) if member.scaling.is_none() => {
+                    let len =
+                        size.checked_mul(n)
+                            .ok_or_else(|| ElucidatorError::ArrayTooLarge {
+                                member: member.identifier.clone(),
+                                requested: n,
+                                limit: buf.remaining() / size,
+                            })?;
+                    let bytes = buf.grab_slice(len)?;
+                    match borrow_array(bytes, &member.dtype) {
+                        Some(value) => value,
+                        None => ReprSlice::Owned(get_array_from_buf(
+                            &mut Buffer::new(bytes),
+                            &member.dtype,
+                            n,
+                        )?),
+                    }
+                }
+                _ => ReprSlice::Owned(member.apply_scaling(get_array_from_buf(
+                    &mut buf,
+                    &member.dtype,
+                    n,
+                )?)),
+            };
+            map.insert(member.identifier.as_str(), value);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Copy `bytes` into storage aligned for any element type, starting `shift` bytes in
+    fn aligned(bytes: &[u8], shift: usize) -> Vec<u64> {
+        let mut storage = vec![0u64; (bytes.len() + shift).div_ceil(8)];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut storage)[shift..shift + bytes.len()]
+            .copy_from_slice(bytes);
+        storage
+    }
+
+    fn spec_and_record() -> (DesignationSpecification, Vec<u8>) {
+        let spec = DesignationSpecification::from_text(
+            "xs: f64[3], id: u32, ys: i16[], name: string, bytes: u8[2], zs: u64[]",
+        )
+        .unwrap();
+        let record = spec
+            .pack(&HashMap::from([
+                ("xs", DataValue::Float64Array(vec![1.5, -2.0, f64::MAX])),
+                ("id", DataValue::UnsignedInteger32(7)),
+                ("ys", DataValue::SignedInteger16Array(vec![-1, 2, 300])),
+                ("name", DataValue::Str("probe".to_string())),
+                ("bytes", DataValue::ByteArray(vec![4, 5])),
+                ("zs", DataValue::UnsignedInteger64Array(vec![])),
+            ]))
+            .unwrap();
+        (spec, record)
+    }
+
+    fn assert_matches_interpret_enum(spec: &DesignationSpecification, buffer: &[u8]) {
+        let copied = spec.interpret_enum(buffer).unwrap();
+        let borrowed: HashMap<&str, DataValue> = spec
+            .interpret_zerocopy(buffer)
+            .unwrap()
+            .iter()
+            .map(|(k, v)| (*k, v.to_data_value()))
+            .collect();
+        assert_eq!(borrowed, copied);
+    }
+
+    #[test]
+    fn aligned_arrays_borrowed() {
+        let (spec, record) = spec_and_record();
+        let storage = aligned(&record, 0);
+        let buffer = &bytemuck::cast_slice::<u64, u8>(&storage)[..record.len()];
+        assert_matches_interpret_enum(&spec, buffer);
+
+        let values = spec.interpret_zerocopy(buffer).unwrap();
+        assert_eq!(
+            values["xs"],
+            ReprSlice::Float64Array(&[1.5, -2.0, f64::MAX])
+        );
+        assert_eq!(
+            values["id"],
+            ReprSlice::Scalar(DataValue::UnsignedInteger32(7))
+        );
+        // ys starts 36 bytes in, which is aligned for i16
+        assert_eq!(values["ys"], ReprSlice::SignedInteger16Array(&[-1, 2, 300]));
+        assert_eq!(
+            values["name"],
+            ReprSlice::Scalar(DataValue::Str("probe".to_string()))
+        );
+        assert_eq!(values["bytes"], ReprSlice::ByteArray(&[4, 5]));
+    }
+
+    #[test]
+    fn misaligned_arrays_owned() {
+        let (spec, record) = spec_and_record();
+        let storage = aligned(&record, 1);
+        let buffer = &bytemuck::cast_slice::<u64, u8>(&storage)[1..record.len() + 1];
+        assert_matches_interpret_enum(&spec, buffer);
+
+        let values = spec.interpret_zerocopy(buffer).unwrap();
+        assert_eq!(
+            values["xs"],
+            ReprSlice::Owned(DataValue::Float64Array(vec![1.5, -2.0, f64::MAX]))
+        );
+        assert!(values["bytes"].is_borrowed());
+    }
+
+    #[test]
+    fn scaled_arrays_owned() {
+        let spec = DesignationSpecification::from_text("t: i16[2] @ q8.8").unwrap();
+        let record = [2, 0, 4, 0];
+        assert_matches_interpret_enum(&spec, &record);
+        assert!(!spec.interpret_zerocopy(&record).unwrap()["t"].is_borrowed());
+    }
+
+    #[test]
+    fn short_buffer_err() {
+        let (spec, record) = spec_and_record();
+        assert_eq!(
+            spec.interpret_zerocopy(&record[..record.len() - 1]).err(),
+            spec.interpret_enum(&record[..record.len() - 1]).err()
+        );
+    }
+
+    #[test]
+    fn fixed_size_overflow_err() {
+        let spec = DesignationSpecification::from_text("xs: u64[2305843009213693952]").unwrap();
+        assert_eq!(
+            spec.interpret_zerocopy(&[0; 16]),
+            Err(ElucidatorError::ArrayTooLarge {
+                member: "xs".to_string(),
+                requested: 1 << 61,
+                limit: 2,
+            })
+        );
+    }
+}
@@ -37,11 +37,35 @@ impl fmt::Display for Primitive {
     }
 }
 
+// The `Dtype` variant for a primitive, so generated error messages use `Dtype::keyword`
+// rather than spelling out the names themselves.
+fn dtype_variant(primitive: &str) -> &'static str {
+    match primitive {
+        "u8" => "Dtype::Byte",
+        "u16" => "Dtype::UnsignedInteger16",
+        "u32" => "Dtype::UnsignedInteger32",
+        "u64" => "Dtype::UnsignedInteger64",
+        "i8" => "Dtype::SignedInteger8",
+        "i16" => "Dtype::SignedInteger16",
+        "i32" => "Dtype::SignedInteger32",
+        "i64" => "Dtype::SignedInteger64",
+        "f32" => "Dtype::Float32",
+        "f64" => "Dtype::Float64",
+        _ => {
+            todo!("Need to add get_dtype_return for {}", primitive)
+        }
+    }
+}
+
 // Only usable for primitives!! Specifically, u, i, f types. NO chars or bools.
 fn attempt_convert(source: &str, target: &str) -> String {
+    let narrow = format!(
+        "crate::ElucidatorError::new_narrowing({}.keyword(), {}.keyword())",
+        dtype_variant(source),
+        dtype_variant(target)
+    );
     let source = Primitive::from(source);
     let target = Primitive::from(target);
-    let narrow = format!("crate::ElucidatorError::new_narrowing(\"{source}\", \"{target}\")");
     let ok = format!("Ok( *self as {})", target.as_string());
 
     let return_value = if source == target {
@@ -82,10 +106,13 @@ fn attempt_convert(source: &str, target: &str) -> String {
 
 // Only usable for primitives!! Specifically, u, i, f types. NO chars or bools.
 fn attempt_convert_vec(source: &str, target: &str) -> String {
+    let narrow = format!(
+        "crate::ElucidatorError::new_narrowing(&{}.array_keyword(), &{}.array_keyword())",
+        dtype_variant(source),
+        dtype_variant(target)
+    );
     let source = Primitive::from(source);
     let target = Primitive::from(target);
-    let narrow =
-        format!("crate::ElucidatorError::new_narrowing(\"{source} array\", \"{target} array\")");
     let ok = format!(
         "Ok(self.iter().map(|x| *x as {}).collect())",
         target.as_string()
@@ -146,25 +173,10 @@ pub fn representable_primitive_impl(item: TokenStream) -> TokenStream {
     let is_signed = this_primitive.is_signed();
     let is_integer = this_primitive.is_integer();
     let is_floating = this_primitive.is_float();
-    let get_dtype_return = match string_repr.as_str() {
-        "u8" => quote! { Dtype::Byte},
-        "u16" => quote! { Dtype::UnsignedInteger16 },
-        "u32" => quote! { Dtype::UnsignedInteger32 },
-        "u64" => quote! { Dtype::UnsignedInteger64 },
-        "i8" => quote! { Dtype::SignedInteger8},
-        "i16" => quote! { Dtype::SignedInteger16 },
-        "i32" => quote! { Dtype::SignedInteger32 },
-        "i64" => quote! { Dtype::SignedInteger64 },
-        "f32" => quote! { Dtype::Float32 },
-        "f64" => quote! { Dtype::Float64 },
-        _ => {
-            todo!("Need to add get_dtype_return for {}", string_repr)
-        }
-    }
-    .to_token_stream();
+    let get_dtype_return: proc_macro2::TokenStream = dtype_variant(&string_repr).parse().unwrap();
 
     let buffer_conversion = quote! {
-        self.to_le_bytes().iter().map(|x| *x).collect()
+        out.extend_from_slice(&self.to_le_bytes());
     }
     .to_token_stream();
 
@@ -181,8 +193,10 @@ pub fn representable_primitive_impl(item: TokenStream) -> TokenStream {
     let vec_conversion_text = target_types.iter()
         .map(|x| format!(
             "fn as_vec_{x}(&self) -> std::result::Result<std::vec::Vec<std::primitive::{x}>, crate::ElucidatorError> {{
-               crate::ElucidatorError::new_conversion(\"{string_repr}\", \"{x} array\")
-            }}\n"
+               crate::ElucidatorError::new_conversion({}.keyword(), &{}.array_keyword())
+            }}\n",
+            dtype_variant(&string_repr),
+            dtype_variant(x)
         ))
         .collect::<Vec<String>>()
         .join("\n");
@@ -196,10 +210,10 @@ pub fn representable_primitive_impl(item: TokenStream) -> TokenStream {
             fn is_integer(&self) -> std::primitive::bool { #is_integer }
             fn is_floating(&self) -> std::primitive::bool { #is_floating }
             fn get_dtype(&self) -> Dtype { #get_dtype_return }
-            fn as_buffer(&self) -> std::vec::Vec<u8> { #buffer_conversion }
+            fn as_buffer_into(&self, out: &mut std::vec::Vec<u8>) { #buffer_conversion }
             #conversion_functions
             fn as_string(&self) -> std::result::Result<std::string::String, crate::ElucidatorError> {
-                crate::ElucidatorError::new_conversion(#string_repr, "string")
+                crate::ElucidatorError::new_conversion(#get_dtype_return.keyword(), Dtype::Str.keyword())
             }
             #vec_conversion_functions
         }
@@ -219,7 +233,6 @@ pub fn representable_vec_impl(item: TokenStream) -> TokenStream {
     let last_ident = &in_path.path.segments.iter().last().unwrap().ident;
     // println!("{last_ident:#?}");
     let string_repr = format!("{last_ident}");
-    let string_repr_arr = format!("{last_ident} array");
 
     let this_primitive = Primitive::from(string_repr.as_str());
 
@@ -228,31 +241,13 @@ pub fn representable_vec_impl(item: TokenStream) -> TokenStream {
     let is_signed = this_primitive.is_signed();
     let is_integer = this_primitive.is_integer();
     let is_floating = this_primitive.is_float();
-    let get_dtype_return = match string_repr.as_str() {
-        "u8" => quote! { Dtype::Byte},
-        "u16" => quote! { Dtype::UnsignedInteger16 },
-        "u32" => quote! { Dtype::UnsignedInteger32 },
-        "u64" => quote! { Dtype::UnsignedInteger64 },
-        "i8" => quote! { Dtype::SignedInteger8},
-        "i16" => quote! { Dtype::SignedInteger16 },
-        "i32" => quote! { Dtype::SignedInteger32 },
-        "i64" => quote! { Dtype::SignedInteger64 },
-        "f32" => quote! { Dtype::Float32 },
-        "f64" => quote! { Dtype::Float64 },
-        _ => {
-            todo!("Need to add get_dtype_return for {}", string_repr)
-        }
-    }
-    .to_token_stream();
+    let get_dtype_return: proc_macro2::TokenStream = dtype_variant(&string_repr).parse().unwrap();
 
     let buffer_conversion = quote! {
-        let length = self.len() * std::mem::size_of::<#last_ident>();
-        let mut buffer: std::vec::Vec<u8> = std::vec::Vec::with_capacity(length);
+        out.reserve(self.len() * std::mem::size_of::<#last_ident>());
         for item in self {
-            let mut item_buffer = item.as_buffer();
-            buffer.append(&mut item_buffer);
+            out.extend_from_slice(&item.to_le_bytes());
         }
-        buffer
     }
     .to_token_stream();
 
@@ -265,9 +260,11 @@ pub fn representable_vec_impl(item: TokenStream) -> TokenStream {
         .map(|x| {
             format!(
         "fn as_{x}(&self) -> std::result::Result<std::primitive::{x}, crate::ElucidatorError> {{
-           crate::ElucidatorError::new_conversion(\"{string_repr} array\", \"{x}\")
-        }}\n"
-    )
+           crate::ElucidatorError::new_conversion(&{}.array_keyword(), {}.keyword())
+        }}\n",
+                dtype_variant(&string_repr),
+                dtype_variant(x)
+            )
         })
         .collect::<Vec<String>>()
         .join("\n");
@@ -287,10 +284,10 @@ pub fn representable_vec_impl(item: TokenStream) -> TokenStream {
             fn is_integer(&self) -> std::primitive::bool { #is_integer }
             fn is_floating(&self) -> std::primitive::bool { #is_floating }
             fn get_dtype(&self) -> Dtype { #get_dtype_return }
-            fn as_buffer(&self) -> std::vec::Vec<u8> { #buffer_conversion }
+            fn as_buffer_into(&self, out: &mut std::vec::Vec<u8>) { #buffer_conversion }
             #conversion_functions
             fn as_string(&self) -> std::result::Result<std::string::String, crate::ElucidatorError> {
-                crate::ElucidatorError::new_conversion(#string_repr_arr, "string")
+                crate::ElucidatorError::new_conversion(&#get_dtype_return.array_keyword(), Dtype::Str.keyword())
             }
             #vec_conversion_functions
         }
@@ -1,4 +1,8 @@
-use elucidator::error::ElucidatorError;
+use elucidator::{
+    error::ElucidatorError,
+    member::{Dtype, Sizing},
+    value::DataValue,
+};
 
 use elucidator_db::{
     backends::rtree::RTreeDatabase,
@@ -82,11 +86,18 @@ enum ApiError {
         id: u32,
         handle_type: String,
     },
-    #[allow(dead_code)]
     DesignationNotFound {
         session: u32,
         designation: String,
     },
+    MemberNotFound {
+        designation: String,
+        member: String,
+    },
+    MemberNotScalar {
+        designation: String,
+        member: String,
+    },
 }
 
 impl fmt::Display for ApiError {
@@ -117,6 +128,36 @@ impl fmt::Display for ApiError {
                     "Cannot find designation {designation} in session {session}"
                 )
             }
+            Self::MemberNotFound {
+                designation,
+                member,
+            } => {
+                write!(f, "Designation {designation} has no member {member}")
+            }
+            Self::MemberNotScalar {
+                designation,
+                member,
+            } => {
+                write!(
+                    f,
+                    "Member {member} of designation {designation} is not a numeric scalar"
+                )
+            }
+        }
+    }
+}
+
+impl ApiError {
+    /// Numeric code reported by `get_error_code`
+    fn code(&self) -> u32 {
+        match self {
+            Self::Eluci(e) => e.code(),
+            Self::Database(error::DatabaseError::ElucidatorError { reason }) => reason.code(),
+            Self::Database(_) => 1000,
+            Self::HandleNotFound { .. } => 1001,
+            Self::DesignationNotFound { .. } => 1002,
+            Self::MemberNotFound { .. } => 1003,
+            Self::MemberNotScalar { .. } => 1004,
         }
     }
 }
@@ -200,7 +241,7 @@ impl BufNode {
     }
 }
 
-unsafe fn blobs_into_bufnode(blobs: &mut Vec<&Vec<u8>>) -> *mut BufNode {
+unsafe fn blobs_into_bufnode(blobs: &[Vec<u8>]) -> *mut BufNode {
     let mut prev: *mut BufNode = std::ptr::null_mut::<BufNode>();
     let mut bf = BufNode::empty();
     for blob in blobs.iter().rev() {
@@ -213,7 +254,6 @@ unsafe fn blobs_into_bufnode(blobs: &mut Vec<&Vec<u8>>) -> *mut BufNode {
         bf = BufNode::from(p, n, next);
         prev = bf;
     }
-    blobs.truncate(0);
     bf
 }
 
@@ -236,8 +276,7 @@ pub extern "C" fn fetch_sample_blob() -> *mut BufNode {
     let b = vec![2, 3, 5, 7, 11, 13];
     let c = vec![0, 27, 6];
 
-    let mut sample: Vec<&Vec<u8>> = vec![&a, &b, &c];
-    unsafe { blobs_into_bufnode(&mut sample) }
+    unsafe { blobs_into_bufnode(&[a, b, c]) }
 }
 
 /// Instantiate a new Elucidator session. Individual sessions will have
@@ -274,6 +313,34 @@ pub extern "C" fn get_error_string(eh: *const ErrorHandle) -> *mut c_char {
     }
 }
 
+/// Get a stable numeric code for the error behind the provided handle, for callers that
+/// should not parse the string from get_error_string. Returns 0 if the handle cannot be
+/// found.
+///
+/// Codes 1 through 999 are errors from the core library, as documented on
+/// `ElucidatorError::code` (for example 1 is a conversion error, 2 a narrowing error, 3 a
+/// buffer of the wrong size, and 5 an invalid specification). Database errors that wrap a
+/// core library error report that error's code. Codes from 1000 are specific to this API:
+///
+/// | code | meaning                                  |
+/// |------|------------------------------------------|
+/// | 1000 | other database error                     |
+/// | 1001 | handle not found                         |
+/// | 1002 | designation not found in the session     |
+/// | 1003 | member not found in the designation      |
+/// | 1004 | member is not a numeric scalar           |
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn get_error_code(eh: *const ErrorHandle) -> u32 {
+    unsafe {
+        ERROR_MAP
+            .read()
+            .unwrap()
+            .get(&*eh)
+            .map_or(0, ApiError::code)
+    }
+}
+
 /// Register the given name and specification to a given session handle.
 /// On failure, an error handle will be placed into the provided pointer.
 /// Runtime should be O(1) unless the insertion causes a re-hash of a
@@ -321,7 +388,8 @@ pub extern "C" fn add_spec_to_session(
     }
 }
 
-/// Insert metadata into a session.
+/// Insert metadata into a session. The designation must already have been added with
+/// `add_spec_to_session`.
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn insert_metadata_in_session(
@@ -333,9 +401,9 @@ pub extern "C" fn insert_metadata_in_session(
     eh: *mut ErrorHandle,
 ) -> ElucidatorStatus {
     let designation = String::from_utf8_lossy(unsafe { CStr::from_ptr(designation) }.to_bytes());
-    let mut map = SESSION_MAP.write().unwrap();
+    let map = SESSION_MAP.read().unwrap();
     let hdl = unsafe { (*sh).clone() };
-    let session = match map.get_mut(&hdl) {
+    let session = match map.get(&hdl) {
         Some(ses) => ses,
         None => {
             let ehdl = ErrorHandle::get_new();
@@ -362,7 +430,75 @@ pub extern "C" fn insert_metadata_in_session(
         designation: &designation,
         buffer,
     };
-    match session.insert_metadata(&datum) {
+    match session.insert_metadata_shared(&datum) {
+        Ok(_) => ElucidatorStatus::ok(),
+        Err(e) => {
+            let ehdl = ErrorHandle::get_new();
+            unsafe {
+                *eh = ehdl.clone();
+            }
+            ERROR_MAP
+                .write()
+                .unwrap()
+                .insert(ehdl.clone(), ApiError::Database(e.clone()));
+            ElucidatorStatus::err()
+        }
+    }
+}
+
+/// Insert several blobs of one designation into a session under a single transaction. The
+/// designation must already have been added with `add_spec_to_session`.
+/// `boxes` must point to one bounding box per blob in `blobs`, in the same order. Nodes with
+/// a NULL pointer, such as the empty list returned by `get_metadata_in_bb`, are skipped and
+/// consume no bounding box.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn insert_n_metadata_in_session(
+    sh: *const SessionHandle,
+    boxes: *const BoundingBox,
+    designation: *const c_char,
+    blobs: *const BufNode,
+    eh: *mut ErrorHandle,
+) -> ElucidatorStatus {
+    let designation = String::from_utf8_lossy(unsafe { CStr::from_ptr(designation) }.to_bytes());
+    let map = SESSION_MAP.read().unwrap();
+    let hdl = unsafe { (*sh).clone() };
+    let session = match map.get(&hdl) {
+        Some(ses) => ses,
+        None => {
+            let ehdl = ErrorHandle::get_new();
+            unsafe {
+                *eh = ehdl.clone();
+            }
+            ERROR_MAP
+                .write()
+                .unwrap()
+                .insert(ehdl.clone(), not_found_from(&hdl));
+            return ElucidatorStatus::err();
+        }
+    };
+    let mut data = Vec::new();
+    let mut current = blobs;
+    while !current.is_null() {
+        let node = unsafe { &*current };
+        if !node.p.is_null() {
+            let bb = unsafe { &*boxes.add(data.len()) };
+            data.push(Metadata {
+                xmin: bb.a.x,
+                xmax: bb.b.x,
+                ymin: bb.a.y,
+                ymax: bb.b.y,
+                zmin: bb.a.z,
+                zmax: bb.b.z,
+                tmin: bb.a.t,
+                tmax: bb.b.t,
+                designation: &designation,
+                buffer: unsafe { slice::from_raw_parts(node.p, node.n) },
+            });
+        }
+        current = node.next;
+    }
+    match session.insert_n_metadata_shared(&data) {
         Ok(_) => ElucidatorStatus::ok(),
         Err(e) => {
             let ehdl = ErrorHandle::get_new();
@@ -390,9 +526,9 @@ pub extern "C" fn get_metadata_in_bb(
     eh: *mut ErrorHandle,
 ) -> ElucidatorStatus {
     let designation = String::from_utf8_lossy(unsafe { CStr::from_ptr(designation) }.to_bytes());
-    let mut map = SESSION_MAP.write().unwrap();
+    let map = SESSION_MAP.read().unwrap();
     let hdl = unsafe { (*sh).clone() };
-    let session = match map.get_mut(&hdl) {
+    let session = match map.get(&hdl) {
         Some(ses) => ses,
         None => {
             let ehdl = ErrorHandle::get_new();
@@ -406,7 +542,7 @@ pub extern "C" fn get_metadata_in_bb(
             return ElucidatorStatus::err();
         }
     };
-    let mut r = session.get_metadata_blobs_in_bb(
+    let r = session.get_metadata_blobs_in_bb(
         bb.a.x,
         bb.b.x,
         bb.a.y,
@@ -418,7 +554,7 @@ pub extern "C" fn get_metadata_in_bb(
         &designation,
         Some(epsilon),
     );
-    match &mut r {
+    match &r {
         Ok(o) => {
             unsafe {
                 let bn = blobs_into_bufnode(o);
@@ -440,6 +576,176 @@ pub extern "C" fn get_metadata_in_bb(
     }
 }
 
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum ElucidatorDtype {
+    ELUCIDATOR_U8,
+    ELUCIDATOR_U16,
+    ELUCIDATOR_U32,
+    ELUCIDATOR_U64,
+    ELUCIDATOR_I8,
+    ELUCIDATOR_I16,
+    ELUCIDATOR_I32,
+    ELUCIDATOR_I64,
+    ELUCIDATOR_F32,
+    ELUCIDATOR_F64,
+}
+
+impl ElucidatorDtype {
+    fn from_dtype(dtype: &Dtype) -> Option<Self> {
+        match dtype {
+            Dtype::Byte => Some(Self::ELUCIDATOR_U8),
+            Dtype::UnsignedInteger16 => Some(Self::ELUCIDATOR_U16),
+            Dtype::UnsignedInteger32 => Some(Self::ELUCIDATOR_U32),
+            Dtype::UnsignedInteger64 => Some(Self::ELUCIDATOR_U64),
+            Dtype::SignedInteger8 => Some(Self::ELUCIDATOR_I8),
+            Dtype::SignedInteger16 => Some(Self::ELUCIDATOR_I16),
+            Dtype::SignedInteger32 => Some(Self::ELUCIDATOR_I32),
+            // Durations are i64 nanoseconds on the wire
+            Dtype::SignedInteger64 | Dtype::Duration => Some(Self::ELUCIDATOR_I64),
+            Dtype::Float32 => Some(Self::ELUCIDATOR_F32),
+            Dtype::Float64 => Some(Self::ELUCIDATOR_F64),
+            _ => None,
+        }
+    }
+}
+
+/// Contiguous array of `n` little-endian values of type `dtype` stored at `data`.
+/// Free with `free_typed_array`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct TypedArray {
+    dtype: ElucidatorDtype,
+    n: usize,
+    data: *mut u8,
+}
+
+unsafe fn values_into_typed_array(dtype: ElucidatorDtype, values: &[DataValue]) -> TypedArray {
+    let bytes: Vec<u8> = values.iter().flat_map(DataValue::as_buffer).collect();
+    let data = libc::malloc(bytes.len().max(1)) as *mut u8;
+    ptr::copy_nonoverlapping(bytes.as_ptr(), data, bytes.len());
+    TypedArray {
+        dtype,
+        n: values.len(),
+        data,
+    }
+}
+
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn free_typed_array(arr: *mut TypedArray) {
+    unsafe {
+        if arr.is_null() {
+            return;
+        }
+        libc::free((*arr).data as *mut libc::c_void);
+        (*arr).data = ptr::null_mut::<u8>();
+        (*arr).n = 0;
+    }
+}
+
+fn member_in_bb(
+    session: &RTreeDatabase,
+    session_id: u32,
+    bb: &BoundingBox,
+    designation: &str,
+    member: &str,
+    epsilon: f64,
+) -> Result<(ElucidatorDtype, Vec<DataValue>), ApiError> {
+    let spec =
+        session
+            .get_designation_spec(designation)
+            .ok_or_else(|| ApiError::DesignationNotFound {
+                session: session_id,
+                designation: designation.to_string(),
+            })?;
+    let member_spec = spec
+        .member(member)
+        .ok_or_else(|| ApiError::MemberNotFound {
+            designation: designation.to_string(),
+            member: member.to_string(),
+        })?;
+    let dtype = match (
+        member_spec.sizing(),
+        ElucidatorDtype::from_dtype(member_spec.dtype()),
+    ) {
+        // Scaled members decode to f64 whatever their stored dtype
+        (Sizing::Singleton, Some(_)) if member_spec.scaling().is_some() => {
+            ElucidatorDtype::ELUCIDATOR_F64
+        }
+        (Sizing::Singleton, Some(dtype)) => dtype,
+        _ => {
+            return Err(ApiError::MemberNotScalar {
+                designation: designation.to_string(),
+                member: member.to_string(),
+            })
+        }
+    };
+    let blobs = session.get_metadata_blobs_in_bb(
+        bb.a.x,
+        bb.b.x,
+        bb.a.y,
+        bb.b.y,
+        bb.a.z,
+        bb.b.z,
+        bb.a.t,
+        bb.b.t,
+        designation,
+        Some(epsilon),
+    )?;
+    let values = blobs
+        .iter()
+        .map(|blob| {
+            let mut subset = spec.interpret_subset(blob, &[member])?;
+            Ok(subset.remove(member).unwrap())
+        })
+        .collect::<Result<Vec<DataValue>, ElucidatorError>>()?;
+    Ok((dtype, values))
+}
+
+/// Get a single numeric scalar member from each record overlapping a bounding box. Only the
+/// named member is decoded. On success, `results` holds one value per matching record and must
+/// be released with `free_typed_array`. Members with fixed-point scaling are returned as
+/// `ELUCIDATOR_F64`, whatever their stored dtype.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn get_member_in_bb(
+    sh: *const SessionHandle,
+    bb: BoundingBox,
+    designation: *const c_char,
+    member_name: *const c_char,
+    epsilon: f64,
+    results: *mut TypedArray,
+    eh: *mut ErrorHandle,
+) -> ElucidatorStatus {
+    let designation = String::from_utf8_lossy(unsafe { CStr::from_ptr(designation) }.to_bytes());
+    let member_name = String::from_utf8_lossy(unsafe { CStr::from_ptr(member_name) }.to_bytes());
+    let map = SESSION_MAP.read().unwrap();
+    let hdl = unsafe { (*sh).clone() };
+    let result = match map.get(&hdl) {
+        Some(session) => member_in_bb(session, hdl.id(), &bb, &designation, &member_name, epsilon),
+        None => Err(not_found_from(&hdl)),
+    };
+    match result {
+        Ok((dtype, values)) => {
+            unsafe {
+                *results = values_into_typed_array(dtype, &values);
+            }
+            ElucidatorStatus::ok()
+        }
+        Err(e) => {
+            let ehdl = ErrorHandle::get_new();
+            unsafe {
+                *eh = ehdl.clone();
+            }
+            ERROR_MAP.write().unwrap().insert(ehdl.clone(), e);
+            ElucidatorStatus::err()
+        }
+    }
+}
+
 /// Print a session map
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
@@ -462,3 +768,247 @@ pub extern "C" fn print_session(sh: *const SessionHandle) {
 pub extern "C" fn print_the_mayhem() {
     println!("{:#?}", SESSION_MAP.read().unwrap());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(v: f64) -> Point {
+        Point {
+            x: v,
+            y: v,
+            z: v,
+            t: v,
+        }
+    }
+
+    fn c_string(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn get_member_in_bb_u64_ok() {
+        let mut sh = SessionHandle { hdl: 0 };
+        let mut eh = ErrorHandle { hdl: 0 };
+        assert_eq!(
+            new_session(&mut sh, DatabaseKind::ELUCIDATOR_RTREE),
+            ElucidatorStatus::ok()
+        );
+        let designation = c_string("Counter");
+        let spec = c_string("label: string, hits: u64, weights: f32[]");
+        assert_eq!(
+            add_spec_to_session(designation.as_ptr(), spec.as_ptr(), &sh, &mut eh),
+            ElucidatorStatus::ok()
+        );
+        let parsed =
+            elucidator::designation::DesignationSpecification::from_text(spec.to_str().unwrap())
+                .unwrap();
+        for i in 0..4u64 {
+            let values = HashMap::from([
+                ("label", DataValue::Str(format!("record {i}"))),
+                ("hits", DataValue::UnsignedInteger64(i * 10)),
+                ("weights", DataValue::Float32Array(vec![0.5; i as usize])),
+            ]);
+            let blob = parsed.pack(&values).unwrap();
+            let bb = BoundingBox {
+                a: point(i as f64),
+                b: point(i as f64),
+            };
+            assert_eq!(
+                insert_metadata_in_session(
+                    &sh,
+                    bb,
+                    designation.as_ptr(),
+                    blob.as_ptr(),
+                    blob.len(),
+                    &mut eh
+                ),
+                ElucidatorStatus::ok()
+            );
+        }
+
+        let member = c_string("hits");
+        let mut results = TypedArray {
+            dtype: ElucidatorDtype::ELUCIDATOR_U8,
+            n: 0,
+            data: ptr::null_mut(),
+        };
+        let bb = BoundingBox {
+            a: point(0.0),
+            b: point(2.0),
+        };
+        let status = get_member_in_bb(
+            &sh,
+            bb,
+            designation.as_ptr(),
+            member.as_ptr(),
+            0.0,
+            &mut results,
+            &mut eh,
+        );
+        assert_eq!(status, ElucidatorStatus::ok());
+        assert_eq!(results.dtype, ElucidatorDtype::ELUCIDATOR_U64);
+        let mut hits =
+            unsafe { slice::from_raw_parts(results.data as *const u64, results.n) }.to_vec();
+        hits.sort();
+        assert_eq!(hits, vec![0, 10, 20]);
+        free_typed_array(&mut results);
+        assert!(results.data.is_null());
+    }
+
+    #[test]
+    fn get_member_in_bb_scaled_f64() {
+        let mut sh = SessionHandle { hdl: 0 };
+        let mut eh = ErrorHandle { hdl: 0 };
+        new_session(&mut sh, DatabaseKind::ELUCIDATOR_RTREE);
+        let designation = c_string("Position");
+        let spec = c_string("x: i32 @ q16.16");
+        add_spec_to_session(designation.as_ptr(), spec.as_ptr(), &sh, &mut eh);
+        let blob = (3i32 << 15).to_le_bytes();
+        let bb = || BoundingBox {
+            a: point(0.0),
+            b: point(0.0),
+        };
+        let status =
+            insert_metadata_in_session(&sh, bb(), designation.as_ptr(), blob.as_ptr(), 4, &mut eh);
+        assert_eq!(status, ElucidatorStatus::ok());
+
+        let member = c_string("x");
+        let mut results = TypedArray {
+            dtype: ElucidatorDtype::ELUCIDATOR_U8,
+            n: 0,
+            data: ptr::null_mut(),
+        };
+        let status = get_member_in_bb(
+            &sh,
+            bb(),
+            designation.as_ptr(),
+            member.as_ptr(),
+            0.0,
+            &mut results,
+            &mut eh,
+        );
+        assert_eq!(status, ElucidatorStatus::ok());
+        assert_eq!(results.dtype, ElucidatorDtype::ELUCIDATOR_F64);
+        let xs = unsafe { slice::from_raw_parts(results.data as *const f64, results.n) }.to_vec();
+        assert_eq!(xs, vec![1.5]);
+        free_typed_array(&mut results);
+    }
+
+    #[test]
+    fn insert_n_metadata_in_session_ok() {
+        let mut sh = SessionHandle { hdl: 0 };
+        let mut eh = ErrorHandle { hdl: 0 };
+        new_session(&mut sh, DatabaseKind::ELUCIDATOR_RTREE);
+        let designation = c_string("Reading");
+        let spec = c_string("value: u32");
+        add_spec_to_session(designation.as_ptr(), spec.as_ptr(), &sh, &mut eh);
+
+        let blobs: Vec<Vec<u8>> = (1..=3u32).map(|v| v.to_le_bytes().to_vec()).collect();
+        let blobs = unsafe { blobs_into_bufnode(&blobs) };
+        let boxes: Vec<BoundingBox> = (0..3)
+            .map(|i| BoundingBox {
+                a: point(i as f64),
+                b: point(i as f64),
+            })
+            .collect();
+        let status =
+            insert_n_metadata_in_session(&sh, boxes.as_ptr(), designation.as_ptr(), blobs, &mut eh);
+        free_bufnodes(blobs);
+        assert_eq!(status, ElucidatorStatus::ok());
+
+        let mut results = ptr::null_mut::<BufNode>();
+        let bb = BoundingBox {
+            a: point(0.0),
+            b: point(2.0),
+        };
+        let status = get_metadata_in_bb(&sh, bb, designation.as_ptr(), 0.0, &mut results, &mut eh);
+        assert_eq!(status, ElucidatorStatus::ok());
+        let mut found = Vec::new();
+        let mut current = results;
+        while !current.is_null() {
+            let node = unsafe { &*current };
+            let bytes = unsafe { slice::from_raw_parts(node.p, node.n) };
+            found.push(u32::from_le_bytes(bytes.try_into().unwrap()));
+            current = node.next;
+        }
+        free_bufnodes(results);
+        found.sort();
+        assert_eq!(found, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn insert_metadata_unregistered_designation_fails() {
+        let mut sh = SessionHandle { hdl: 0 };
+        let mut eh = ErrorHandle { hdl: 0 };
+        new_session(&mut sh, DatabaseKind::ELUCIDATOR_RTREE);
+        let designation = c_string("Unregistered");
+        let blob = [1u8];
+        let bb = BoundingBox {
+            a: point(0.0),
+            b: point(0.0),
+        };
+        let status =
+            insert_metadata_in_session(&sh, bb, designation.as_ptr(), blob.as_ptr(), 1, &mut eh);
+        assert_eq!(status, ElucidatorStatus::err());
+        assert!(matches!(
+            ERROR_MAP.read().unwrap().get(&eh),
+            Some(ApiError::Database(
+                error::DatabaseError::SpecificationError { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn get_error_code_ok() {
+        let mut sh = SessionHandle { hdl: 0 };
+        let mut eh = ErrorHandle { hdl: 0 };
+        new_session(&mut sh, DatabaseKind::ELUCIDATOR_RTREE);
+        let designation = c_string("Broken");
+        let spec = c_string("foo: u9");
+        let status = add_spec_to_session(designation.as_ptr(), spec.as_ptr(), &sh, &mut eh);
+        assert_eq!(status, ElucidatorStatus::err());
+        assert_eq!(get_error_code(&eh), 5);
+
+        let missing = SessionHandle { hdl: u32::MAX };
+        let status = add_spec_to_session(designation.as_ptr(), spec.as_ptr(), &missing, &mut eh);
+        assert_eq!(status, ElucidatorStatus::err());
+        assert_eq!(get_error_code(&eh), 1001);
+        assert_eq!(get_error_code(&ErrorHandle { hdl: u32::MAX }), 0);
+    }
+
+    #[test]
+    fn get_member_in_bb_array_member_fails() {
+        let mut sh = SessionHandle { hdl: 0 };
+        let mut eh = ErrorHandle { hdl: 0 };
+        new_session(&mut sh, DatabaseKind::ELUCIDATOR_RTREE);
+        let designation = c_string("Samples");
+        let spec = c_string("weights: f32[]");
+        add_spec_to_session(designation.as_ptr(), spec.as_ptr(), &sh, &mut eh);
+
+        let member = c_string("weights");
+        let mut results = TypedArray {
+            dtype: ElucidatorDtype::ELUCIDATOR_U8,
+            n: 0,
+            data: ptr::null_mut(),
+        };
+        let bb = BoundingBox {
+            a: point(0.0),
+            b: point(1.0),
+        };
+        let status = get_member_in_bb(
+            &sh,
+            bb,
+            designation.as_ptr(),
+            member.as_ptr(),
+            0.0,
+            &mut results,
+            &mut eh,
+        );
+        assert_eq!(status, ElucidatorStatus::err());
+        assert!(matches!(
+            ERROR_MAP.read().unwrap().get(&eh),
+            Some(ApiError::MemberNotScalar { .. })
+        ));
+    }
+}
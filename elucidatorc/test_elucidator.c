@@ -14,7 +14,7 @@ void wrap_insertion(SessionHandle * sh, const char * designation, const char * s
     if ( status != ELUCIDATOR_OK ) {
         char * msg = get_error_string(eh);
         fprintf(stderr, "Encountered error while inserting %s\n", designation);
-        fprintf(stderr, "%s\n", msg);
+        fprintf(stderr, "%s (code %u)\n", msg, get_error_code(eh));
         free(msg);
     }
     else {
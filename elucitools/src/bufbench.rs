@@ -0,0 +1,59 @@
+use clap::Parser;
+use elucidator::representable::Representable;
+use std::time::Instant;
+
+mod alloc_counter;
+use alloc_counter::{allocations, CountingAllocator};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Compare building a record buffer with `as_buffer` against `as_buffer_into`
+#[derive(Parser)]
+struct Args {
+    /// Number of members in the record
+    #[arg(short, long, default_value_t = 10_000)]
+    members: usize,
+}
+
+fn measure(name: &str, build: impl FnOnce() -> Vec<u8>) -> Vec<u8> {
+    let before = allocations();
+    let start = Instant::now();
+    let buffer = build();
+    let elapsed = start.elapsed();
+    let allocations = allocations() - before;
+    println!(
+        "{name}: {} bytes, {allocations} allocations, {elapsed:?}",
+        buffer.len()
+    );
+    buffer
+}
+
+fn main() {
+    let args = Args::parse();
+    let values: Vec<Box<dyn Representable>> = (0..args.members)
+        .map(|i| -> Box<dyn Representable> {
+            match i % 3 {
+                0 => Box::new(i as u32),
+                1 => Box::new(i as f64),
+                _ => Box::new(vec![i as i16; 4]),
+            }
+        })
+        .collect();
+
+    let extended = measure("as_buffer", || {
+        let mut buffer = Vec::new();
+        for value in &values {
+            buffer.extend(value.as_buffer());
+        }
+        buffer
+    });
+    let appended = measure("as_buffer_into", || {
+        let mut buffer = Vec::new();
+        for value in &values {
+            value.as_buffer_into(&mut buffer);
+        }
+        buffer
+    });
+    assert_eq!(extended, appended);
+}
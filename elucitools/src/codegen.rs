@@ -0,0 +1,203 @@
+use clap::Parser;
+use elucidator::{
+    designation::DesignationSpecification,
+    member::{Dtype, MemberSpecification, Sizing},
+};
+use std::process::ExitCode;
+
+/// Generate a Rust struct definition matching a designation specification, with a `to_buffer`
+/// method serializing it as `DesignationSpecification::pack` would. Members with an enum mapping
+/// hold their stored integer. Fixed-point scaling and string length caps are rejected, since
+/// `to_buffer` cannot fail the way `pack` does on out-of-range or overlong values.
+#[derive(Parser)]
+struct Args {
+    /// Designation specification to generate a struct for
+    #[arg(short, long)]
+    spec: String,
+    /// Name of the generated struct
+    #[arg(short, long)]
+    name: String,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Keywords that cannot be written as raw identifiers either
+const RESERVED_KEYWORDS: &[&str] = &["crate", "self", "super", "Self"];
+
+fn primitive_type(dtype: &Dtype) -> Result<&'static str, String> {
+    Ok(match dtype {
+        Dtype::Byte => "u8",
+        Dtype::UnsignedInteger16 => "u16",
+        Dtype::UnsignedInteger32 => "u32",
+        Dtype::UnsignedInteger64 => "u64",
+        Dtype::SignedInteger8 => "i8",
+        Dtype::SignedInteger16 => "i16",
+        Dtype::SignedInteger32 => "i32",
+        Dtype::SignedInteger64 | Dtype::Duration => "i64",
+        Dtype::Float32 => "f32",
+        Dtype::Float64 => "f64",
+        Dtype::Str => "String",
+        Dtype::Char => "char",
+        dtype => return Err(format!("No Rust type mapping for {}", dtype.keyword())),
+    })
+}
+
+fn field_type(member: &MemberSpecification) -> Result<String, String> {
+    let primitive = primitive_type(member.dtype())?;
+    Ok(match member.sizing() {
+        Sizing::Singleton => primitive.to_string(),
+        Sizing::Fixed(n) => format!("[{primitive}; {n}]"),
+        Sizing::Dynamic => format!("Vec<{primitive}>"),
+        sizing => return Err(format!("No Rust type mapping for sizing {sizing:?}")),
+    })
+}
+
+fn field_name(identifier: &str) -> Result<String, String> {
+    if RESERVED_KEYWORDS.contains(&identifier) {
+        Err(format!(
+            "Member \"{identifier}\" cannot be used as a Rust field name"
+        ))
+    } else if RUST_KEYWORDS.contains(&identifier) {
+        Ok(format!("r#{identifier}"))
+    } else {
+        Ok(identifier.to_string())
+    }
+}
+
+// Statement appending the little-endian bytes of `value`, an expression of the member's dtype
+fn push_value(dtype: &Dtype, value: &str) -> String {
+    match dtype {
+        Dtype::Char => format!("buffer.extend_from_slice(&({value} as u32).to_le_bytes());"),
+        Dtype::Str => format!(
+            "buffer.extend_from_slice(&({value}.len() as u64).to_le_bytes());\n        \
+             buffer.extend_from_slice({value}.as_bytes());"
+        ),
+        _ => format!("buffer.extend_from_slice(&{value}.to_le_bytes());"),
+    }
+}
+
+fn push_member(member: &MemberSpecification) -> Result<String, String> {
+    let identifier = member.identifier();
+    if let Some(scaling) = member.scaling() {
+        return Err(format!(
+            "Member \"{identifier}\" has fixed-point scaling @ {scaling}, which to_buffer cannot reproduce"
+        ));
+    }
+    if let Some(max_len) = member.max_len() {
+        return Err(format!(
+            "Member \"{identifier}\" has a length cap of {max_len}, which to_buffer cannot enforce"
+        ));
+    }
+    let field = format!("self.{}", field_name(identifier)?);
+    let push_each = format!(
+        "for v in &{field} {{\n            {}\n        }}",
+        push_value(member.dtype(), "v")
+    );
+    Ok(match member.sizing() {
+        Sizing::Singleton => push_value(member.dtype(), &field),
+        Sizing::Fixed(_) => push_each,
+        Sizing::Dynamic => format!(
+            "buffer.extend_from_slice(&({field}.len() as u64).to_le_bytes());\n        {push_each}"
+        ),
+        sizing => return Err(format!("No Rust type mapping for sizing {sizing:?}")),
+    })
+}
+
+fn generate_struct(spec: &DesignationSpecification, name: &str) -> Result<String, String> {
+    let mut code = format!("#[derive(Debug, Clone, PartialEq)]\npub struct {name} {{\n");
+    for member in spec.members() {
+        if let Some(mapping) = member.enum_mapping() {
+            code.push_str(&format!("    /// Stored value of enum {mapping}\n"));
+        }
+        code.push_str(&format!(
+            "    pub {}: {},\n",
+            field_name(member.identifier())?,
+            field_type(member)?
+        ));
+    }
+    code.push_str("}\n\n");
+    code.push_str(&format!("impl {name} {{\n"));
+    code.push_str(
+        "    /// Serialize the fields in specification order, as `DesignationSpecification::pack` would\n",
+    );
+    code.push_str(
+        "    pub fn to_buffer(&self) -> Vec<u8> {\n        let mut buffer = Vec::new();\n",
+    );
+    for member in spec.members() {
+        code.push_str(&format!("        {}\n", push_member(member)?));
+    }
+    code.push_str("        buffer\n    }\n}\n");
+    Ok(code)
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let code = DesignationSpecification::from_text(&args.spec)
+        .map_err(|e| e.to_string())
+        .and_then(|spec| generate_struct(&spec, &args.name));
+    match code {
+        Ok(code) => {
+            print!("{code}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_identifier_ok() {
+        let spec = DesignationSpecification::from_text("type: i16, fn: u8").unwrap();
+        let code = generate_struct(&spec, "MyRecord").unwrap();
+        assert!(
+            code.contains("    pub r#type: i16,\n    pub r#fn: u8,\n"),
+            "{code}"
+        );
+    }
+
+    #[test]
+    fn to_buffer_unsupported_err() {
+        for (text, error) in [
+            (
+                "t: i16 @ q8.8",
+                "Member \"t\" has fixed-point scaling @ q8.8, which to_buffer cannot reproduce",
+            ),
+            (
+                "name: string(8)",
+                "Member \"name\" has a length cap of 8, which to_buffer cannot enforce",
+            ),
+        ] {
+            let spec = DesignationSpecification::from_text(text).unwrap();
+            pretty_assertions::assert_eq!(
+                generate_struct(&spec, "MyRecord"),
+                Err(error.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn reserved_identifier_err() {
+        for identifier in RESERVED_KEYWORDS {
+            let spec = DesignationSpecification::from_text(&format!("{identifier}: u8")).unwrap();
+            pretty_assertions::assert_eq!(
+                generate_struct(&spec, "MyRecord"),
+                Err(format!(
+                    "Member \"{identifier}\" cannot be used as a Rust field name"
+                ))
+            );
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use clap::Parser;
+use elucidator::{
+    designation::{DecodeScratch, DesignationSpecification},
+    value::DataValue,
+};
+use std::{collections::HashMap, time::Instant};
+
+mod alloc_counter;
+use alloc_counter::{allocations, CountingAllocator};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Compare decoding records with `interpret_enum` against `interpret_enum_reuse`, and with the
+/// `zerocopy` feature, against `interpret_zerocopy`
+#[derive(Parser)]
+struct Args {
+    /// Number of records to decode
+    #[arg(short, long, default_value_t = 100_000)]
+    records: usize,
+}
+
+fn measure(name: &str, decode: impl FnOnce() -> f64) {
+    let before = allocations();
+    let start = Instant::now();
+    let checksum = decode();
+    let elapsed = start.elapsed();
+    let allocations = allocations() - before;
+    println!("{name}: {allocations} allocations, {elapsed:?} (checksum {checksum})");
+}
+
+/// Pack `records` records of `spec`, which must have the members `id`, `xs`, `name` and `ys`
+fn pack_records(spec: &DesignationSpecification, records: usize) -> Vec<Vec<u8>> {
+    (0..records)
+        .map(|i| {
+            spec.pack(&HashMap::from([
+                ("id", DataValue::UnsignedInteger32(i as u32)),
+                ("xs", DataValue::Float64Array(vec![i as f64; i % 8])),
+                ("name", DataValue::Str(format!("record {i}"))),
+                ("ys", DataValue::SignedInteger16Array(vec![1, 2, 3, 4])),
+            ]))
+            .unwrap()
+        })
+        .collect()
+}
+
+fn sum_xs(values: &HashMap<&str, DataValue>) -> f64 {
+    match &values["xs"] {
+        DataValue::Float64Array(xs) => xs.iter().sum::<f64>(),
+        _ => unreachable!(),
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let spec = DesignationSpecification::from_text("id: u32, xs: f64[], name: string, ys: i16[4]")
+        .unwrap();
+    let buffers = pack_records(&spec, args.records);
+
+    measure("interpret_enum", || {
+        buffers
+            .iter()
+            .map(|b| sum_xs(&spec.interpret_enum(b).unwrap()))
+            .sum()
+    });
+    measure("interpret_enum_reuse", || {
+        let mut scratch = DecodeScratch::new();
+        buffers
+            .iter()
+            .map(|b| sum_xs(spec.interpret_enum_reuse(b, &mut scratch).unwrap()))
+            .sum()
+    });
+    #[cfg(feature = "zerocopy")]
+    zerocopy(args.records);
+}
+
+/// Compare `interpret_enum` against `interpret_zerocopy` on records whose `xs` array starts on
+/// an 8-byte boundary, so it can be borrowed rather than copied
+#[cfg(feature = "zerocopy")]
+fn zerocopy(records: usize) {
+    use elucidator::zerocopy::ReprSlice;
+
+    let spec = DesignationSpecification::from_text("xs: f64[], id: u32, name: string, ys: i16[4]")
+        .unwrap();
+    let buffers = pack_records(&spec, records);
+
+    measure("interpret_enum (aligned xs)", || {
+        buffers
+            .iter()
+            .map(|b| sum_xs(&spec.interpret_enum(b).unwrap()))
+            .sum()
+    });
+    measure("interpret_zerocopy (aligned xs)", || {
+        buffers
+            .iter()
+            .map(|b| match &spec.interpret_zerocopy(b).unwrap()["xs"] {
+                ReprSlice::Float64Array(xs) => xs.iter().sum::<f64>(),
+                ReprSlice::Owned(DataValue::Float64Array(xs)) => xs.iter().sum::<f64>(),
+                _ => unreachable!(),
+            })
+            .sum()
+    });
+}
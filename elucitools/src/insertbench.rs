@@ -0,0 +1,57 @@
+use clap::Parser;
+use elucidator_db::{
+    backends::rtree::RTreeDatabase,
+    database::{BoundingBox, Database},
+};
+use rand::random;
+use std::time::Instant;
+
+/// Compare inserting records by designation name against inserting through a designation handle
+#[derive(Parser)]
+struct Args {
+    /// Number of records to insert with each method
+    #[arg(short, long, default_value_t = 100_000)]
+    records: usize,
+}
+
+static DESIGNATION: &str = "sample";
+
+fn fresh_db() -> RTreeDatabase {
+    let mut db = RTreeDatabase::new(None, None).unwrap();
+    db.insert_spec_text(DESIGNATION, "id: u64, value: f64")
+        .unwrap();
+    db
+}
+
+fn measure(name: &str, insert: impl FnOnce()) {
+    let start = Instant::now();
+    insert();
+    println!("{name}: {:?}", start.elapsed());
+}
+
+fn main() {
+    let args = Args::parse();
+    let records: Vec<(BoundingBox, Vec<u8>)> = (0..args.records)
+        .map(|i| {
+            let a: [f64; 4] = random();
+            let b: [f64; 4] = random();
+            let mut buffer = (i as u64).to_le_bytes().to_vec();
+            buffer.extend(random::<f64>().to_le_bytes());
+            (BoundingBox::from_points(a, b), buffer)
+        })
+        .collect();
+
+    let mut db = fresh_db();
+    measure("by name", || {
+        for (bb, buffer) in &records {
+            db.insert_metadata_box(bb, DESIGNATION, buffer).unwrap();
+        }
+    });
+    let mut db = fresh_db();
+    let handle = db.designation_handle(DESIGNATION).unwrap();
+    measure("by handle", || {
+        for (bb, buffer) in &records {
+            db.insert_with_handle(&handle, bb, buffer).unwrap();
+        }
+    });
+}
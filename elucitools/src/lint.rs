@@ -0,0 +1,91 @@
+use clap::Parser;
+use elucidator::designation::DesignationSpecification;
+use std::{fs, path::PathBuf, process::ExitCode};
+
+/// Validate every designation specification file in a directory
+#[derive(Parser)]
+struct Args {
+    /// Directory containing specification files, one specification per file
+    #[arg(short, long)]
+    dir: PathBuf,
+    /// Only check file names matching this pattern, where `*` matches any run of characters
+    #[arg(short, long, default_value = "*")]
+    glob: String,
+}
+
+/// Match `name` against `pattern`, where `*` matches any (possibly empty) run of characters
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            name.char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(name.len()))
+                .any(|i| matches_glob(rest, &name[i..]))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let entries = match fs::read_dir(&args.dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Could not read {}: {e}", args.dir.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| matches_glob(&args.glob, name))
+        })
+        .collect();
+    paths.sort();
+
+    let mut failures = 0;
+    for path in &paths {
+        let result = fs::read_to_string(path)
+            .map_err(|e| format!("{e}\n"))
+            .and_then(|text| {
+                DesignationSpecification::from_text(text.trim_end()).map_err(|e| format!("{e}"))
+            });
+        match result {
+            Ok(_) => println!("PASS {}", path.display()),
+            Err(e) => {
+                failures += 1;
+                println!("FAIL {}", path.display());
+                print!("{e}");
+            }
+        }
+    }
+    println!("{} of {} files passed", paths.len() - failures, paths.len());
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_glob_ok() {
+        assert!(matches_glob("*", "anything"));
+        assert!(matches_glob("*.spec", "foo.spec"));
+        assert!(matches_glob("*.spec", ".spec"));
+        assert!(!matches_glob("*.spec", "foo.spec.bak"));
+        assert!(matches_glob("foo*bar*", "foo-x-bar-y"));
+        assert!(!matches_glob("foo", "foobar"));
+    }
+}
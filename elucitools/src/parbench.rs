@@ -0,0 +1,57 @@
+use clap::Parser;
+use elucidator::{
+    designation::DesignationSpecification, parallel::interpret_many, value::DataValue,
+};
+use std::{collections::HashMap, time::Instant};
+
+/// Compare decoding records one after another against `interpret_many` on the rayon pool
+#[derive(Parser)]
+struct Args {
+    /// Number of records to decode
+    #[arg(short, long, default_value_t = 100_000)]
+    records: usize,
+}
+
+fn measure(name: &str, decode: impl FnOnce() -> Vec<HashMap<String, DataValue>>) {
+    let start = Instant::now();
+    let records = decode();
+    println!("{name}: {:?} ({} records)", start.elapsed(), records.len());
+}
+
+fn main() {
+    let args = Args::parse();
+    let spec = DesignationSpecification::from_text("id: u32, xs: f64[], name: string, ys: i16[4]")
+        .unwrap();
+    let buffers: Vec<Vec<u8>> = (0..args.records)
+        .map(|i| {
+            spec.pack(&HashMap::from([
+                ("id", DataValue::UnsignedInteger32(i as u32)),
+                ("xs", DataValue::Float64Array(vec![i as f64; i % 8])),
+                ("name", DataValue::Str(format!("record {i}"))),
+                ("ys", DataValue::SignedInteger16Array(vec![1, 2, 3, 4])),
+            ]))
+            .unwrap()
+        })
+        .collect();
+    let pairs: Vec<(&DesignationSpecification, &[u8])> =
+        buffers.iter().map(|b| (&spec, b.as_slice())).collect();
+
+    measure("serial", || {
+        pairs
+            .iter()
+            .map(|(spec, buffer)| {
+                spec.interpret_enum(buffer)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect()
+            })
+            .collect()
+    });
+    measure("parallel", || {
+        interpret_many(&pairs)
+            .into_iter()
+            .map(|record| record.unwrap())
+            .collect()
+    });
+}
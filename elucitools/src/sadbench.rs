@@ -47,7 +47,7 @@ fn random_bb() -> Bb {
     (xmin, xmax, ymin, ymax, zmin, zmax, tmin, tmax)
 }
 
-fn metadata_from(buffer: &[u8]) -> Metadata {
+fn metadata_from(buffer: &[u8]) -> Metadata<'_> {
     let (xmin, xmax) = rand_pair();
     let (ymin, ymax) = rand_pair();
     let (zmin, zmax) = rand_pair();
@@ -1,18 +1,55 @@
 use clap::Parser;
 use elucidator::designation::DesignationSpecification;
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    process::ExitCode,
+};
 
 /// Verify that a given designation string is valid
 #[derive(Parser)]
 struct Args {
-    /// String to validate
-    input: String,
+    /// String to validate, or `-` to read it from stdin
+    #[arg(required_unless_present = "file", conflicts_with = "file")]
+    input: Option<String>,
+    /// Read the specification to validate from this file instead
+    #[arg(short, long)]
+    file: Option<PathBuf>,
 }
 
-fn main() {
+fn main() -> ExitCode {
     let args = Args::parse();
 
-    match DesignationSpecification::from_text(&args.input) {
-        Ok(_) => println!("All good!"),
-        Err(e) => print!("{e}"),
+    let text = match (&args.file, args.input.as_deref()) {
+        (Some(path), _) => fs::read_to_string(path)
+            .map(|text| text.trim_end().to_string())
+            .map_err(|e| format!("Could not read {}: {e}", path.display())),
+        (None, Some("-")) => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map(|_| text.trim_end().to_string())
+                .map_err(|e| format!("Could not read stdin: {e}"))
+        }
+        (None, input) => Ok(input.unwrap_or_default().to_string()),
+    };
+    let text = match text {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match DesignationSpecification::from_text(&text) {
+        Ok(_) => {
+            println!("All good!");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            print!("{e}");
+            ExitCode::FAILURE
+        }
     }
 }
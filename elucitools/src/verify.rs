@@ -0,0 +1,38 @@
+use clap::Parser;
+use elucidator_db::{backends::sqlite::SqlDatabase, database::Database};
+use std::{path::PathBuf, process::ExitCode};
+
+/// Check that every record stored in a SQLite database decodes against its specification
+#[derive(Parser)]
+struct Args {
+    /// Path to the database file
+    #[arg(short, long)]
+    db: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let failures =
+        SqlDatabase::from_path(&args.db.to_string_lossy()).and_then(|db| db.verify_all());
+    let failures = match failures {
+        Ok(failures) => failures,
+        Err(e) => {
+            eprintln!("Could not verify {}: {e}", args.db.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    for failure in &failures {
+        println!(
+            "FAIL record {} ({}): {}",
+            failure.id, failure.designation, failure.error
+        );
+    }
+    println!("{} records failed to decode", failures.len());
+
+    if failures.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
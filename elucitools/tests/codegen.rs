@@ -0,0 +1,64 @@
+use elucidator::{designation::DesignationSpecification, value::DataValue};
+use std::{collections::HashMap, process::Command};
+
+/// Output of `codegen` for `SPEC`, included so that it is known to compile
+mod my_record {
+    include!("codegen/my_record.rs");
+}
+use my_record::MyRecord;
+
+const SPEC: &str = "count: u32, samples: f64[], header: u8[4], label: string, type: i16, \
+                    mode: enum u8 { idle=0, active=1 }, grade: char";
+
+fn codegen(spec: &str) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_codegen"))
+        .args(["--spec", spec, "--name", "MyRecord"])
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn generated_code_matches() {
+    let output = codegen(SPEC);
+    assert!(output.status.success());
+    pretty_assertions::assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        include_str!("codegen/my_record.rs")
+    );
+}
+
+#[test]
+fn to_buffer_round_trip() {
+    let spec = DesignationSpecification::from_text(SPEC).unwrap();
+    let record = MyRecord {
+        count: 3,
+        samples: vec![0.5, -1.25],
+        header: [1, 2, 3, 4],
+        label: "probe".to_string(),
+        r#type: -7,
+        mode: 1,
+        grade: 'é',
+    };
+    let values = HashMap::from([
+        ("count", DataValue::UnsignedInteger32(3)),
+        ("samples", DataValue::Float64Array(vec![0.5, -1.25])),
+        ("header", DataValue::ByteArray(vec![1, 2, 3, 4])),
+        ("label", DataValue::Str("probe".to_string())),
+        ("type", DataValue::SignedInteger16(-7)),
+        ("mode", DataValue::Byte(1)),
+        ("grade", DataValue::Char('é')),
+    ]);
+    let buffer = record.to_buffer();
+    pretty_assertions::assert_eq!(buffer, spec.pack(&values).unwrap());
+    pretty_assertions::assert_eq!(spec.interpret_enum(&buffer).unwrap(), values);
+}
+
+#[test]
+fn invalid_spec_fails() {
+    for spec in ["foo: u9", "self: u8", "t: i16 @ q8.8", "name: string(8)"] {
+        let output = codegen(spec);
+        assert!(!output.status.success(), "{spec}");
+        assert!(output.stdout.is_empty(), "{spec}");
+        assert!(!output.stderr.is_empty(), "{spec}");
+    }
+}
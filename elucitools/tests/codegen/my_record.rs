@@ -0,0 +1,32 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct MyRecord {
+    pub count: u32,
+    pub samples: Vec<f64>,
+    pub header: [u8; 4],
+    pub label: String,
+    pub r#type: i16,
+    /// Stored value of enum { idle=0, active=1 }
+    pub mode: u8,
+    pub grade: char,
+}
+
+impl MyRecord {
+    /// Serialize the fields in specification order, as `DesignationSpecification::pack` would
+    pub fn to_buffer(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(&self.count.to_le_bytes());
+        buffer.extend_from_slice(&(self.samples.len() as u64).to_le_bytes());
+        for v in &self.samples {
+            buffer.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in &self.header {
+            buffer.extend_from_slice(&v.to_le_bytes());
+        }
+        buffer.extend_from_slice(&(self.label.len() as u64).to_le_bytes());
+        buffer.extend_from_slice(self.label.as_bytes());
+        buffer.extend_from_slice(&self.r#type.to_le_bytes());
+        buffer.extend_from_slice(&self.mode.to_le_bytes());
+        buffer.extend_from_slice(&(self.grade as u32).to_le_bytes());
+        buffer
+    }
+}
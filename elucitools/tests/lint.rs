@@ -0,0 +1,64 @@
+use rand::Rng;
+use std::{fs, path::PathBuf, process::Command};
+
+struct TempDir {
+    pub path: PathBuf,
+}
+
+impl TempDir {
+    fn new() -> Self {
+        let mut rng = rand::thread_rng();
+        let name: String = (0..10)
+            .map(|_| rng.gen_range(b'a'..=b'z') as char)
+            .collect();
+        let path = std::env::temp_dir().join(name);
+        fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+}
+
+impl std::ops::Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[test]
+fn lint_reports_each_file() {
+    let dir = TempDir::new();
+    fs::write(dir.path.join("good.spec"), "foo: u8, bar: f32[]\n").unwrap();
+    fs::write(dir.path.join("bad.spec"), "foo: u8, bar: i9").unwrap();
+    fs::write(dir.path.join("notes.txt"), "not a spec").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_lint"))
+        .arg("--dir")
+        .arg(&dir.path)
+        .args(["--glob", "*.spec"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    let bad = dir.path.join("bad.spec");
+    let good = dir.path.join("good.spec");
+    let expected = format!(
+        "FAIL {}\nError Illegal data type between positions 14 and 16:\n bar: i9\n      ^^\nPASS {}\n1 of 2 files passed\n",
+        bad.display(),
+        good.display(),
+    );
+    pretty_assertions::assert_eq!(stdout, expected);
+}
+
+#[test]
+fn lint_all_valid_succeeds() {
+    let dir = TempDir::new();
+    fs::write(dir.path.join("good.spec"), "foo: u8").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_lint"))
+        .arg("--dir")
+        .arg(&dir.path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+}
@@ -0,0 +1,69 @@
+use rand::Rng;
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+struct TempFile {
+    pub path: PathBuf,
+}
+
+impl TempFile {
+    fn new() -> Self {
+        let mut rng = rand::thread_rng();
+        let name: String = (0..10)
+            .map(|_| rng.gen_range(b'a'..=b'z') as char)
+            .collect();
+        TempFile {
+            path: std::env::temp_dir().join(name),
+        }
+    }
+}
+
+impl std::ops::Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn validate_spec_from_stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_validatespec"))
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"foo: u32\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    pretty_assertions::assert_eq!(stdout, "All good!\n");
+}
+
+#[test]
+fn validate_invalid_spec_from_file() {
+    let file = TempFile::new();
+    fs::write(&file.path, "foo: i9\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_validatespec"))
+        .arg("--file")
+        .arg(&file.path)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    pretty_assertions::assert_eq!(
+        stdout,
+        "Error Illegal data type between positions 5 and 7:\nfoo: i9\n     ^^\n"
+    );
+}
@@ -1,4 +1,8 @@
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyType},
+};
 
 use elucidator::{error::ElucidatorError, value::DataValue};
 
@@ -26,6 +30,7 @@ fn value2obj<'py>(py: Python<'py>, dv: &HashMap<&str, DataValue>) -> PyResult<Bo
             DataValue::Float32(v) => d.set_item(k, v)?,
             DataValue::Float64(v) => d.set_item(k, v)?,
             DataValue::Str(v) => d.set_item(k, v)?,
+            DataValue::Char(v) => d.set_item(k, v)?,
             DataValue::ByteArray(v) => d.set_item(k, v)?,
             DataValue::UnsignedInteger16Array(v) => d.set_item(k, v)?,
             DataValue::UnsignedInteger32Array(v) => d.set_item(k, v)?,
@@ -36,6 +41,9 @@ fn value2obj<'py>(py: Python<'py>, dv: &HashMap<&str, DataValue>) -> PyResult<Bo
             DataValue::SignedInteger64Array(v) => d.set_item(k, v)?,
             DataValue::Float32Array(v) => d.set_item(k, v)?,
             DataValue::Float64Array(v) => d.set_item(k, v)?,
+            // Durations are handed to Python as integer nanoseconds
+            DataValue::Duration(v) => d.set_item(k, v)?,
+            DataValue::DurationArray(v) => d.set_item(k, v)?,
         }
     }
     Ok(d)
@@ -120,6 +128,32 @@ impl Session {
         };
         Ok(Session { db })
     }
+    /// Load a session previously written with `save_as`
+    #[classmethod]
+    fn open(_cls: &Bound<'_, PyType>, path: &str) -> PyResult<Self> {
+        match RTreeDatabase::from_path(path) {
+            Ok(db) => Ok(Session { db }),
+            Err(e) => Err(Into::<PyErr>::into(ApiError::from(e)))?,
+        }
+    }
+    fn save_as(&self, path: &str) -> PyResult<()> {
+        match self.db.save_as(path) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(Into::<PyErr>::into(ApiError::from(e)))?,
+        }
+    }
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+    /// Sessions hold no external resources, so leaving a `with` block only propagates exceptions
+    fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> bool {
+        false
+    }
     fn add_designation(&mut self, name: &str, spec: &str) -> PyResult<()> {
         match self.db.insert_spec_text(name, spec) {
             Ok(()) => Ok(()),
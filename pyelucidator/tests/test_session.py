@@ -0,0 +1,43 @@
+import struct
+
+import pytest
+
+from pyelucidator import BoundingBox, Point, Session
+
+
+def unit_box():
+    return BoundingBox(Point(0.0, 0.0, 0.0, 0.0), Point(1.0, 1.0, 1.0, 1.0))
+
+
+def populated_session():
+    session = Session()
+    session.add_designation("Counter", "hits: u64, misses: u64")
+    session.insert_metadata("Counter", unit_box(), struct.pack("<QQ", 31, 9))
+    return session
+
+
+def test_context_manager_returns_session():
+    with populated_session() as session:
+        records = session.get_metadata("Counter", unit_box(), None)
+    assert records == [{"hits": 31, "misses": 9}]
+
+
+def test_save_and_open(tmp_path):
+    path = str(tmp_path / "session.db")
+    with populated_session() as session:
+        session.save_as(path)
+
+    with Session.open(path) as reloaded:
+        records = reloaded.get_metadata("Counter", unit_box(), None)
+    assert records == [{"hits": 31, "misses": 9}]
+
+
+def test_open_missing_file_fails(tmp_path):
+    with pytest.raises(ValueError):
+        Session.open(str(tmp_path / "missing" / "session.db"))
+
+
+def test_exceptions_propagate_from_with():
+    with pytest.raises(KeyError):
+        with Session():
+            raise KeyError("boom")